@@ -1,3 +1,4 @@
+#include <algorithm>
 #include <cassert>
 #include <tuple>
 #include <vector>
@@ -8,6 +9,7 @@
 #include "include/codec/SkCodec.h"
 // core/
 #include "include/core/SkAnnotation.h"
+#include "include/core/SkBBHFactory.h"
 #include "include/core/SkBlendMode.h"
 #include "include/core/SkCanvas.h"
 #include "include/core/SkColor.h"
@@ -33,6 +35,7 @@
 #include "include/core/SkM44.h"
 #include "include/core/SkMatrix44.h"
 #include "include/core/SkMaskFilter.h"
+#include "include/core/SkOverdrawCanvas.h"
 #include "include/core/SkPaint.h"
 #include "include/core/SkPath.h"
 #include "include/core/SkPathBuilder.h"
@@ -48,6 +51,7 @@
 #include "include/core/SkRegion.h"
 #include "include/core/SkRRect.h"
 #include "include/core/SkRSXform.h"
+#include "include/core/SkSerialProcs.h"
 #include "include/core/SkStream.h"
 #include "include/core/SkStrokeRec.h"
 #include "include/core/SkSurface.h"
@@ -89,11 +93,17 @@
 #include "include/utils/SkCamera.h"
 #include "include/utils/SkCustomTypeface.h"
 #include "include/utils/SkInterpolator.h"
+#include "include/utils/SkNWayCanvas.h"
+#include "include/utils/SkNoDrawCanvas.h"
 #include "include/utils/SkNullCanvas.h"
+#include "include/utils/SkPaintFilterCanvas.h"
 #include "include/utils/SkParsePath.h"
 #include "include/utils/SkShadowUtils.h"
 #include "include/utils/SkTextUtils.h"
 
+// m91: SkCanvas::onDrawShadowRec() receives a SkDrawShadowRec.
+#include "src/core/SkDrawShadowInfo.h"
+
 //
 // codec/SkCodec.h
 //
@@ -701,6 +711,284 @@ extern "C" void C_SkCanvas_discard(SkCanvas* self) {
     self->discard();
 }
 
+extern "C" void C_SkCanvas_drawAtlas(SkCanvas* self, const SkImage* atlas, const SkRSXform xform[], const SkRect tex[],
+                                     const SkColor colors[], int count, SkBlendMode mode,
+                                     const SkSamplingOptions* sampling, const SkRect* cullRect, const SkPaint* paint) {
+    self->drawAtlas(atlas, xform, tex, colors, count, mode, *sampling, cullRect, paint);
+}
+
+extern "C" void C_SkCanvas_experimental_DrawEdgeAAQuad(SkCanvas* self, const SkRect* rect, const SkPoint* clip,
+                                                       SkCanvas::QuadAAFlags aaFlags, const SkColor4f* color,
+                                                       SkBlendMode mode) {
+    self->experimental_DrawEdgeAAQuad(*rect, clip, aaFlags, *color, mode);
+}
+
+struct C_ImageSetEntry {
+    const SkImage* image;
+    SkRect srcRect;
+    SkRect dstRect;
+    int matrixIndex;
+    float alpha;
+    unsigned aaFlags;
+    bool hasClip;
+};
+
+extern "C" void C_SkCanvas_experimental_DrawEdgeAAImageSet(SkCanvas* self, const C_ImageSetEntry* set, int count,
+                                                           const SkPoint* dstClips, const SkMatrix* preViewMatrices,
+                                                           const SkSamplingOptions* sampling, const SkPaint* paint,
+                                                           SkCanvas::SrcRectConstraint constraint) {
+    std::vector<SkCanvas::ImageSetEntry> entries;
+    entries.reserve(count);
+    for (int i = 0; i < count; ++i) {
+        auto& e = set[i];
+        entries.emplace_back(sk_ref_sp(e.image), e.srcRect, e.dstRect, e.matrixIndex, e.alpha, e.aaFlags, e.hasClip);
+    }
+    self->experimental_DrawEdgeAAImageSet(entries.data(), count, dstClips, preViewMatrices, *sampling, paint, constraint);
+}
+
+//
+// CanvasBackend, a SkCanvas that forwards its drawing commands to Rust.
+//
+
+namespace CanvasBackend {
+    extern "C" typedef void (*Save)(void*);
+    extern "C" typedef void (*SaveLayer)(void*, const SkRect* bounds, const SkPaint* paint, const SkImageFilter* backdrop, SkCanvas::SaveLayerFlags flags);
+    extern "C" typedef void (*Restore)(void*);
+    extern "C" typedef void (*MatrixChanged)(void*, const SkM44* localToDevice);
+    extern "C" typedef void (*ClipRect)(void*, const SkRect*, SkClipOp, bool antiAlias);
+    extern "C" typedef void (*ClipRRect)(void*, const SkRRect*, SkClipOp, bool antiAlias);
+    extern "C" typedef void (*ClipPath)(void*, const SkPath*, SkClipOp, bool antiAlias);
+    extern "C" typedef void (*ClipRegion)(void*, const SkRegion*, SkClipOp);
+    extern "C" typedef void (*DrawPaint)(void*, const SkPaint*);
+    extern "C" typedef void (*DrawRect)(void*, const SkRect*, const SkPaint*);
+    extern "C" typedef void (*DrawRRect)(void*, const SkRRect*, const SkPaint*);
+    extern "C" typedef void (*DrawDRRect)(void*, const SkRRect* outer, const SkRRect* inner, const SkPaint*);
+    extern "C" typedef void (*DrawOval)(void*, const SkRect*, const SkPaint*);
+    extern "C" typedef void (*DrawArc)(void*, const SkRect*, SkScalar startAngle, SkScalar sweepAngle, bool useCenter, const SkPaint*);
+    extern "C" typedef void (*DrawPath)(void*, const SkPath*, const SkPaint*);
+    extern "C" typedef void (*DrawRegion)(void*, const SkRegion*, const SkPaint*);
+    extern "C" typedef void (*DrawPoints)(void*, SkCanvas::PointMode, size_t count, const SkPoint* points, const SkPaint*);
+    extern "C" typedef void (*DrawTextBlob)(void*, const SkTextBlob*, SkScalar x, SkScalar y, const SkPaint*);
+    extern "C" typedef void (*DrawImage)(void*, const SkImage*, SkScalar left, SkScalar top, const SkSamplingOptions*, const SkPaint*);
+    extern "C" typedef void (*DrawImageRect)(void*, const SkImage*, const SkRect* src, const SkRect* dst, const SkSamplingOptions*, const SkPaint*, SkCanvas::SrcRectConstraint);
+    extern "C" typedef void (*DrawVertices)(void*, const SkVertices*, SkBlendMode, const SkPaint*);
+    extern "C" typedef void (*DrawAtlas)(void*, const SkImage*, const SkRSXform* xforms, const SkRect* tex, const SkColor* colors, int count, SkBlendMode, const SkSamplingOptions*, const SkRect* cull, const SkPaint*);
+    extern "C" typedef void (*DrawPatch)(void*, const SkPoint* cubics, const SkColor* colors, const SkPoint* texCoords, SkBlendMode, const SkPaint*);
+    extern "C" typedef void (*DrawImageLattice)(void*, const SkImage*, const SkCanvas::Lattice*, const SkRect* dst, SkFilterMode, const SkPaint*);
+    extern "C" typedef void (*DrawEdgeAAQuad)(void*, const SkRect*, const SkPoint* clip, unsigned aaFlags, const SkColor4f*, SkBlendMode);
+    extern "C" typedef void (*DrawEdgeAAImageSet)(void*, const C_ImageSetEntry* set, int count, const SkPoint* dstClips, int dstClipCount, const SkMatrix* preViewMatrices, int matrixCount, const SkSamplingOptions*, const SkPaint*, SkCanvas::SrcRectConstraint);
+    extern "C" typedef void (*DrawShadow)(void*, const SkPath*, const SkPoint3* zPlaneParams, const SkPoint3* lightPos, SkScalar lightRadius, SkColor ambientColor, SkColor spotColor, uint32_t flags);
+    extern "C" typedef void (*DrawAnnotation)(void*, const SkRect*, const char* key, SkData* value);
+}
+
+struct RustCanvasBackendParam {
+    void* data;
+    ::CanvasBackend::Save save;
+    ::CanvasBackend::SaveLayer saveLayer;
+    ::CanvasBackend::Restore restore;
+    ::CanvasBackend::MatrixChanged matrixChanged;
+    ::CanvasBackend::ClipRect clipRect;
+    ::CanvasBackend::ClipRRect clipRRect;
+    ::CanvasBackend::ClipPath clipPath;
+    ::CanvasBackend::ClipRegion clipRegion;
+    ::CanvasBackend::DrawPaint drawPaint;
+    ::CanvasBackend::DrawRect drawRect;
+    ::CanvasBackend::DrawRRect drawRRect;
+    ::CanvasBackend::DrawDRRect drawDRRect;
+    ::CanvasBackend::DrawOval drawOval;
+    ::CanvasBackend::DrawArc drawArc;
+    ::CanvasBackend::DrawPath drawPath;
+    ::CanvasBackend::DrawRegion drawRegion;
+    ::CanvasBackend::DrawPoints drawPoints;
+    ::CanvasBackend::DrawTextBlob drawTextBlob;
+    ::CanvasBackend::DrawImage drawImage;
+    ::CanvasBackend::DrawImageRect drawImageRect;
+    ::CanvasBackend::DrawVertices drawVertices;
+    ::CanvasBackend::DrawAtlas drawAtlas;
+    ::CanvasBackend::DrawPatch drawPatch;
+    ::CanvasBackend::DrawImageLattice drawImageLattice;
+    ::CanvasBackend::DrawEdgeAAQuad drawEdgeAAQuad;
+    ::CanvasBackend::DrawEdgeAAImageSet drawEdgeAAImageSet;
+    ::CanvasBackend::DrawShadow drawShadow;
+    ::CanvasBackend::DrawAnnotation drawAnnotation;
+};
+
+class RustCanvas: public SkNoDrawCanvas {
+public:
+    RustCanvas(int width, int height, const RustCanvasBackendParam& param)
+    :SkNoDrawCanvas(width, height), _param(param) {
+    }
+
+protected:
+    void willSave() override {
+        _param.save(_param.data);
+    }
+
+    SaveLayerStrategy getSaveLayerStrategy(const SaveLayerRec& rec) override {
+        _param.saveLayer(_param.data, rec.fBounds, rec.fPaint, rec.fBackdrop, rec.fSaveLayerFlags);
+        return kNoLayer_SaveLayerStrategy;
+    }
+
+    void willRestore() override {
+        _param.restore(_param.data);
+    }
+
+    void didRestore() override {
+        matrixChanged();
+    }
+
+    void didConcat44(const SkM44&) override {
+        matrixChanged();
+    }
+
+    void didSetM44(const SkM44&) override {
+        matrixChanged();
+    }
+
+    void didTranslate(SkScalar, SkScalar) override {
+        matrixChanged();
+    }
+
+    void didScale(SkScalar, SkScalar) override {
+        matrixChanged();
+    }
+
+    void onClipRect(const SkRect& rect, SkClipOp op, ClipEdgeStyle edgeStyle) override {
+        _param.clipRect(_param.data, &rect, op, edgeStyle == kSoft_ClipEdgeStyle);
+        SkNoDrawCanvas::onClipRect(rect, op, edgeStyle);
+    }
+
+    void onClipRRect(const SkRRect& rrect, SkClipOp op, ClipEdgeStyle edgeStyle) override {
+        _param.clipRRect(_param.data, &rrect, op, edgeStyle == kSoft_ClipEdgeStyle);
+        SkNoDrawCanvas::onClipRRect(rrect, op, edgeStyle);
+    }
+
+    void onClipPath(const SkPath& path, SkClipOp op, ClipEdgeStyle edgeStyle) override {
+        _param.clipPath(_param.data, &path, op, edgeStyle == kSoft_ClipEdgeStyle);
+        SkNoDrawCanvas::onClipPath(path, op, edgeStyle);
+    }
+
+    void onClipRegion(const SkRegion& region, SkClipOp op) override {
+        _param.clipRegion(_param.data, &region, op);
+        SkNoDrawCanvas::onClipRegion(region, op);
+    }
+
+    void onDrawPaint(const SkPaint& paint) override {
+        _param.drawPaint(_param.data, &paint);
+    }
+
+    void onDrawRect(const SkRect& rect, const SkPaint& paint) override {
+        _param.drawRect(_param.data, &rect, &paint);
+    }
+
+    void onDrawRRect(const SkRRect& rrect, const SkPaint& paint) override {
+        _param.drawRRect(_param.data, &rrect, &paint);
+    }
+
+    void onDrawDRRect(const SkRRect& outer, const SkRRect& inner, const SkPaint& paint) override {
+        _param.drawDRRect(_param.data, &outer, &inner, &paint);
+    }
+
+    void onDrawOval(const SkRect& oval, const SkPaint& paint) override {
+        _param.drawOval(_param.data, &oval, &paint);
+    }
+
+    void onDrawArc(const SkRect& oval, SkScalar startAngle, SkScalar sweepAngle, bool useCenter, const SkPaint& paint) override {
+        _param.drawArc(_param.data, &oval, startAngle, sweepAngle, useCenter, &paint);
+    }
+
+    void onDrawPath(const SkPath& path, const SkPaint& paint) override {
+        _param.drawPath(_param.data, &path, &paint);
+    }
+
+    void onDrawRegion(const SkRegion& region, const SkPaint& paint) override {
+        _param.drawRegion(_param.data, &region, &paint);
+    }
+
+    void onDrawPoints(PointMode mode, size_t count, const SkPoint pts[], const SkPaint& paint) override {
+        _param.drawPoints(_param.data, mode, count, pts, &paint);
+    }
+
+    void onDrawTextBlob(const SkTextBlob* blob, SkScalar x, SkScalar y, const SkPaint& paint) override {
+        _param.drawTextBlob(_param.data, blob, x, y, &paint);
+    }
+
+    void onDrawImage2(const SkImage* image, SkScalar left, SkScalar top, const SkSamplingOptions& sampling,
+                      const SkPaint* paint) override {
+        _param.drawImage(_param.data, image, left, top, &sampling, paint);
+    }
+
+    void onDrawImageRect2(const SkImage* image, const SkRect& src, const SkRect& dst, const SkSamplingOptions& sampling,
+                          const SkPaint* paint, SrcRectConstraint constraint) override {
+        _param.drawImageRect(_param.data, image, &src, &dst, &sampling, paint, constraint);
+    }
+
+    void onDrawVerticesObject(const SkVertices* vertices, SkBlendMode mode, const SkPaint& paint) override {
+        _param.drawVertices(_param.data, vertices, mode, &paint);
+    }
+
+    void onDrawAtlas2(const SkImage* atlas, const SkRSXform xforms[], const SkRect tex[], const SkColor colors[],
+                      int count, SkBlendMode mode, const SkSamplingOptions& sampling, const SkRect* cull,
+                      const SkPaint* paint) override {
+        _param.drawAtlas(_param.data, atlas, xforms, tex, colors, count, mode, &sampling, cull, paint);
+    }
+
+    void onDrawPatch(const SkPoint cubics[12], const SkColor colors[4], const SkPoint texCoords[4], SkBlendMode mode,
+                     const SkPaint& paint) override {
+        _param.drawPatch(_param.data, cubics, colors, texCoords, mode, &paint);
+    }
+
+    void onDrawImageLattice2(const SkImage* image, const Lattice& lattice, const SkRect& dst, SkFilterMode filter,
+                             const SkPaint* paint) override {
+        _param.drawImageLattice(_param.data, image, &lattice, &dst, filter, paint);
+    }
+
+    void onDrawEdgeAAQuad(const SkRect& rect, const SkPoint clip[4], QuadAAFlags aaFlags, const SkColor4f& color,
+                          SkBlendMode mode) override {
+        _param.drawEdgeAAQuad(_param.data, &rect, clip, aaFlags, &color, mode);
+    }
+
+    void onDrawEdgeAAImageSet2(const ImageSetEntry imageSet[], int count, const SkPoint dstClips[],
+                               const SkMatrix preViewMatrices[], const SkSamplingOptions& sampling,
+                               const SkPaint* paint, SrcRectConstraint constraint) override {
+        std::vector<C_ImageSetEntry> entries;
+        entries.reserve(count);
+        int dstClipCount = 0;
+        int matrixCount = 0;
+        for (int i = 0; i < count; ++i) {
+            auto& e = imageSet[i];
+            entries.push_back({e.fImage.get(), e.fSrcRect, e.fDstRect, e.fMatrixIndex, e.fAlpha, e.fAAFlags, e.fHasClip});
+            dstClipCount += e.fHasClip ? 4 : 0;
+            matrixCount = std::max(matrixCount, e.fMatrixIndex + 1);
+        }
+        _param.drawEdgeAAImageSet(
+            _param.data, entries.data(), count, dstClips, dstClipCount, preViewMatrices, matrixCount, &sampling,
+            paint, constraint);
+    }
+
+    void onDrawShadowRec(const SkPath& path, const SkDrawShadowRec& rec) override {
+        _param.drawShadow(
+            _param.data, &path, &rec.fZPlaneParams, &rec.fLightPos, rec.fLightRadius, rec.fAmbientColor,
+            rec.fSpotColor, rec.fFlags);
+    }
+
+    void onDrawAnnotation(const SkRect& rect, const char key[], SkData* value) override {
+        _param.drawAnnotation(_param.data, &rect, key, value);
+    }
+
+private:
+    void matrixChanged() {
+        auto localToDevice = getLocalToDevice();
+        _param.matrixChanged(_param.data, &localToDevice);
+    }
+
+    RustCanvasBackendParam _param;
+};
+
+extern "C" SkCanvas* C_RustCanvas_new(int width, int height, const RustCanvasBackendParam* param) {
+    return new RustCanvas(width, height, *param);
+}
+
 //
 // core/SkAutoCanvasRestore.h
 //
@@ -993,6 +1281,153 @@ extern "C" SkImage* C_SkBitmap_asImage(const SkBitmap* self) {
     return self->asImage().release();
 }
 
+//
+// core/SkSerialProcs.h
+//
+
+namespace SerialProcs {
+    extern "C" typedef SkData* (*SerializePicture)(void*, SkPicture*);
+    extern "C" typedef SkData* (*SerializeImage)(void*, SkImage*);
+    extern "C" typedef SkData* (*SerializeTypeface)(void*, SkTypeface*);
+    extern "C" typedef SkPicture* (*DeserializePicture)(void*, const void* data, size_t length);
+    extern "C" typedef SkImage* (*DeserializeImage)(void*, const void* data, size_t length);
+    extern "C" typedef SkTypeface* (*DeserializeTypeface)(void*, const void* data, size_t length);
+}
+
+// The procs are optional, Skia uses its default serialization for the ones that are null.
+struct RustSerialProcs {
+    void* data;
+    ::SerialProcs::SerializePicture picture;
+    ::SerialProcs::SerializeImage image;
+    ::SerialProcs::SerializeTypeface typeface;
+};
+
+struct RustDeserialProcs {
+    void* data;
+    ::SerialProcs::DeserializePicture picture;
+    ::SerialProcs::DeserializeImage image;
+    ::SerialProcs::DeserializeTypeface typeface;
+};
+
+static SkSerialProcs makeSerialProcs(const RustSerialProcs* procs) {
+    SkSerialProcs r;
+    void* ctx = const_cast<RustSerialProcs*>(procs);
+    if (procs->picture) {
+        r.fPictureProc = [](SkPicture* picture, void* ctx) {
+            auto procs = static_cast<const RustSerialProcs*>(ctx);
+            return sk_sp<SkData>(procs->picture(procs->data, picture));
+        };
+        r.fPictureCtx = ctx;
+    }
+    if (procs->image) {
+        r.fImageProc = [](SkImage* image, void* ctx) {
+            auto procs = static_cast<const RustSerialProcs*>(ctx);
+            return sk_sp<SkData>(procs->image(procs->data, image));
+        };
+        r.fImageCtx = ctx;
+    }
+    if (procs->typeface) {
+        r.fTypefaceProc = [](SkTypeface* typeface, void* ctx) {
+            auto procs = static_cast<const RustSerialProcs*>(ctx);
+            return sk_sp<SkData>(procs->typeface(procs->data, typeface));
+        };
+        r.fTypefaceCtx = ctx;
+    }
+    return r;
+}
+
+static SkDeserialProcs makeDeserialProcs(const RustDeserialProcs* procs) {
+    SkDeserialProcs r;
+    void* ctx = const_cast<RustDeserialProcs*>(procs);
+    if (procs->picture) {
+        r.fPictureProc = [](const void* data, size_t length, void* ctx) {
+            auto procs = static_cast<const RustDeserialProcs*>(ctx);
+            return sk_sp<SkPicture>(procs->picture(procs->data, data, length));
+        };
+        r.fPictureCtx = ctx;
+    }
+    if (procs->image) {
+        r.fImageProc = [](const void* data, size_t length, void* ctx) {
+            auto procs = static_cast<const RustDeserialProcs*>(ctx);
+            return sk_sp<SkImage>(procs->image(procs->data, data, length));
+        };
+        r.fImageCtx = ctx;
+    }
+    if (procs->typeface) {
+        r.fTypefaceProc = [](const void* data, size_t length, void* ctx) {
+            auto procs = static_cast<const RustDeserialProcs*>(ctx);
+            return sk_sp<SkTypeface>(procs->typeface(procs->data, data, length));
+        };
+        r.fTypefaceCtx = ctx;
+    }
+    return r;
+}
+
+// Pictures write the data of their typefaces directly into their stream and pass the stream instead of the
+// data to the typeface deserialization proc (last checked: m91). Typefaces that are referenced by text blobs
+// and nested pictures are written inline, and their data is passed as is. To give the Rust procs exactly the
+// data they returned in both cases, it is framed with a tag and its size. Frames are always longer than a
+// stream pointer, because empty data is left to Skia's default serialization.
+static const uint32_t kRustTypefaceTag = SkSetFourByteTag('r', 's', 'T', 'F');
+static const size_t kRustTypefaceFrameHeaderSize = 2 * sizeof(uint32_t);
+
+static SkSerialProcs makePictureSerialProcs(const RustSerialProcs* procs) {
+    auto r = makeSerialProcs(procs);
+    if (procs->typeface) {
+        r.fTypefaceProc = [](SkTypeface* typeface, void* ctx) -> sk_sp<SkData> {
+            auto procs = static_cast<const RustSerialProcs*>(ctx);
+            auto data = sk_sp<SkData>(procs->typeface(procs->data, typeface));
+            if (!data || data->isEmpty()) {
+                return nullptr;
+            }
+            SkDynamicMemoryWStream stream;
+            stream.write32(kRustTypefaceTag);
+            stream.write32(SkToU32(data->size()));
+            stream.write(data->data(), data->size());
+            return stream.detachAsData();
+        };
+    }
+    return r;
+}
+
+static SkDeserialProcs makePictureDeserialProcs(const RustDeserialProcs* procs) {
+    auto r = makeDeserialProcs(procs);
+    if (procs->typeface) {
+        r.fTypefaceProc = [](const void* data, size_t length, void* ctx) -> sk_sp<SkTypeface> {
+            auto procs = static_cast<const RustDeserialProcs*>(ctx);
+
+            if (length == sizeof(SkStream*)) {
+                auto stream = *static_cast<SkStream* const*>(data);
+                uint32_t tag = 0;
+                if (stream->peek(&tag, sizeof(tag)) != sizeof(tag) || tag != kRustTypefaceTag) {
+                    return SkTypeface::MakeDeserialize(stream);
+                }
+                uint32_t size;
+                if (stream->skip(sizeof(tag)) != sizeof(tag) || !stream->readU32(&size)) {
+                    return nullptr;
+                }
+                auto bytes = SkData::MakeFromStream(stream, size);
+                if (!bytes) {
+                    return nullptr;
+                }
+                return sk_sp<SkTypeface>(procs->typeface(procs->data, bytes->data(), bytes->size()));
+            }
+
+            uint32_t header[2] = {0, 0};
+            if (length >= kRustTypefaceFrameHeaderSize) {
+                memcpy(header, data, kRustTypefaceFrameHeaderSize);
+            }
+            if (header[0] != kRustTypefaceTag || header[1] != length - kRustTypefaceFrameHeaderSize) {
+                SkMemoryStream stream(data, length);
+                return SkTypeface::MakeDeserialize(&stream);
+            }
+            auto bytes = static_cast<const char*>(data) + kRustTypefaceFrameHeaderSize;
+            return sk_sp<SkTypeface>(procs->typeface(procs->data, bytes, header[1]));
+        };
+    }
+    return r;
+}
+
 //
 // core/SkPicture.h
 //
@@ -1009,6 +1444,21 @@ extern "C" SkData* C_SkPicture_serialize(const SkPicture* self) {
     return self->serialize().release();
 }
 
+extern "C" SkPicture* C_SkPicture_MakeFromDataWithProcs(const SkData* data, const RustDeserialProcs* procs) {
+    auto deserialProcs = makePictureDeserialProcs(procs);
+    return SkPicture::MakeFromData(data, &deserialProcs).release();
+}
+
+extern "C" SkPicture* C_SkPicture_MakeFromData2WithProcs(const void* data, size_t size, const RustDeserialProcs* procs) {
+    auto deserialProcs = makePictureDeserialProcs(procs);
+    return SkPicture::MakeFromData(data, size, &deserialProcs).release();
+}
+
+extern "C" SkData* C_SkPicture_serializeWithProcs(const SkPicture* self, const RustSerialProcs* procs) {
+    auto serialProcs = makePictureSerialProcs(procs);
+    return self->serialize(&serialProcs).release();
+}
+
 extern "C" SkPicture* C_SkPicture_MakePlaceholder(const SkRect& cull) {
     return SkPicture::MakePlaceholder(cull).release();
 }
@@ -1017,6 +1467,32 @@ extern "C" void C_SkPicture_playback(const SkPicture* self, SkCanvas* canvas) {
     self->playback(canvas);
 }
 
+namespace PictureAbort {
+    extern "C" typedef bool (*Abort)(void*);
+}
+
+struct RustPictureAbortParam {
+    void* data;
+    ::PictureAbort::Abort abort;
+};
+
+class RustAbortCallback: public SkPicture::AbortCallback {
+public:
+    RustAbortCallback(const RustPictureAbortParam& param): _param(param) {}
+
+    bool abort() override {
+        return _param.abort(_param.data);
+    }
+
+private:
+    RustPictureAbortParam _param;
+};
+
+extern "C" void C_SkPicture_playbackWithAbort(const SkPicture* self, SkCanvas* canvas, const RustPictureAbortParam* param) {
+    RustAbortCallback callback(*param);
+    self->playback(canvas, &callback);
+}
+
 extern "C" SkRect C_SkPicture_cullRect(const SkPicture* self) {
     return self->cullRect();
 }
@@ -1034,6 +1510,79 @@ extern "C" void C_SkPicture_approximateBytesUsed(const SkPicture* self, size_t*
     *out = self->approximateBytesUsed();
 }
 
+namespace PictureOps {
+    extern "C" typedef void (*Op)(void*, size_t index);
+}
+
+// Collects the bounds a picture recorder computes for its ops (m91: the bounding box hierarchy of a
+// picture is not accessible through the public API).
+class OpBounds : public SkBBoxHierarchy {
+public:
+    using SkBBoxHierarchy::insert;
+
+    void insert(const SkRect boxes[], int N) override {
+        fBounds.assign(boxes, boxes + N);
+    }
+
+    void search(const SkRect& query, std::vector<int>* results) const override {
+        for (int index = 0; index < count(); ++index) {
+            if (SkRect::Intersects(fBounds[index], query)) {
+                results->push_back(index);
+            }
+        }
+    }
+
+    size_t bytesUsed() const override {
+        return sizeof(*this) + fBounds.capacity() * sizeof(SkRect);
+    }
+
+    int count() const {
+        return static_cast<int>(fBounds.size());
+    }
+
+    const SkRect& bounds(int index) const {
+        return fBounds[index];
+    }
+
+private:
+    std::vector<SkRect> fBounds;
+};
+
+class OpBoundsFactory : public SkBBHFactory {
+public:
+    sk_sp<SkBBoxHierarchy> operator()() const override {
+        return fBounds;
+    }
+
+    sk_sp<OpBounds> fBounds = sk_make_sp<OpBounds>();
+};
+
+extern "C" void C_SkPicture_opsInRect(const SkPicture* self, const SkRect* rect, void* data, ::PictureOps::Op op) {
+    // Playback wraps the ops in a save() / restore() pair, so a faithful recording of them has two more ops.
+    OpBoundsFactory factory;
+    SkPictureRecorder recorder;
+    self->playback(recorder.beginRecording(self->cullRect(), &factory));
+    recorder.finishRecordingAsPicture();
+    auto& bounds = *factory.fBounds;
+    auto count = self->approximateOpCount();
+    if (bounds.count() == count + 2) {
+        for (int index = 0; index < count; ++index) {
+            if (SkRect::Intersects(bounds.bounds(index + 1), *rect)) {
+                op(data, index);
+            }
+        }
+        return;
+    }
+
+    // The recording differs (for example if nested pictures got unrolled), so report all ops if the cull
+    // rect intersects.
+    if (SkRect::Intersects(self->cullRect(), *rect)) {
+        for (int index = 0; index < count; ++index) {
+            op(data, index);
+        }
+    }
+}
+
 extern "C" SkShader *C_SkPicture_makeShader(
     const SkPicture *self, 
     SkTileMode tmx, SkTileMode tmy, 
@@ -1267,6 +1816,18 @@ extern "C" SkData* C_SkFlattenable_serialize(const SkFlattenable* self) {
     return self->serialize().release();
 }
 
+// Flattenables write their typefaces inline, so their procs don't need the framing of the picture procs.
+extern "C" SkData* C_SkFlattenable_serializeWithProcs(const SkFlattenable* self, const RustSerialProcs* procs) {
+    auto serialProcs = makeSerialProcs(procs);
+    return self->serialize(&serialProcs).release();
+}
+
+extern "C" SkFlattenable* C_SkFlattenable_DeserializeWithProcs(
+    SkFlattenable::Type type, const void* data, size_t length, const RustDeserialProcs* procs) {
+    auto deserialProcs = makeDeserialProcs(procs);
+    return SkFlattenable::Deserialize(type, data, length, &deserialProcs).release();
+}
+
 //
 // core/SkFont.h
 //
@@ -1327,8 +1888,11 @@ extern "C" void C_SkFontArguments_setCollectionIndex(SkFontArguments* self, int
     self->setCollectionIndex(collectionIndex);
 }
 
-extern "C" void C_SkFontArguments_setVariationDesignPosition(SkFontArguments* self, SkFontArguments::VariationPosition position) {
-    self->setVariationDesignPosition(position);
+// wasm: does not pass VariationPosition by value.
+extern "C" void C_SkFontArguments_setVariationDesignPositionCoordinates(
+        SkFontArguments* self,
+        const SkFontArguments::VariationPosition::Coordinate* coordinates, int coordinateCount) {
+    self->setVariationDesignPosition({coordinates, coordinateCount});
 }
 
 extern "C" SkFontArguments::VariationPosition
@@ -1415,6 +1979,18 @@ extern "C" SkVertices* C_SkVertices_Builder_detach(SkVertices::Builder* builder)
     return builder->detach().release();
 }
 
+//
+// core/SkBBHFactory.h
+//
+
+extern "C" void C_SkRTreeFactory_Construct(SkRTreeFactory* uninitialized) {
+    new(uninitialized) SkRTreeFactory();
+}
+
+extern "C" void C_SkRTreeFactory_destruct(SkRTreeFactory* self) {
+    self->~SkRTreeFactory();
+}
+
 //
 // SkPictureRecorder
 //
@@ -2719,3 +3295,171 @@ extern "C" void C_SkInterpolator_setMirror(SkInterpolator* self, bool mirror) {
 extern "C" SkCanvas* C_SkMakeNullCanvas() {
     return SkMakeNullCanvas().release();
 }
+
+extern "C" SkCanvas* C_SkNWayCanvas_new(int width, int height) {
+    return new SkNWayCanvas(width, height);
+}
+
+extern "C" void C_SkNWayCanvas_addCanvas(SkCanvas* self, SkCanvas* canvas) {
+    static_cast<SkNWayCanvas*>(self)->addCanvas(canvas);
+}
+
+extern "C" void C_SkNWayCanvas_removeCanvas(SkCanvas* self, SkCanvas* canvas) {
+    static_cast<SkNWayCanvas*>(self)->removeCanvas(canvas);
+}
+
+extern "C" void C_SkNWayCanvas_removeAll(SkCanvas* self) {
+    static_cast<SkNWayCanvas*>(self)->removeAll();
+}
+
+extern "C" SkCanvas* C_SkOverdrawCanvas_new(SkCanvas* canvas) {
+    return new SkOverdrawCanvas(canvas);
+}
+
+enum class PaintFilterDrawType {
+    Paint,
+    Behind,
+    Points,
+    Rect,
+    RRect,
+    DRRect,
+    Region,
+    Oval,
+    Arc,
+    Path,
+    Image,
+    ImageRect,
+    ImageLattice,
+    Atlas,
+    ImageSet,
+    TextBlob,
+    Patch,
+    Vertices,
+    Picture,
+};
+
+namespace PaintFilter {
+    extern "C" typedef bool (*Filter)(void*, SkPaint*, PaintFilterDrawType);
+}
+
+struct RustPaintFilterParam {
+    void* data;
+    ::PaintFilter::Filter filter;
+};
+
+// Tracks the type of the current draw call, because SkPaintFilterCanvas::onFilter() does not
+// receive it.
+class RustPaintFilterCanvas: public SkPaintFilterCanvas {
+public:
+    RustPaintFilterCanvas(SkCanvas* canvas, const RustPaintFilterParam& param)
+    :SkPaintFilterCanvas(canvas), _param(param), _drawType(PaintFilterDrawType::Paint) {
+    }
+
+protected:
+    bool onFilter(SkPaint& paint) const override {
+        return _param.filter(_param.data, &paint, _drawType);
+    }
+
+    void onDrawPaint(const SkPaint& paint) override {
+        _drawType = PaintFilterDrawType::Paint;
+        SkPaintFilterCanvas::onDrawPaint(paint);
+    }
+
+    void onDrawBehind(const SkPaint& paint) override {
+        _drawType = PaintFilterDrawType::Behind;
+        SkPaintFilterCanvas::onDrawBehind(paint);
+    }
+
+    void onDrawPoints(PointMode mode, size_t count, const SkPoint pts[], const SkPaint& paint) override {
+        _drawType = PaintFilterDrawType::Points;
+        SkPaintFilterCanvas::onDrawPoints(mode, count, pts, paint);
+    }
+
+    void onDrawRect(const SkRect& rect, const SkPaint& paint) override {
+        _drawType = PaintFilterDrawType::Rect;
+        SkPaintFilterCanvas::onDrawRect(rect, paint);
+    }
+
+    void onDrawRRect(const SkRRect& rrect, const SkPaint& paint) override {
+        _drawType = PaintFilterDrawType::RRect;
+        SkPaintFilterCanvas::onDrawRRect(rrect, paint);
+    }
+
+    void onDrawDRRect(const SkRRect& outer, const SkRRect& inner, const SkPaint& paint) override {
+        _drawType = PaintFilterDrawType::DRRect;
+        SkPaintFilterCanvas::onDrawDRRect(outer, inner, paint);
+    }
+
+    void onDrawRegion(const SkRegion& region, const SkPaint& paint) override {
+        _drawType = PaintFilterDrawType::Region;
+        SkPaintFilterCanvas::onDrawRegion(region, paint);
+    }
+
+    void onDrawOval(const SkRect& rect, const SkPaint& paint) override {
+        _drawType = PaintFilterDrawType::Oval;
+        SkPaintFilterCanvas::onDrawOval(rect, paint);
+    }
+
+    void onDrawArc(const SkRect& rect, SkScalar startAngle, SkScalar sweepAngle, bool useCenter, const SkPaint& paint) override {
+        _drawType = PaintFilterDrawType::Arc;
+        SkPaintFilterCanvas::onDrawArc(rect, startAngle, sweepAngle, useCenter, paint);
+    }
+
+    void onDrawPath(const SkPath& path, const SkPaint& paint) override {
+        _drawType = PaintFilterDrawType::Path;
+        SkPaintFilterCanvas::onDrawPath(path, paint);
+    }
+
+    void onDrawImage2(const SkImage* image, SkScalar left, SkScalar top, const SkSamplingOptions& sampling, const SkPaint* paint) override {
+        _drawType = PaintFilterDrawType::Image;
+        SkPaintFilterCanvas::onDrawImage2(image, left, top, sampling, paint);
+    }
+
+    void onDrawImageRect2(const SkImage* image, const SkRect& src, const SkRect& dst, const SkSamplingOptions& sampling, const SkPaint* paint, SrcRectConstraint constraint) override {
+        _drawType = PaintFilterDrawType::ImageRect;
+        SkPaintFilterCanvas::onDrawImageRect2(image, src, dst, sampling, paint, constraint);
+    }
+
+    void onDrawImageLattice2(const SkImage* image, const Lattice& lattice, const SkRect& dst, SkFilterMode filter, const SkPaint* paint) override {
+        _drawType = PaintFilterDrawType::ImageLattice;
+        SkPaintFilterCanvas::onDrawImageLattice2(image, lattice, dst, filter, paint);
+    }
+
+    void onDrawAtlas2(const SkImage* atlas, const SkRSXform xform[], const SkRect tex[], const SkColor colors[], int count, SkBlendMode mode, const SkSamplingOptions& sampling, const SkRect* cull, const SkPaint* paint) override {
+        _drawType = PaintFilterDrawType::Atlas;
+        SkPaintFilterCanvas::onDrawAtlas2(atlas, xform, tex, colors, count, mode, sampling, cull, paint);
+    }
+
+    void onDrawEdgeAAImageSet2(const ImageSetEntry set[], int count, const SkPoint dstClips[], const SkMatrix preViewMatrices[], const SkSamplingOptions& sampling, const SkPaint* paint, SrcRectConstraint constraint) override {
+        _drawType = PaintFilterDrawType::ImageSet;
+        SkPaintFilterCanvas::onDrawEdgeAAImageSet2(set, count, dstClips, preViewMatrices, sampling, paint, constraint);
+    }
+
+    void onDrawTextBlob(const SkTextBlob* blob, SkScalar x, SkScalar y, const SkPaint& paint) override {
+        _drawType = PaintFilterDrawType::TextBlob;
+        SkPaintFilterCanvas::onDrawTextBlob(blob, x, y, paint);
+    }
+
+    void onDrawPatch(const SkPoint cubics[12], const SkColor colors[4], const SkPoint texCoords[4], SkBlendMode mode, const SkPaint& paint) override {
+        _drawType = PaintFilterDrawType::Patch;
+        SkPaintFilterCanvas::onDrawPatch(cubics, colors, texCoords, mode, paint);
+    }
+
+    void onDrawVerticesObject(const SkVertices* vertices, SkBlendMode mode, const SkPaint& paint) override {
+        _drawType = PaintFilterDrawType::Vertices;
+        SkPaintFilterCanvas::onDrawVerticesObject(vertices, mode, paint);
+    }
+
+    void onDrawPicture(const SkPicture* picture, const SkMatrix* matrix, const SkPaint* paint) override {
+        _drawType = PaintFilterDrawType::Picture;
+        SkPaintFilterCanvas::onDrawPicture(picture, matrix, paint);
+    }
+
+private:
+    RustPaintFilterParam _param;
+    PaintFilterDrawType _drawType;
+};
+
+extern "C" SkCanvas* C_RustPaintFilterCanvas_new(SkCanvas* canvas, const RustPaintFilterParam* param) {
+    return new RustPaintFilterCanvas(canvas, *param);
+}
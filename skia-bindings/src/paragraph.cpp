@@ -2,6 +2,8 @@
 
 #include "bindings.h"
 
+#include <algorithm>
+
 #include "modules/skparagraph/include/DartTypes.h"
 #include "modules/skparagraph/include/FontCollection.h"
 #include "modules/skparagraph/include/Metrics.h"
@@ -12,10 +14,14 @@
 #include "modules/skparagraph/include/TextShadow.h"
 #include "modules/skparagraph/include/TextStyle.h"
 #include "modules/skparagraph/include/TypefaceFontProvider.h"
+#include "include/utils/SkNoDrawCanvas.h"
 
 // m84: needs definition of SkFontData
 #include "src/core/SkFontDescriptor.h"
 
+// m91: the ICU break iterators used by ParagraphImpl are only available from src/.
+#include "src/utils/SkUnicode.h"
+
 using namespace skia::textlayout;
 
 //
@@ -246,8 +252,122 @@ extern "C" {
 }
 
 
+// m91: ParagraphBuilder can not be given additional line break opportunities, so the ones a Rust LineBreaker
+// finds are inserted into the text Skia lays out as soft hyphens and zero width spaces. The recorded text does
+// not contain them, and the offsets passed to and returned from the paragraph are mapped.
+struct InsertedBreak {
+    // The UTF-8 offset in the recorded text the break was inserted at.
+    size_t offset;
+    // A soft hyphen, or a zero width space.
+    bool hyphenate;
+};
+
+static const char kSoftHyphen[] = "\xC2\xAD";
+static const char kZeroWidthSpace[] = "\xE2\x80\x8B";
+
+// m91: Paragraph does not expose the text, styles and placeholders it was built from, so the builder records
+// them and passes a copy to every paragraph it builds.
+struct ParagraphContent {
+    ParagraphStyle paragraphStyle;
+    sk_sp<FontCollection> fontCollection;
+    SkString text;
+    std::vector<Block> styles;
+    std::vector<Placeholder> placeholders;
+    std::vector<size_t> placeholderIds;
+    std::vector<InsertedBreak> insertedBreaks;
+};
+
+extern "C" typedef void (*SoftHyphensDrop)(void*);
+
+struct RustParagraph {
+    Paragraph* paragraph;
+    ParagraphContent* content;
+    // The Rust soft hyphens found by the last layout.
+    void* softHyphens = nullptr;
+    SoftHyphensDrop dropSoftHyphens = nullptr;
+
+    void setSoftHyphens(void* newSoftHyphens, SoftHyphensDrop newDropSoftHyphens) {
+        if (softHyphens) {
+            dropSoftHyphens(softHyphens);
+        }
+        softHyphens = newSoftHyphens;
+        dropSoftHyphens = newDropSoftHyphens;
+    }
+};
+
+static size_t breakLength(const InsertedBreak& insertedBreak, bool utf16) {
+    if (utf16) {
+        return 1;
+    }
+    return insertedBreak.hyphenate ? sizeof(kSoftHyphen) - 1 : sizeof(kZeroWidthSpace) - 1;
+}
+
+static size_t utf16Length(const char* utf8, size_t len) {
+    size_t units = 0;
+    for (size_t i = 0; i < len; ++i) {
+        auto c = static_cast<uint8_t>(utf8[i]);
+        if ((c & 0xC0) != 0x80) {
+            units += c >= 0xF0 ? 2 : 1;
+        }
+    }
+    return units;
+}
+
+// Maps UTF-8 or UTF-16 offsets between the recorded text and the text Skia lays out, which contains the
+// inserted breaks.
+class BreakOffsets {
+public:
+    BreakOffsets(const ParagraphContent* content, bool utf16) {
+        size_t last = 0;
+        size_t lastUtf16 = 0;
+        for (auto& insertedBreak : content->insertedBreaks) {
+            auto offset = insertedBreak.offset;
+            if (utf16) {
+                lastUtf16 += utf16Length(content->text.c_str() + last, offset - last);
+                last = offset;
+                offset = lastUtf16;
+            }
+            _offsets.push_back(offset);
+            _lengths.push_back(breakLength(insertedBreak, utf16));
+        }
+    }
+
+    // An offset at an inserted break maps to the offset behind the break.
+    size_t toNative(size_t offset) const {
+        size_t shift = 0;
+        for (size_t i = 0; i < _offsets.size() && _offsets[i] <= offset; ++i) {
+            shift += _lengths[i];
+        }
+        return offset + shift;
+    }
+
+    // An offset inside or behind an inserted break maps to the offset of the break.
+    size_t toSource(size_t nativeOffset) const {
+        size_t shift = 0;
+        for (size_t i = 0; i < _offsets.size(); ++i) {
+            auto start = _offsets[i] + shift;
+            if (start >= nativeOffset) {
+                break;
+            }
+            shift += std::min(_lengths[i], nativeOffset - start);
+        }
+        return nativeOffset - shift;
+    }
+
+private:
+    std::vector<size_t> _offsets;
+    std::vector<size_t> _lengths;
+};
+
+static size_t nativeTextSize(const ParagraphContent* content) {
+    return BreakOffsets(content, false).toNative(content->text.size());
+}
+
 extern "C" {
-    void C_Paragraph_delete(Paragraph* self) {
+    void C_Paragraph_delete(RustParagraph* self) {
+        self->setSoftHyphens(nullptr, nullptr);
+        delete self->paragraph;
+        delete self->content;
         delete self;
     }
     
@@ -259,9 +379,11 @@ extern "C" {
         self->paint(canvas, x, y);
     }
 
-    void C_Paragraph_getRectsForRange(Paragraph *self, unsigned start, unsigned end, RectHeightStyle rectHeightStyle,
+    void C_Paragraph_getRectsForRange(RustParagraph *self, unsigned start, unsigned end, RectHeightStyle rectHeightStyle,
                                             RectWidthStyle rectWidthStyle, TextBoxes* uninitialized) {
-        auto v = self->getRectsForRange(start, end, rectHeightStyle, rectWidthStyle);
+        BreakOffsets offsets(self->content, true);
+        auto v = self->paragraph->getRectsForRange(
+            offsets.toNative(start), offsets.toNative(end), rectHeightStyle, rectWidthStyle);
         new(uninitialized) TextBoxes{std::move(v)};
     }
 
@@ -270,19 +392,36 @@ extern "C" {
         new(uninitialized) TextBoxes{std::move(v)};
     }
 
-    void C_Paragraph_getGlyphPositionAtCoordinate(Paragraph* self, SkScalar x, SkScalar y, PositionWithAffinity* position) {
-        *position = self->getGlyphPositionAtCoordinate(x, y);
+    void C_Paragraph_getGlyphPositionAtCoordinate(RustParagraph* self, SkScalar x, SkScalar y, PositionWithAffinity* position) {
+        *position = self->paragraph->getGlyphPositionAtCoordinate(x, y);
+        position->position = BreakOffsets(self->content, true).toSource(position->position);
     }
 
-    void C_Paragraph_getWordBoundary(Paragraph* self, unsigned offset, size_t range[2]) {
-        auto sk_range = self->getWordBoundary(offset);
-        range[0] = sk_range.start;
-        range[1] = sk_range.end;
+    void C_Paragraph_getWordBoundary(RustParagraph* self, unsigned offset, size_t range[2]) {
+        BreakOffsets offsets(self->content, true);
+        auto sk_range = self->paragraph->getWordBoundary(offsets.toNative(offset));
+        range[0] = offsets.toSource(sk_range.start);
+        range[1] = offsets.toSource(sk_range.end);
     }
 
-    void C_Paragraph_getLineMetrics(Paragraph* self, LineMetricsVector* uninitialized) {
+    void C_Paragraph_getLineMetrics(RustParagraph* self, LineMetricsVector* uninitialized) {
         auto v = new(uninitialized) LineMetricsVector();
-        self->getLineMetrics(v->lineMetrics);
+        self->paragraph->getLineMetrics(v->lineMetrics);
+        if (self->content->insertedBreaks.empty()) {
+            return;
+        }
+        BreakOffsets offsets(self->content, false);
+        for (auto& metrics : v->lineMetrics) {
+            metrics.fStartIndex = offsets.toSource(metrics.fStartIndex);
+            metrics.fEndIndex = offsets.toSource(metrics.fEndIndex);
+            metrics.fEndExcludingWhitespaces = offsets.toSource(metrics.fEndExcludingWhitespaces);
+            metrics.fEndIncludingNewline = offsets.toSource(metrics.fEndIncludingNewline);
+            std::map<size_t, StyleMetrics> styleMetrics;
+            for (auto& entry : metrics.fLineMetrics) {
+                styleMetrics.emplace(offsets.toSource(entry.first), entry.second);
+            }
+            metrics.fLineMetrics = std::move(styleMetrics);
+        }
     }
     
     size_t C_Paragraph_lineNumber(Paragraph* self) {
@@ -292,47 +431,459 @@ extern "C" {
     void C_Paragraph_markDirty(Paragraph* self) {
         self->markDirty();
     }
+
+    void C_Paragraph_updateTextAlign(Paragraph* self, TextAlign textAlign) {
+        self->updateTextAlign(textAlign);
+    }
+
+    const Block* C_Paragraph_styles(const RustParagraph* self, size_t* count) {
+        auto& styles = self->content->styles;
+        *count = styles.size();
+        return styles.data();
+    }
+
+    const Placeholder* C_Paragraph_placeholders(const RustParagraph* self, size_t* count) {
+        auto& placeholders = self->content->placeholders;
+        *count = placeholders.size();
+        return placeholders.data();
+    }
+
+    const size_t* C_Paragraph_placeholderIds(const RustParagraph* self, size_t* count) {
+        auto& ids = self->content->placeholderIds;
+        *count = ids.size();
+        return ids.data();
+    }
+
+    const InsertedBreak* C_Paragraph_insertedBreaks(const RustParagraph* self, size_t* count) {
+        auto& breaks = self->content->insertedBreaks;
+        *count = breaks.size();
+        return breaks.data();
+    }
+
+    const char* C_Paragraph_text(const RustParagraph* self, size_t* len) {
+        auto& text = self->content->text;
+        *len = text.size();
+        return text.c_str();
+    }
+
+    void C_Paragraph_setSoftHyphens(RustParagraph* self, void* softHyphens, SoftHyphensDrop drop) {
+        self->setSoftHyphens(softHyphens, drop);
+    }
+
+    const void* C_Paragraph_softHyphens(const RustParagraph* self) {
+        return self->softHyphens;
+    }
+
+    typedef void (*ParagraphVisitor)(void* context, int lineNumber, const Paragraph::VisitorInfo* info);
+
+    void C_Paragraph_visit(RustParagraph* self, void* context, ParagraphVisitor visitor) {
+        if (self->content->insertedBreaks.empty()) {
+            self->paragraph->visit([&](int lineNumber, const Paragraph::VisitorInfo* info) {
+                visitor(context, lineNumber, info);
+            });
+            return;
+        }
+        BreakOffsets offsets(self->content, false);
+        std::vector<uint32_t> utf8Starts;
+        self->paragraph->visit([&](int lineNumber, const Paragraph::VisitorInfo* info) {
+            if (!info) {
+                visitor(context, lineNumber, info);
+                return;
+            }
+            utf8Starts.clear();
+            for (int i = 0; i <= info->count; ++i) {
+                utf8Starts.push_back(SkToU32(offsets.toSource(info->utf8Starts[i])));
+            }
+            auto mapped = *info;
+            mapped.utf8Starts = utf8Starts.data();
+            visitor(context, lineNumber, &mapped);
+        });
+    }
+}
+
+//
+// SkUnicode.h
+//
+
+extern "C" {
+    typedef void (*BoundaryVisitor)(void* context, size_t utf8Offset);
+
+    // Visits the UTF-8 offsets of the grapheme cluster boundaries (UBRK_CHARACTER), including 0 and the end of
+    // the text. Returns false if there is no ICU break iterator available.
+    bool C_SkUnicode_visitGraphemeBoundaries(const char* utf8, size_t utf8Units, void* context, BoundaryVisitor visitor) {
+        auto unicode = SkUnicode::Make();
+        if (!unicode) {
+            return false;
+        }
+        auto iterator = unicode->makeBreakIterator("", SkUnicode::BreakType::kGraphemes);
+        if (!iterator || !iterator->setText(utf8, SkToInt(utf8Units))) {
+            return false;
+        }
+        for (auto position = iterator->first(); !iterator->isDone(); position = iterator->next()) {
+            visitor(context, position);
+        }
+        return true;
+    }
+}
+
+//
+// ParagraphPainter
+//
+
+namespace ParagraphPainter {
+    extern "C" typedef void (*DrawTextBlob)(void*, const SkTextBlob*, SkScalar, SkScalar, const SkPaint*);
+    extern "C" typedef void (*DrawRect)(void*, const SkRect*, const SkPaint*);
+    extern "C" typedef void (*DrawPoints)(void*, SkCanvas::PointMode, size_t count, const SkPoint* points, const SkPaint*);
+    extern "C" typedef void (*DrawPath)(void*, const SkPath*, const SkPaint*);
+    extern "C" typedef void (*ClipRect)(void*, const SkRect*);
+    extern "C" typedef void (*Translate)(void*, SkScalar, SkScalar);
+    extern "C" typedef void (*Save)(void*);
+    extern "C" typedef void (*Restore)(void*);
+}
+
+struct RustParagraphPainterParam {
+    void* data;
+    ::ParagraphPainter::DrawTextBlob drawTextBlob;
+    ::ParagraphPainter::DrawTextBlob drawTextShadow;
+    ::ParagraphPainter::DrawRect drawRect;
+    ::ParagraphPainter::DrawPoints drawPoints;
+    ::ParagraphPainter::DrawPath drawPath;
+    ::ParagraphPainter::ClipRect clipRect;
+    ::ParagraphPainter::Translate translate;
+    ::ParagraphPainter::Save save;
+    ::ParagraphPainter::Restore restore;
+};
+
+/// A canvas that forwards the drawing commands skparagraph uses to a Rust ParagraphPainter.
+class RustParagraphPainterCanvas: public SkNoDrawCanvas {
+public:
+    RustParagraphPainterCanvas(const RustParagraphPainterParam& param, const Block* styles, size_t styleCount)
+    :SkNoDrawCanvas(SK_MaxS32, SK_MaxS32), _param(param), _styles(styles), _styleCount(styleCount) {
+    }
+
+protected:
+    void willSave() override {
+        _param.save(_param.data);
+    }
+
+    void willRestore() override {
+        _param.restore(_param.data);
+    }
+
+    void didTranslate(SkScalar dx, SkScalar dy) override {
+        _param.translate(_param.data, dx, dy);
+    }
+
+    void onClipRect(const SkRect& rect, SkClipOp op, ClipEdgeStyle edgeStyle) override {
+        _param.clipRect(_param.data, &rect);
+        SkNoDrawCanvas::onClipRect(rect, op, edgeStyle);
+    }
+
+    void onDrawTextBlob(const SkTextBlob* blob, SkScalar x, SkScalar y, const SkPaint& paint) override {
+        if (isShadow(paint)) {
+            _param.drawTextShadow(_param.data, blob, x, y, &paint);
+        } else {
+            _param.drawTextBlob(_param.data, blob, x, y, &paint);
+        }
+    }
+
+    void onDrawRect(const SkRect& rect, const SkPaint& paint) override {
+        _param.drawRect(_param.data, &rect, &paint);
+    }
+
+    void onDrawPoints(PointMode mode, size_t count, const SkPoint pts[], const SkPaint& paint) override {
+        _param.drawPoints(_param.data, mode, count, pts, &paint);
+    }
+
+    void onDrawPath(const SkPath& path, const SkPaint& paint) override {
+        _param.drawPath(_param.data, &path, &paint);
+    }
+
+private:
+    // skparagraph draws a shadow with a paint that has the color of the shadow and a blur mask filter if the
+    // shadow is blurred. Paints that may also be the text paint of one of the styles are considered text.
+    bool isShadow(const SkPaint& paint) const {
+        bool shadowPaint = false;
+        for (size_t i = 0; i < _styleCount; ++i) {
+            const auto& style = _styles[i].fStyle;
+            bool textPaint = style.hasForeground()
+                ? paint == style.getForeground()
+                : paint.getColor() == style.getColor() && !paint.getMaskFilter();
+            if (textPaint) {
+                return false;
+            }
+            for (const auto& shadow : style.getShadows()) {
+                shadowPaint |= shadow.hasShadow()
+                    && paint.getColor() == shadow.fColor
+                    && (paint.getMaskFilter() != nullptr) == (shadow.fBlurRadius != 0.0);
+            }
+        }
+        return shadowPaint;
+    }
+
+    RustParagraphPainterParam _param;
+    const Block* _styles;
+    size_t _styleCount;
+};
+
+extern "C" {
+    void C_Paragraph_paintWithPainter(RustParagraph* self, const RustParagraphPainterParam* param, SkScalar x, SkScalar y) {
+        auto& styles = self->content->styles;
+        RustParagraphPainterCanvas canvas(*param, styles.data(), styles.size());
+        self->paragraph->paint(&canvas, x, y);
+    }
 }
 
 //
 // ParagraphBuilder.h
 //
 
+extern "C" typedef void (*LineBreakerDrop)(void*);
+
+struct RustParagraphBuilder {
+    ParagraphBuilder* builder;
+    ParagraphContent* content;
+    // A boxed Rust LineBreaker that is applied to the text added.
+    void* lineBreaker = nullptr;
+    LineBreakerDrop dropLineBreaker = nullptr;
+
+    void setLineBreaker(void* newLineBreaker, LineBreakerDrop newDropLineBreaker) {
+        if (lineBreaker) {
+            dropLineBreaker(lineBreaker);
+        }
+        lineBreaker = newLineBreaker;
+        dropLineBreaker = newDropLineBreaker;
+    }
+
+    // Records the text and its style the way ParagraphBuilderImpl splits it into blocks.
+    void addText(const char* text, size_t len, const TextStyle& style) {
+        auto start = content->text.size();
+        content->text.append(text, len);
+        auto end = content->text.size();
+        if (start == end) {
+            return;
+        }
+        auto& styles = content->styles;
+        auto merge = !styles.empty()
+            && styles.back().fRange.end == start
+            && !style.isPlaceholder()
+            && !styles.back().fStyle.isPlaceholder()
+            && styles.back().fStyle.equals(style);
+        if (merge) {
+            styles.back().fRange.end = end;
+        } else {
+            styles.emplace_back(start, end, style);
+        }
+    }
+};
+
 extern "C" {
-    void C_ParagraphBuilder_delete(ParagraphBuilder* self) {
+    void C_ParagraphBuilder_delete(RustParagraphBuilder* self) {
+        self->setLineBreaker(nullptr, nullptr);
+        delete self->builder;
+        delete self->content;
         delete self;
     }
 
-    void C_ParagraphBuilder_pushStyle(ParagraphBuilder* self, const TextStyle* style) {
-        self->pushStyle(*style);
+    void C_ParagraphBuilder_pushStyle(RustParagraphBuilder* self, const TextStyle* style) {
+        self->builder->pushStyle(*style);
+    }
+
+    void C_ParagraphBuilder_pop(RustParagraphBuilder* self) {
+        self->builder->pop();
     }
 
-    void C_ParagraphBuilder_pop(ParagraphBuilder* self) {
-        self->pop();
+    void C_ParagraphBuilder_peekStyle(RustParagraphBuilder* self, TextStyle* style) {
+        *style = self->builder->peekStyle();
     }
 
-    void C_ParagraphBuilder_peekStyle(ParagraphBuilder* self, TextStyle* style) {
-        *style = self->peekStyle();
+    void C_ParagraphBuilder_addText(RustParagraphBuilder* self, const char* text, size_t len) {
+        self->builder->addText(text, len);
+        self->addText(text, len, self->builder->peekStyle());
     }
 
-    void C_ParagraphBuilder_addText(ParagraphBuilder* self, const char* text, size_t len) {
-        self->addText(text, len);
+    // Adds a break to the text Skia lays out, but not to the recorded text.
+    void C_ParagraphBuilder_addBreak(RustParagraphBuilder* self, bool hyphenate) {
+        self->content->insertedBreaks.push_back(InsertedBreak{self->content->text.size(), hyphenate});
+        self->builder->addText(hyphenate ? kSoftHyphen : kZeroWidthSpace);
     }
 
-    void C_ParagraphBuilder_addPlaceholder(ParagraphBuilder* self, const PlaceholderStyle* placeholderStyle) {
-        self->addPlaceholder(*placeholderStyle);
+    void C_ParagraphBuilder_setLineBreaker(RustParagraphBuilder* self, void* lineBreaker, LineBreakerDrop drop) {
+        self->setLineBreaker(lineBreaker, drop);
     }
 
-    void C_ParagraphBuilder_setParagraphStyle(ParagraphBuilder* self, const ParagraphStyle* style) {
-        self->setParagraphStyle(*style);
+    void* C_ParagraphBuilder_lineBreaker(const RustParagraphBuilder* self) {
+        return self->lineBreaker;
     }
 
-    Paragraph* C_ParagraphBuilder_Build(ParagraphBuilder* self) {
-        return self->Build().release();
+    void C_ParagraphBuilder_addPlaceholder(RustParagraphBuilder* self, const PlaceholderStyle* placeholderStyle, size_t id) {
+        auto content = self->content;
+        auto& placeholders = content->placeholders;
+        BlockRange blocksBefore(
+            placeholders.empty() ? 0 : placeholders.back().fBlocksBefore.end + 1, content->styles.size());
+        TextRange textBefore(placeholders.empty() ? 0 : placeholders.back().fRange.end, content->text.size());
+        auto topStyle = self->builder->peekStyle();
+        auto start = content->text.size();
+        self->builder->addPlaceholder(*placeholderStyle);
+        // The object replacement character U+FFFC.
+        self->addText("\xEF\xBF\xBC", 3, TextStyle(topStyle, true));
+        placeholders.emplace_back(
+            start, content->text.size(), *placeholderStyle, topStyle, blocksBefore, textBefore);
+        content->placeholderIds.push_back(id);
     }
 
-    ParagraphBuilder* C_ParagraphBuilder_make(const ParagraphStyle* style, const FontCollection* fontCollection) {
-        return ParagraphBuilder::make(*style, spFromConst(fontCollection)).release();
+    size_t C_ParagraphBuilder_placeholderCount(const RustParagraphBuilder* self) {
+        return self->content->placeholders.size();
+    }
+
+    const char* C_ParagraphBuilder_text(const RustParagraphBuilder* self, size_t* len) {
+        auto& text = self->content->text;
+        *len = text.size();
+        return text.c_str();
+    }
+
+    void C_ParagraphBuilder_setParagraphStyle(RustParagraphBuilder* self, const ParagraphStyle* style) {
+        self->builder->setParagraphStyle(*style);
+        self->content->paragraphStyle = *style;
+    }
+
+    RustParagraph* C_ParagraphBuilder_Build(RustParagraphBuilder* self) {
+        return new RustParagraph{self->builder->Build().release(), new ParagraphContent(*self->content)};
+    }
+
+    RustParagraphBuilder* C_ParagraphBuilder_make(const ParagraphStyle* style, const FontCollection* fontCollection) {
+        auto content = new ParagraphContent();
+        content->paragraphStyle = *style;
+        content->fontCollection = spFromConst(fontCollection);
+        return new RustParagraphBuilder{
+            ParagraphBuilder::make(*style, content->fontCollection).release(),
+            content};
+    }
+}
+
+// m91: Paragraph can only update the styles of the whole text. Updates of a part of the text are applied to the
+// recorded styles, and the paragraph is built again from its recorded content.
+
+// Splits the recorded styles at from and to, and applies update to the styles in between.
+template <typename Update>
+static void updateStyles(ParagraphContent* content, size_t from, size_t to, Update update) {
+    std::vector<Block> styles;
+    for (auto& block : content->styles) {
+        auto start = block.fRange.start;
+        auto end = block.fRange.end;
+        if (block.fStyle.isPlaceholder() || to <= start || end <= from) {
+            styles.push_back(block);
+            if (start < to && from < end) {
+                update(styles.back().fStyle);
+            }
+            continue;
+        }
+        if (start < from) {
+            styles.emplace_back(start, from, block.fStyle);
+        }
+        styles.emplace_back(std::max(start, from), std::min(end, to), block.fStyle);
+        update(styles.back().fStyle);
+        if (to < end) {
+            styles.emplace_back(to, end, block.fStyle);
+        }
+    }
+    content->styles = std::move(styles);
+}
+
+// Replaces the paragraph by one that is built from the recorded content.
+static void rebuild(RustParagraph* self) {
+    auto old = self->content;
+    RustParagraphBuilder builder{
+        ParagraphBuilder::make(old->paragraphStyle, old->fontCollection).release(),
+        new ParagraphContent()};
+    builder.content->paragraphStyle = old->paragraphStyle;
+    builder.content->fontCollection = old->fontCollection;
+    size_t placeholder = 0;
+    size_t insertedBreak = 0;
+    auto& insertedBreaks = old->insertedBreaks;
+    for (auto& block : old->styles) {
+        auto start = block.fRange.start;
+        auto end = block.fRange.end;
+        builder.builder->pushStyle(block.fStyle.isPlaceholder()
+            ? old->placeholders[placeholder].fTextStyle
+            : block.fStyle);
+        // The breaks inserted before the block and inside of it.
+        auto breakEnd = block.fStyle.isPlaceholder() ? start + 1 : end;
+        for (; insertedBreak < insertedBreaks.size() && insertedBreaks[insertedBreak].offset < breakEnd; ++insertedBreak) {
+            auto& b = insertedBreaks[insertedBreak];
+            auto offset = std::max(start, b.offset);
+            if (start < offset) {
+                C_ParagraphBuilder_addText(&builder, old->text.c_str() + start, offset - start);
+                start = offset;
+            }
+            C_ParagraphBuilder_addBreak(&builder, b.hyphenate);
+        }
+        if (block.fStyle.isPlaceholder()) {
+            auto& p = old->placeholders[placeholder];
+            C_ParagraphBuilder_addPlaceholder(&builder, &p.fStyle, old->placeholderIds[placeholder]);
+            ++placeholder;
+        } else if (start < end) {
+            C_ParagraphBuilder_addText(&builder, old->text.c_str() + start, end - start);
+        }
+        builder.builder->pop();
+    }
+
+    self->setSoftHyphens(nullptr, nullptr);
+    delete self->paragraph;
+    delete self->content;
+    self->paragraph = builder.builder->Build().release();
+    self->content = builder.content;
+    delete builder.builder;
+}
+
+template <typename Update>
+static void updateRange(RustParagraph* self, size_t from, size_t to, Update update) {
+    updateStyles(self->content, from, to, update);
+    rebuild(self);
+}
+
+// Applies update to the recorded styles and the default text style, like ParagraphImpl does.
+template <typename Update>
+static void updateWholeText(ParagraphContent* content, Update update) {
+    updateStyles(content, 0, content->text.size(), update);
+    auto defaultStyle = content->paragraphStyle.getTextStyle();
+    update(defaultStyle);
+    content->paragraphStyle.setTextStyle(defaultStyle);
+}
+
+static bool isWholeText(const RustParagraph* self, size_t from, size_t to) {
+    return from == 0 && to >= self->content->text.size();
+}
+
+extern "C" {
+    void C_Paragraph_updateFontSize(RustParagraph* self, size_t from, size_t to, SkScalar fontSize) {
+        auto update = [=](TextStyle& style) { style.setFontSize(fontSize); };
+        if (!isWholeText(self, from, to)) {
+            updateRange(self, from, to, update);
+            return;
+        }
+        self->paragraph->updateFontSize(0, nativeTextSize(self->content), fontSize);
+        updateWholeText(self->content, update);
+    }
+
+    void C_Paragraph_updateForegroundPaint(RustParagraph* self, size_t from, size_t to, const SkPaint* paint) {
+        auto update = [=](TextStyle& style) { style.setForegroundColor(*paint); };
+        if (!isWholeText(self, from, to)) {
+            updateRange(self, from, to, update);
+            return;
+        }
+        self->paragraph->updateForegroundPaint(0, nativeTextSize(self->content), *paint);
+        updateWholeText(self->content, update);
+    }
+
+    void C_Paragraph_updateBackgroundPaint(RustParagraph* self, size_t from, size_t to, const SkPaint* paint) {
+        auto update = [=](TextStyle& style) { style.setBackgroundColor(*paint); };
+        if (!isWholeText(self, from, to)) {
+            updateRange(self, from, to, update);
+            return;
+        }
+        self->paragraph->updateBackgroundPaint(0, nativeTextSize(self->content), *paint);
+        updateWholeText(self->content, update);
     }
 }
 
@@ -4,6 +4,10 @@
 
 #include "modules/skshaper/include/SkShaper.h"
 #include "include/core/SkFontMgr.h"
+#include "include/core/SkStream.h"
+#include "include/core/SkTypeface.h"
+#include "src/pdf/SkPDFGlyphUse.h"
+#include "src/pdf/SkPDFSubsetFont.h"
 
 extern "C" SkShaper* C_SkShaper_MakePrimitive() {
     return SkShaper::MakePrimitive().release();
@@ -178,6 +182,118 @@ extern "C" void C_RustRunHandler_construct(RustRunHandler* uninitialized, const
     new(uninitialized)RustRunHandler(*param);
 }
 
+namespace RunIterator {
+    extern "C" typedef void (*Consume)(void*);
+    extern "C" typedef size_t (*EndOfCurrentRun)(void*);
+    extern "C" typedef bool (*AtEnd)(void*);
+    extern "C" typedef const SkFont* (*CurrentFont)(void*);
+    extern "C" typedef uint8_t (*CurrentLevel)(void*);
+    extern "C" typedef SkFourByteTag (*CurrentScript)(void*);
+    extern "C" typedef const char* (*CurrentLanguage)(void*);
+}
+
+struct RustRunIteratorParam {
+    void* data;
+    ::RunIterator::Consume consume;
+    ::RunIterator::EndOfCurrentRun endOfCurrentRun;
+    ::RunIterator::AtEnd atEnd;
+};
+
+template<typename Iterator>
+class RustRunIterator: public Iterator {
+public:
+    explicit RustRunIterator(const RustRunIteratorParam& param)
+    :_param(param) {
+    }
+
+    void consume() override {
+        _param.consume(_param.data);
+    }
+
+    size_t endOfCurrentRun() const override {
+        return _param.endOfCurrentRun(_param.data);
+    }
+
+    bool atEnd() const override {
+        return _param.atEnd(_param.data);
+    }
+
+protected:
+    RustRunIteratorParam _param;
+};
+
+class RustFontRunIterator: public RustRunIterator<SkShaper::FontRunIterator> {
+public:
+    RustFontRunIterator(const RustRunIteratorParam& param, ::RunIterator::CurrentFont currentFont)
+    :RustRunIterator(param), _currentFont(currentFont) {
+    }
+
+    const SkFont& currentFont() const override {
+        return *_currentFont(_param.data);
+    }
+
+private:
+    ::RunIterator::CurrentFont _currentFont;
+};
+
+class RustBiDiRunIterator: public RustRunIterator<SkShaper::BiDiRunIterator> {
+public:
+    RustBiDiRunIterator(const RustRunIteratorParam& param, ::RunIterator::CurrentLevel currentLevel)
+    :RustRunIterator(param), _currentLevel(currentLevel) {
+    }
+
+    uint8_t currentLevel() const override {
+        return _currentLevel(_param.data);
+    }
+
+private:
+    ::RunIterator::CurrentLevel _currentLevel;
+};
+
+class RustScriptRunIterator: public RustRunIterator<SkShaper::ScriptRunIterator> {
+public:
+    RustScriptRunIterator(const RustRunIteratorParam& param, ::RunIterator::CurrentScript currentScript)
+    :RustRunIterator(param), _currentScript(currentScript) {
+    }
+
+    SkFourByteTag currentScript() const override {
+        return _currentScript(_param.data);
+    }
+
+private:
+    ::RunIterator::CurrentScript _currentScript;
+};
+
+class RustLanguageRunIterator: public RustRunIterator<SkShaper::LanguageRunIterator> {
+public:
+    RustLanguageRunIterator(const RustRunIteratorParam& param, ::RunIterator::CurrentLanguage currentLanguage)
+    :RustRunIterator(param), _currentLanguage(currentLanguage) {
+    }
+
+    const char* currentLanguage() const override {
+        return _currentLanguage(_param.data);
+    }
+
+private:
+    ::RunIterator::CurrentLanguage _currentLanguage;
+};
+
+extern "C" SkShaper::FontRunIterator* C_RustFontRunIterator_new(const RustRunIteratorParam* param, ::RunIterator::CurrentFont currentFont) {
+    return new RustFontRunIterator(*param, currentFont);
+}
+
+extern "C" SkShaper::BiDiRunIterator* C_RustBiDiRunIterator_new(const RustRunIteratorParam* param, ::RunIterator::CurrentLevel currentLevel) {
+    return new RustBiDiRunIterator(*param, currentLevel);
+}
+
+extern "C" SkShaper::ScriptRunIterator* C_RustScriptRunIterator_new(const RustRunIteratorParam* param, ::RunIterator::CurrentScript currentScript) {
+    return new RustScriptRunIterator(*param, currentScript);
+}
+
+extern "C" SkShaper::LanguageRunIterator* C_RustLanguageRunIterator_new(const RustRunIteratorParam* param, ::RunIterator::CurrentLanguage currentLanguage) {
+    return new RustLanguageRunIterator(*param, currentLanguage);
+}
+
 extern "C" void
 C_SkShaper_shape(const SkShaper *self, const char *utf8, size_t utf8Bytes, const SkFont *srcFont, bool leftToRight,
                  SkScalar width, SkShaper::RunHandler *runHandler) {
@@ -218,3 +334,28 @@ extern "C" SkTextBlob* C_SkTextBlobBuilderRunHandler_makeBlob(SkTextBlobBuilderR
 extern "C" SkPoint C_SkTextBlobBuilderRunHandler_endPoint(SkTextBlobBuilderRunHandler* self) {
     return self->endPoint();
 }
+
+//
+// Font subsetting, uses HarfBuzz, which is available with the textlayout feature only.
+//
+
+extern "C" SkData* C_SkTypeface_subset(const SkTypeface* self, const SkGlyphID* glyphs, size_t glyphsCount) {
+    int ttcIndex = 0;
+    auto stream = self->openStream(&ttcIndex);
+    if (!stream) {
+        return nullptr;
+    }
+    auto data = SkData::MakeFromStream(stream.get(), stream->getLength());
+    int glyphCount = self->countGlyphs();
+    if (!data || glyphCount < 1) {
+        return nullptr;
+    }
+    SkPDFGlyphUse glyphUse(1, SkToU16(glyphCount - 1));
+    for (size_t i = 0; i != glyphsCount; ++i) {
+        if (glyphs[i] < glyphCount) {
+            glyphUse.set(glyphs[i]);
+        }
+    }
+    return SkPDFSubsetFont(std::move(data), glyphUse, SkPDF::Metadata::kHarfbuzz_Subsetter, nullptr, ttcIndex)
+        .release();
+}
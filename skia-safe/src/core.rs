@@ -16,6 +16,11 @@ pub use blur_types::*;
 pub mod canvas;
 pub use canvas::{AutoCanvasRestore, Canvas, OwnedCanvas};
 
+mod canvas_backend;
+#[cfg(test)]
+pub(crate) use canvas_backend::RectCounter;
+pub use canvas_backend::*;
+
 mod clip_op;
 pub use clip_op::*;
 
@@ -190,6 +195,9 @@ pub use sampling_options::{
 mod scalar_;
 pub use scalar_::*;
 
+mod serial_procs;
+pub use serial_procs::*;
+
 pub mod shader;
 pub use shader::{shaders, Shader};
 
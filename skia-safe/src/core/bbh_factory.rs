@@ -1,5 +1,9 @@
 use crate::prelude::*;
-use skia_bindings::{SkBBHFactory, SkBBoxHierarchy};
+use skia_bindings::{self as sb, SkBBHFactory, SkBBoxHierarchy, SkRTreeFactory};
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
 
 // TODO: complete the wrapper
 pub type BBoxHierarchy = RCHandle<SkBBoxHierarchy>;
@@ -15,3 +19,47 @@ impl NativeDrop for SkBBHFactory {
 
 // TODO: complete the wrapper functions
 impl BBHFactory {}
+
+/// A [`BBHFactory`] that creates R-trees.
+///
+/// Pictures that are recorded with an R-tree only play back the drawing commands that intersect
+/// the clip of the canvas they are played back into, see [`crate::Picture::playback_in_rect()`].
+pub type RTreeFactory = Handle<SkRTreeFactory>;
+
+impl NativeDrop for SkRTreeFactory {
+    fn drop(&mut self) {
+        unsafe { sb::C_SkRTreeFactory_destruct(self) }
+    }
+}
+
+impl Default for RTreeFactory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for RTreeFactory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RTreeFactory").finish()
+    }
+}
+
+impl Deref for RTreeFactory {
+    type Target = BBHFactory;
+
+    fn deref(&self) -> &Self::Target {
+        BBHFactory::from_native_ref(&self.native()._base)
+    }
+}
+
+impl DerefMut for RTreeFactory {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        BBHFactory::from_native_ref_mut(&mut self.native_mut()._base)
+    }
+}
+
+impl RTreeFactory {
+    pub fn new() -> Self {
+        Self::construct(|f| unsafe { sb::C_SkRTreeFactory_Construct(f) })
+    }
+}
@@ -2,9 +2,10 @@
 use crate::gpu;
 use crate::{
     prelude::*, scalar, u8cpu, Bitmap, BlendMode, ClipOp, Color, Color4f, Data, Drawable,
-    FilterMode, Font, IPoint, IRect, ISize, Image, ImageFilter, ImageInfo, Matrix, Paint, Path,
-    Picture, Pixmap, Point, QuickReject, RRect, Rect, Region, SamplingOptions, Shader, Surface,
-    SurfaceProps, TextBlob, TextEncoding, Vector, Vertices, M44,
+    FilterMode, Font, GlyphId, IPoint, IRect, ISize, Image, ImageFilter, ImageInfo, Matrix, Paint,
+    Path, Picture, Pixmap, Point, QuickReject, RRect, RSXform, Rect, Region, SamplingOptions,
+    Shader, Surface, SurfaceProps, TextBlob, TextBlobBuilder, TextEncoding, TileMode, Vector,
+    Vertices, M44,
 };
 use skia_bindings as sb;
 use skia_bindings::{
@@ -25,18 +26,28 @@ pub use lattice::Lattice;
 bitflags! {
     /// [`SaveLayerFlags`] provides options that may be used in any combination in [`SaveLayerRec`],
     /// defining how layer allocated by [`Canvas::save_layer()`] operates. It may be set to zero,
-    /// [`PRESERVE_LCD_TEXT`], [`INIT_WITH_PREVIOUS`], or both flags.
+    /// or any combination of [`PRESERVE_LCD_TEXT`], [`INIT_WITH_PREVIOUS`], and
+    /// [`F16_COLOR_TYPE`].
     pub struct SaveLayerFlags: u32 {
         const PRESERVE_LCD_TEXT = sb::SkCanvas_SaveLayerFlagsSet_kPreserveLCDText_SaveLayerFlag as _;
         /// initializes with previous contents
         const INIT_WITH_PREVIOUS = sb::SkCanvas_SaveLayerFlagsSet_kInitWithPrevious_SaveLayerFlag as _;
+        /// allocates the layer with a half float color type, to preserve precision and values
+        /// outside of `[0, 1]`
         const F16_COLOR_TYPE = sb::SkCanvas_SaveLayerFlagsSet_kF16ColorType as _;
     }
 }
 
 /// [`SaveLayerRec`] contains the state used to create the layer.
-#[allow(dead_code)]
 pub struct SaveLayerRec<'a> {
+    native: NativeSaveLayerRec<'a>,
+    clip_mask: Option<&'a Image>,
+    clip_matrix: Option<&'a Matrix>,
+}
+
+/// The part of a [`SaveLayerRec`] that Skia still supports natively.
+#[allow(dead_code)]
+struct NativeSaveLayerRec<'a> {
     // We _must_ store _references_ to the native types here, because not all of them are native
     // transmutable, like ImageFilter or Image, which are represented as ref counted pointers and so
     // we would store a reference to a pointer only.
@@ -46,90 +57,97 @@ pub struct SaveLayerRec<'a> {
     flags: SaveLayerFlags,
 }
 
-impl<'a> NativeTransmutable<SkCanvas_SaveLayerRec> for SaveLayerRec<'a> {}
+impl<'a> NativeTransmutable<SkCanvas_SaveLayerRec> for NativeSaveLayerRec<'a> {}
 
 #[test]
 fn test_save_layer_rec_layout() {
-    SaveLayerRec::test_layout()
+    NativeSaveLayerRec::test_layout()
 }
 
 impl fmt::Debug for SaveLayerRec<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let native = &self.native;
         f.debug_struct("SaveLayerRec")
-            .field("bounds", &self.bounds.map(Rect::from_native_ref))
-            .field("paint", &self.paint.map(Paint::from_native_ref))
+            .field("bounds", &native.bounds.map(Rect::from_native_ref))
+            .field("paint", &native.paint.map(Paint::from_native_ref))
             .field(
                 "backdrop",
-                &ImageFilter::from_unshared_ptr_ref(&(self.backdrop.as_ptr_or_null() as *mut _)),
+                &ImageFilter::from_unshared_ptr_ref(&(native.backdrop.as_ptr_or_null() as *mut _)),
             )
-            .field("flags", &self.flags)
+            .field("flags", &native.flags)
+            .field("clip_mask", &self.clip_mask)
+            .field("clip_matrix", &self.clip_matrix)
             .finish()
     }
 }
 
 impl<'a> Default for SaveLayerRec<'a> {
-    /// Sets [`Self::bounds`], [`Self::paint`], and [`Self::backdrop`] to `None`. Clears
-    /// [`Self::flags`].
+    /// Sets [`Self::bounds`], [`Self::paint`], [`Self::backdrop`], [`Self::clip_mask`], and
+    /// [`Self::clip_matrix`] to `None`. Clears [`Self::flags`].
     ///
     /// Returns empty [`SaveLayerRec`]
     fn default() -> Self {
         SaveLayerRec {
-            bounds: None,
-            paint: None,
-            backdrop: None,
-            flags: SaveLayerFlags::empty(),
+            native: NativeSaveLayerRec {
+                bounds: None,
+                paint: None,
+                backdrop: None,
+                flags: SaveLayerFlags::empty(),
+            },
+            clip_mask: None,
+            clip_matrix: None,
         }
     }
 }
 
 impl<'a> SaveLayerRec<'a> {
     /// Hints at layer size limit
-    pub fn bounds(self, bounds: &'a Rect) -> Self {
-        Self {
-            bounds: Some(bounds.native()),
-            ..self
-        }
+    pub fn bounds(mut self, bounds: &'a Rect) -> Self {
+        self.native.bounds = Some(bounds.native());
+        self
     }
 
     /// Modifies overlay
-    pub fn paint(self, paint: &'a Paint) -> Self {
-        Self {
-            paint: Some(paint.native()),
-            ..self
-        }
+    pub fn paint(mut self, paint: &'a Paint) -> Self {
+        self.native.paint = Some(paint.native());
+        self
     }
 
     /// If not `None`, this triggers the same initialization behavior as setting
     /// [`SaveLayerFlags::INIT_WITH_PREVIOUS`] on [`Self::flags`]: the current layer is copied into
     /// the new layer, rather than initializing the new layer with transparent-black. This is then
     /// filtered by [`Self::backdrop`] (respecting the current clip).
-    pub fn backdrop(self, backdrop: &'a ImageFilter) -> Self {
+    pub fn backdrop(mut self, backdrop: &'a ImageFilter) -> Self {
+        self.native.backdrop = Some(backdrop.native());
+        self
+    }
+
+    /// Masks the layer with the alpha channel of `clip_mask` when it is composited at restore,
+    /// positioned by [`Self::clip_matrix`] relative to the current [`Matrix`].
+    ///
+    /// Skia removed native clip masks, so [`Canvas::save_layer()`] saves the canvas and clips it
+    /// with the mask as a shader before it creates the layer. Restore both with
+    /// [`Canvas::restore_to_count()`] and the count [`Canvas::save_layer()`] returns.
+    pub fn clip_mask(self, clip_mask: &'a Image) -> Self {
         Self {
-            backdrop: Some(backdrop.native()),
+            clip_mask: Some(clip_mask),
             ..self
         }
     }
 
-    #[deprecated(
-        since = "0.33.0",
-        note = "removed without replacement, does not set clip_mask"
-    )]
-    pub fn clip_mask(self, _clip_mask: &'a Image) -> Self {
-        self
+    /// Maps the [`Self::clip_mask`] into the local coordinates of the layer.
+    pub fn clip_matrix(self, clip_matrix: &'a Matrix) -> Self {
+        Self {
+            clip_matrix: Some(clip_matrix),
+            ..self
+        }
     }
 
-    #[deprecated(
-        since = "0.33.0",
-        note = "removed without replacement, does not set clip_matrix"
-    )]
-    pub fn clip_matrix(self, _clip_matrix: &'a Matrix) -> Self {
+    /// Preserves LCD text, creates with prior layer contents, or uses a half float color type
+    pub fn flags(mut self, flags: SaveLayerFlags) -> Self {
+        self.native.flags = flags;
         self
     }
-
-    /// Preserves LCD text, creates with prior layer contents
-    pub fn flags(self, flags: SaveLayerFlags) -> Self {
-        Self { flags, ..self }
-    }
 }
 
 /// Selects if an array of points are drawn as discrete points, as lines, or as an open polygon.
@@ -150,6 +168,53 @@ fn test_src_rect_constraint_naming() {
     let _ = SrcRectConstraint::Fast;
 }
 
+bitflags! {
+    /// Controls which edges of a quad drawn with [`Canvas::experimental_draw_edge_aa_quad()`] or
+    /// [`Canvas::experimental_draw_edge_aa_image_set()`] are anti-aliased.
+    pub struct QuadAAFlags: u32 {
+        const LEFT = sb::SkCanvas_QuadAAFlags_kLeft_QuadAAFlag as _;
+        const TOP = sb::SkCanvas_QuadAAFlags_kTop_QuadAAFlag as _;
+        const RIGHT = sb::SkCanvas_QuadAAFlags_kRight_QuadAAFlag as _;
+        const BOTTOM = sb::SkCanvas_QuadAAFlags_kBottom_QuadAAFlag as _;
+        const NONE = sb::SkCanvas_QuadAAFlags_kNone_QuadAAFlags as _;
+        const ALL = sb::SkCanvas_QuadAAFlags_kAll_QuadAAFlags as _;
+    }
+}
+
+/// An image drawn by [`Canvas::experimental_draw_edge_aa_image_set()`].
+#[derive(Clone, Debug)]
+pub struct ImageSetEntry {
+    pub image: Image,
+    pub src_rect: Rect,
+    pub dst_rect: Rect,
+    /// Index into the pre-view matrices, `None` if the entry is drawn with the canvas' matrix only.
+    pub matrix_index: Option<usize>,
+    pub alpha: f32,
+    pub aa_flags: QuadAAFlags,
+    /// Whether the entry is clipped by the next four points of the destination clips.
+    pub has_clip: bool,
+}
+
+impl ImageSetEntry {
+    pub fn new(
+        image: impl Into<Image>,
+        src_rect: impl AsRef<Rect>,
+        dst_rect: impl AsRef<Rect>,
+        alpha: f32,
+        aa_flags: QuadAAFlags,
+    ) -> Self {
+        Self {
+            image: image.into(),
+            src_rect: *src_rect.as_ref(),
+            dst_rect: *dst_rect.as_ref(),
+            matrix_index: None,
+            alpha,
+            aa_flags,
+            has_clip: false,
+        }
+    }
+}
+
 /// Provides access to Canvas's pixels.
 ///
 /// Returned by [`Canvas::access_top_layer_pixels()`]
@@ -873,15 +938,33 @@ impl Canvas {
     /// [`SaveLayerRec`] contains the state used to create the layer.
     ///
     /// Call [`Self::restore_to_count()`] with result to restore this and subsequent saves.
+    /// With a [`SaveLayerRec::clip_mask()`], the canvas is saved once more before the layer is
+    /// created, and a single [`Self::restore()`] does not restore it.
     ///
     /// - `layer_rec` layer state
     /// Returns depth of save state stack before this call was made.
     ///
     /// example: https://fiddle.skia.org/c/@Canvas_saveLayer_3
     pub fn save_layer(&mut self, layer_rec: &SaveLayerRec) -> usize {
-        unsafe { self.native_mut().saveLayer1(layer_rec.native()) }
-            .try_into()
-            .unwrap()
+        let mask = layer_rec.clip_mask.and_then(|clip_mask| {
+            clip_mask.to_shader(
+                (TileMode::Decal, TileMode::Decal),
+                SamplingOptions::default(),
+                layer_rec.clip_matrix,
+            )
+        });
+        // The clip of the state the layer is restored to masks the layer when it is composited.
+        match mask {
+            Some(mask) => {
+                let count = self.save();
+                self.clip_shader(mask, ClipOp::Intersect);
+                unsafe { self.native_mut().saveLayer1(layer_rec.native.native()) };
+                count
+            }
+            None => unsafe { self.native_mut().saveLayer1(layer_rec.native.native()) }
+                .try_into()
+                .unwrap(),
+        }
     }
 
     /// Removes changes to [`Matrix`] and clip since [`Canvas`] state was
@@ -1784,6 +1867,111 @@ impl Canvas {
         self
     }
 
+    /// Draws `glyphs` at `positions`, offset by `origin`, using clip, [`Matrix`], [`Font`] `font`,
+    /// and [`Paint`] `paint`.
+    ///
+    /// Skia m91 has no `drawGlyphs()` and no other way to draw glyphs without a [`TextBlob`], so
+    /// each call builds a blob. This spares the caller the [`TextBlobBuilder`], but not the cost of
+    /// building the blob.
+    ///
+    /// - `glyphs` the glyphs to draw
+    /// - `positions` where to draw each glyph relative to `origin`
+    /// - `origin` the origin of all the positions
+    /// - `font` typeface, text size and so on, used to describe the text
+    /// - `paint` blend, color, and so on, used to draw
+    ///
+    /// # Panics
+    ///
+    /// If the lengths of `glyphs` and `positions` differ.
+    pub fn draw_glyphs(
+        &mut self,
+        glyphs: &[GlyphId],
+        positions: &[Point],
+        origin: impl Into<Point>,
+        font: &Font,
+        paint: &Paint,
+    ) -> &mut Self {
+        assert_eq!(positions.len(), glyphs.len());
+        let mut builder = TextBlobBuilder::new();
+        let (run_glyphs, run_positions) = builder.alloc_run_pos(font, glyphs.len(), None);
+        run_glyphs.copy_from_slice(glyphs);
+        run_positions.copy_from_slice(positions);
+        self.draw_text_blob_if_some(builder.make(), origin, paint)
+    }
+
+    /// Draws `glyphs`, each transformed by its [`RSXform`] in `xforms`, offset by `origin`, using
+    /// clip, [`Matrix`], [`Font`] `font`, and [`Paint`] `paint`.
+    ///
+    /// Like [`Self::draw_glyphs()`], this builds a [`TextBlob`] for each call.
+    ///
+    /// # Panics
+    ///
+    /// If the lengths of `glyphs` and `xforms` differ.
+    pub fn draw_glyphs_rsxform(
+        &mut self,
+        glyphs: &[GlyphId],
+        xforms: &[RSXform],
+        origin: impl Into<Point>,
+        font: &Font,
+        paint: &Paint,
+    ) -> &mut Self {
+        assert_eq!(xforms.len(), glyphs.len());
+        let mut builder = TextBlobBuilder::new();
+        let (run_glyphs, run_xforms) = builder.alloc_run_rsxform(font, glyphs.len());
+        run_glyphs.copy_from_slice(glyphs);
+        run_xforms.copy_from_slice(xforms);
+        self.draw_text_blob_if_some(builder.make(), origin, paint)
+    }
+
+    /// Draws `glyphs` like [`Self::draw_glyphs()`] and associates them with the text they
+    /// represent, so that backends like PDF can make the text selectable and searchable.
+    ///
+    /// Like [`Self::draw_glyphs()`], this builds a [`TextBlob`] for each call.
+    ///
+    /// - `clusters` for each glyph, the UTF-8 offset of its text in `utf8_text`
+    /// - `utf8_text` the text the glyphs represent
+    ///
+    /// # Panics
+    ///
+    /// If the lengths of `glyphs`, `positions` and `clusters` differ, or if a cluster is not an
+    /// offset in `utf8_text`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_glyphs_with_clusters(
+        &mut self,
+        glyphs: &[GlyphId],
+        positions: &[Point],
+        clusters: &[u32],
+        utf8_text: impl AsRef<str>,
+        origin: impl Into<Point>,
+        font: &Font,
+        paint: &Paint,
+    ) -> &mut Self {
+        assert_eq!(positions.len(), glyphs.len());
+        assert_eq!(clusters.len(), glyphs.len());
+        let utf8_text = utf8_text.as_ref().as_bytes();
+        assert!(clusters.iter().all(|c| (*c as usize) < utf8_text.len()));
+        let mut builder = TextBlobBuilder::new();
+        let (run_glyphs, run_positions, run_text, run_clusters) =
+            builder.alloc_run_text_pos(font, glyphs.len(), utf8_text.len(), None);
+        run_glyphs.copy_from_slice(glyphs);
+        run_positions.copy_from_slice(positions);
+        run_text.copy_from_slice(utf8_text);
+        run_clusters.copy_from_slice(clusters);
+        self.draw_text_blob_if_some(builder.make(), origin, paint)
+    }
+
+    fn draw_text_blob_if_some(
+        &mut self,
+        blob: Option<TextBlob>,
+        origin: impl Into<Point>,
+        paint: &Paint,
+    ) -> &mut Self {
+        if let Some(blob) = blob {
+            self.draw_text_blob(blob, origin, paint);
+        }
+        self
+    }
+
     /// Draws [`Picture`] picture, using clip and [`Matrix`]; transforming picture with
     /// [`Matrix`] matrix, if provided; and use [`Paint`] `paint` alpha, [`crate::ColorFilter`],
     /// [`ImageFilter`], and [`BlendMode`], if provided.
@@ -1886,7 +2074,158 @@ impl Canvas {
         self
     }
 
-    // TODO: drawAtlas
+    /// Draws a set of sprites from `atlas`, using clip, [`Matrix`], and optional [`Paint`] `paint`.
+    ///
+    /// For each sprite, the [`Rect`] of `tex` locates it in `atlas`, and [`RSXform`] of `xform`
+    /// maps it on [`Canvas`]. If `colors` is given, each sprite's color is blended with its image
+    /// using [`BlendMode`] `mode`.
+    ///
+    /// - `atlas` [`Image`] containing sprites
+    /// - `xform` [`RSXform`] mappings for sprites in atlas
+    /// - `tex` [`Rect`] locations of sprites in atlas
+    /// - `colors` one per sprite, blended with sprite using [`BlendMode`]; may be `None`
+    /// - `mode` [`BlendMode`] combining colors and sprites
+    /// - `sampling` [`SamplingOptions`] used when sampling from the atlas image
+    /// - `cull_rect` bounds of transformed sprites for efficient clipping; may be `None`
+    /// - `paint` [`crate::ColorFilter`], [`ImageFilter`], [`BlendMode`], and so on; may be `None`
+    ///
+    /// # Panics
+    ///
+    /// If the lengths of `xform`, `tex` and `colors` differ.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_atlas(
+        &mut self,
+        atlas: &Image,
+        xform: &[RSXform],
+        tex: &[Rect],
+        colors: Option<&[Color]>,
+        mode: BlendMode,
+        sampling: impl Into<SamplingOptions>,
+        cull_rect: Option<&Rect>,
+        paint: Option<&Paint>,
+    ) -> &mut Self {
+        let count = xform.len();
+        assert_eq!(tex.len(), count);
+        if let Some(colors) = colors {
+            assert_eq!(colors.len(), count);
+        }
+        let sampling = sampling.into();
+        unsafe {
+            sb::C_SkCanvas_drawAtlas(
+                self.native_mut(),
+                atlas.native(),
+                xform.native().as_ptr(),
+                tex.native().as_ptr(),
+                colors.map(|c| c.native().as_ptr()).unwrap_or(ptr::null()),
+                count.try_into().unwrap(),
+                mode,
+                sampling.native(),
+                cull_rect.native_ptr_or_null(),
+                paint.native_ptr_or_null(),
+            )
+        }
+        self
+    }
+
+    /// Draws `rect`, optionally clipped by the quad `clip`, with the anti-aliasing of each edge
+    /// controlled by `aa_flags`, so that adjacent rectangles can be drawn without seams.
+    ///
+    /// - `rect` the rectangle to draw
+    /// - `clip` points of a convex quad inside `rect` that clips it; may be `None`
+    /// - `aa_flags` the edges that are anti-aliased
+    /// - `color` the color of the quad
+    /// - `mode` [`BlendMode`] used to draw the color
+    pub fn experimental_draw_edge_aa_quad(
+        &mut self,
+        rect: impl AsRef<Rect>,
+        clip: Option<&[Point; 4]>,
+        aa_flags: QuadAAFlags,
+        color: impl Into<Color4f>,
+        mode: BlendMode,
+    ) -> &mut Self {
+        let color = color.into();
+        unsafe {
+            sb::C_SkCanvas_experimental_DrawEdgeAAQuad(
+                self.native_mut(),
+                rect.as_ref().native(),
+                clip.map(|c| c.native().as_ptr()).unwrap_or(ptr::null()),
+                aa_flags.bits(),
+                color.native(),
+                mode,
+            )
+        }
+        self
+    }
+
+    /// Draws each [`ImageSetEntry`] of `set` like [`Self::draw_image_rect()`] would, with the
+    /// anti-aliasing of each edge controlled by the entry's [`QuadAAFlags`].
+    ///
+    /// - `set` the images to draw
+    /// - `dst_clips` four points for each entry that has a clip, in order
+    /// - `pre_view_matrices` the matrices the entries' matrix indices refer to
+    /// - `sampling` [`SamplingOptions`] used when sampling the images
+    /// - `paint` [`Paint`] applied to all images; may be `None`
+    /// - `constraint` sampling constraint of the source rectangles, strict by default
+    ///
+    /// # Panics
+    ///
+    /// If the number of `dst_clips` does not match the entries with a clip, or an entry's matrix
+    /// index is out of bounds.
+    pub fn experimental_draw_edge_aa_image_set(
+        &mut self,
+        set: &[ImageSetEntry],
+        dst_clips: &[Point],
+        pre_view_matrices: &[Matrix],
+        sampling: impl Into<SamplingOptions>,
+        paint: Option<&Paint>,
+        constraint: impl Into<Option<SrcRectConstraint>>,
+    ) -> &mut Self {
+        let clip_count = set.iter().filter(|e| e.has_clip).count();
+        assert_eq!(dst_clips.len(), clip_count * 4);
+        let entries: Vec<sb::C_ImageSetEntry> = set
+            .iter()
+            .map(|e| {
+                let matrix_index = match e.matrix_index {
+                    Some(i) => {
+                        assert!(i < pre_view_matrices.len());
+                        i.try_into().unwrap()
+                    }
+                    None => -1,
+                };
+                sb::C_ImageSetEntry {
+                    image: e.image.native(),
+                    srcRect: e.src_rect.into_native(),
+                    dstRect: e.dst_rect.into_native(),
+                    matrixIndex: matrix_index,
+                    alpha: e.alpha,
+                    aaFlags: e.aa_flags.bits(),
+                    hasClip: e.has_clip,
+                }
+            })
+            .collect();
+        let sampling = sampling.into();
+        unsafe {
+            sb::C_SkCanvas_experimental_DrawEdgeAAImageSet(
+                self.native_mut(),
+                entries.as_ptr(),
+                entries.len().try_into().unwrap(),
+                if dst_clips.is_empty() {
+                    ptr::null()
+                } else {
+                    dst_clips.native().as_ptr()
+                },
+                if pre_view_matrices.is_empty() {
+                    ptr::null()
+                } else {
+                    pre_view_matrices.native().as_ptr()
+                },
+                sampling.native(),
+                paint.native_ptr_or_null(),
+                constraint.into().unwrap_or(SrcRectConstraint::Strict),
+            )
+        }
+        self
+    }
 
     /// Draws [`Drawable`] drawable using clip and [`Matrix`], concatenated with
     /// optional matrix.
@@ -2217,8 +2556,9 @@ impl AutoCanvasRestore {
 #[cfg(test)]
 mod tests {
     use crate::{
-        canvas::SaveLayerFlags, canvas::SaveLayerRec, AlphaType, Canvas, ClipOp, Color, ColorType,
-        ImageInfo, OwnedCanvas, Rect,
+        canvas::ImageSetEntry, canvas::QuadAAFlags, canvas::SaveLayerFlags, canvas::SaveLayerRec,
+        AlphaType, BlendMode, Canvas, ClipOp, Color, ColorType, Font, ImageInfo, Matrix,
+        OwnedCanvas, Paint, Point, RSXform, Rect, SamplingOptions, Surface,
     };
 
     #[test]
@@ -2265,6 +2605,63 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_save_layer_with_clip_mask() {
+        let mut mask = Surface::new_raster_n32_premul((4, 4)).unwrap();
+        mask.canvas()
+            .draw_rect(Rect::from_wh(2.0, 4.0), &Paint::default());
+        let mask = mask.image_snapshot();
+        let clip_matrix = Matrix::translate((1.0, 0.0));
+
+        let mut pixels: [u32; 16] = Default::default();
+        {
+            let mut canvas = Canvas::from_raster_direct_n32((4, 4), &mut pixels, None).unwrap();
+            let rec = SaveLayerRec::default()
+                .clip_mask(&mask)
+                .clip_matrix(&clip_matrix)
+                .flags(SaveLayerFlags::INIT_WITH_PREVIOUS | SaveLayerFlags::F16_COLOR_TYPE);
+            let count = canvas.save_layer(&rec);
+            canvas.draw_paint(&Paint::default());
+            canvas.restore_to_count(count);
+            assert_eq!(canvas.save_count(), 1);
+        }
+        assert_eq!(pixels[0], 0);
+        assert_ne!(pixels[1], 0);
+        assert_ne!(pixels[2], 0);
+        assert_eq!(pixels[3], 0);
+    }
+
+    #[test]
+    fn test_save_layer_with_clip_mask_masks_the_backdrop() {
+        use crate::{color_filters, image_filters};
+
+        let mut mask = Surface::new_raster_n32_premul((4, 4)).unwrap();
+        mask.canvas()
+            .draw_rect(Rect::from_wh(2.0, 4.0), &Paint::default());
+        let mask = mask.image_snapshot();
+        let clip_matrix = Matrix::translate((1.0, 0.0));
+        let backdrop = image_filters::color_filter(
+            color_filters::blend(Color::RED, BlendMode::Src).unwrap(),
+            None,
+            image_filters::CropRect::default(),
+        )
+        .unwrap();
+
+        let mut pixels: [u32; 16] = Default::default();
+        {
+            let mut canvas = Canvas::from_raster_direct_n32((4, 4), &mut pixels, None).unwrap();
+            canvas.clear(Color::BLUE);
+            let rec = SaveLayerRec::default()
+                .clip_mask(&mask)
+                .clip_matrix(&clip_matrix)
+                .backdrop(&backdrop);
+            let count = canvas.save_layer(&rec);
+            canvas.restore_to_count(count);
+        }
+        let colors: Vec<Color> = pixels[..4].iter().map(|p| Color::new(*p)).collect();
+        assert_eq!(colors, [Color::BLUE, Color::RED, Color::RED, Color::BLUE]);
+    }
+
     #[test]
     fn test_make_surface() {
         let mut pixels: [u32; 4] = Default::default();
@@ -2298,4 +2695,202 @@ mod tests {
         let _ = surface.canvas().local_clip_bounds();
         let _ = surface.canvas().local_to_device();
     }
+
+    #[test]
+    fn test_draw_atlas() {
+        let mut atlas = crate::Surface::new_raster_n32_premul((4, 4)).unwrap();
+        atlas.canvas().clear(Color::BLUE);
+        let atlas = atlas.image_snapshot();
+        let mut pixels = [0u32; 10 * 10];
+        let mut canvas = Canvas::from_raster_direct_n32((10, 10), &mut pixels, None).unwrap();
+        let xform = [
+            RSXform::new(1.0, 0.0, (0, 0)),
+            RSXform::new(1.0, 0.0, (5, 5)),
+        ];
+        let tex = [Rect::from_wh(4.0, 4.0); 2];
+        canvas.draw_atlas(
+            &atlas,
+            &xform,
+            &tex,
+            None,
+            BlendMode::Modulate,
+            SamplingOptions::default(),
+            None,
+            None,
+        );
+        drop(canvas);
+        let pixel = |x: usize, y: usize| Color::new(pixels[y * 10 + x]);
+        assert_eq!(pixel(1, 1), Color::BLUE);
+        assert_eq!(pixel(6, 6), Color::BLUE);
+        assert_eq!(pixel(5, 0), Color::TRANSPARENT);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_draw_atlas_checks_lengths() {
+        let atlas = crate::Surface::new_raster_n32_premul((4, 4))
+            .unwrap()
+            .image_snapshot();
+        let mut c = OwnedCanvas::default();
+        c.draw_atlas(
+            &atlas,
+            &[RSXform::new(1.0, 0.0, (0, 0))],
+            &[Rect::from_wh(4.0, 4.0)],
+            Some(&[Color::RED, Color::GREEN]),
+            BlendMode::Modulate,
+            SamplingOptions::default(),
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_draw_glyphs_with_clusters_checks_clusters() {
+        let font = Font::default();
+        let glyphs = font.str_to_glyphs_vec("ab");
+        let mut c = OwnedCanvas::default();
+        c.draw_glyphs_with_clusters(
+            &glyphs,
+            &[Point::new(0.0, 0.0), Point::new(10.0, 0.0)],
+            &[0, 2],
+            "ab",
+            (0, 20),
+            &font,
+            &Paint::default(),
+        );
+    }
+
+    #[test]
+    fn test_draw_glyphs() {
+        fn render(draw: impl FnOnce(&mut Canvas)) -> [u32; 32 * 32] {
+            let mut pixels = [0u32; 32 * 32];
+            let mut canvas = Canvas::from_raster_direct_n32((32, 32), &mut pixels, None).unwrap();
+            draw(&mut canvas);
+            drop(canvas);
+            pixels
+        }
+
+        // RSXforms are drawn with a matrix per glyph, which may anti-alias differently.
+        fn ink_bounds(pixels: &[u32]) -> (usize, usize, usize, usize) {
+            let ink = || pixels.iter().enumerate().filter(|(_, p)| **p != 0);
+            let xs = || ink().map(|(i, _)| i % 32);
+            let ys = || ink().map(|(i, _)| i / 32);
+            (
+                xs().min().unwrap(),
+                ys().min().unwrap(),
+                xs().max().unwrap(),
+                ys().max().unwrap(),
+            )
+        }
+
+        let font = Font::default();
+        let paint = Paint::default();
+        let glyphs = font.str_to_glyphs_vec("ab");
+        let positions = [Point::new(0.0, 0.0), Point::new(10.0, 0.0)];
+        let origin = Point::new(2.0, 20.0);
+
+        let single = render(|c| {
+            c.draw_glyphs(&glyphs[..1], &[Point::default()], origin, &font, &paint);
+        });
+        assert!(single.iter().any(|p| *p != 0));
+        assert_eq!(
+            single,
+            render(|c| {
+                c.draw_str("a", origin, &font, &paint);
+            })
+        );
+
+        let positioned = render(|c| {
+            c.draw_glyphs(&glyphs, &positions, origin, &font, &paint);
+        });
+        assert_ne!(positioned, single);
+
+        let xforms = [
+            RSXform::new(1.0, 0.0, positions[0]),
+            RSXform::new(1.0, 0.0, positions[1]),
+        ];
+        let transformed = render(|c| {
+            c.draw_glyphs_rsxform(&glyphs, &xforms, origin, &font, &paint);
+        });
+        assert_eq!(ink_bounds(&transformed), ink_bounds(&positioned));
+        let rotated = [xforms[0], RSXform::new(0.0, 1.0, positions[1])];
+        let rotated = render(|c| {
+            c.draw_glyphs_rsxform(&glyphs, &rotated, origin, &font, &paint);
+        });
+        assert_ne!(ink_bounds(&rotated), ink_bounds(&positioned));
+
+        assert_eq!(
+            positioned,
+            render(|c| {
+                c.draw_glyphs_with_clusters(
+                    &glyphs,
+                    &positions,
+                    &[0, 1],
+                    "ab",
+                    origin,
+                    &font,
+                    &paint,
+                );
+            })
+        );
+
+        let empty = render(|c| {
+            c.draw_glyphs(&[], &[], origin, &font, &paint);
+        });
+        assert!(empty.iter().all(|p| *p == 0));
+    }
+
+    #[test]
+    fn test_draw_edge_aa() {
+        let mut image = crate::Surface::new_raster_n32_premul((4, 4)).unwrap();
+        image.canvas().clear(Color::BLUE);
+        let image = image.image_snapshot();
+        let mut surface = crate::Surface::new_raster_n32_premul((10, 10)).unwrap();
+        let canvas = surface.canvas();
+        canvas.experimental_draw_edge_aa_quad(
+            Rect::from_wh(5.0, 5.0),
+            None,
+            QuadAAFlags::LEFT | QuadAAFlags::TOP,
+            Color::RED,
+            BlendMode::SrcOver,
+        );
+
+        let mut entry = ImageSetEntry::new(
+            image,
+            Rect::from_wh(4.0, 4.0),
+            Rect::from_xywh(5.0, 5.0, 5.0, 5.0),
+            1.0,
+            QuadAAFlags::NONE,
+        );
+        entry.matrix_index = Some(0);
+        entry.has_clip = true;
+        let clip = [
+            Point::new(5.0, 5.0),
+            Point::new(10.0, 5.0),
+            Point::new(10.0, 10.0),
+            Point::new(5.0, 10.0),
+        ];
+        canvas.experimental_draw_edge_aa_image_set(
+            &[entry],
+            &clip,
+            &[Matrix::default()],
+            SamplingOptions::default(),
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_draw_glyphs_checks_lengths() {
+        let mut c = OwnedCanvas::default();
+        c.draw_glyphs(
+            &[1, 2],
+            &[Point::default()],
+            (0, 0),
+            &Font::default(),
+            &Paint::default(),
+        );
+    }
 }
@@ -0,0 +1,955 @@
+use crate::{
+    canvas::{ImageSetEntry, Lattice, PointMode, QuadAAFlags, SaveLayerFlags, SrcRectConstraint},
+    prelude::*,
+    scalar,
+    utils::shadow_utils::ShadowFlags,
+    BlendMode, Canvas, ClipOp, Color, Color4f, Data, FilterMode, IRect, ISize, Image, ImageFilter,
+    Matrix, OwnedCanvas, Paint, Path, Point, Point3, RRect, RSXform, Rect, Region, SamplingOptions,
+    TextBlob, Vertices, M44,
+};
+use skia_bindings as sb;
+use std::{
+    ffi::{c_void, CStr},
+    fmt,
+    ops::{Deref, DerefMut},
+    os::raw,
+};
+
+/// Receives the drawing commands of a [`BackendCanvas`].
+///
+/// All methods do nothing by default. Pictures and drawables are played back into the canvas
+/// command by command. Glyph runs only reach Skia's canvas as text blobs (last checked: m91), and
+/// are received by [`Self::on_draw_text_blob()`].
+#[allow(unused_variables)]
+pub trait CanvasBackend {
+    fn on_save(&mut self) {}
+
+    fn on_save_layer(
+        &mut self,
+        bounds: Option<&Rect>,
+        paint: Option<&Paint>,
+        backdrop: Option<&ImageFilter>,
+        flags: SaveLayerFlags,
+    ) {
+    }
+
+    fn on_restore(&mut self) {}
+
+    /// Called after the canvas' matrix changed, including after a restore.
+    fn on_matrix_changed(&mut self, local_to_device: &M44) {}
+
+    fn on_clip_rect(&mut self, rect: &Rect, op: ClipOp, anti_alias: bool) {}
+
+    fn on_clip_rrect(&mut self, rrect: &RRect, op: ClipOp, anti_alias: bool) {}
+
+    fn on_clip_path(&mut self, path: &Path, op: ClipOp, anti_alias: bool) {}
+
+    fn on_clip_region(&mut self, region: &Region, op: ClipOp) {}
+
+    fn on_draw_paint(&mut self, paint: &Paint) {}
+
+    fn on_draw_rect(&mut self, rect: &Rect, paint: &Paint) {}
+
+    fn on_draw_rrect(&mut self, rrect: &RRect, paint: &Paint) {}
+
+    fn on_draw_drrect(&mut self, outer: &RRect, inner: &RRect, paint: &Paint) {}
+
+    fn on_draw_oval(&mut self, oval: &Rect, paint: &Paint) {}
+
+    fn on_draw_arc(
+        &mut self,
+        oval: &Rect,
+        start_angle: scalar,
+        sweep_angle: scalar,
+        use_center: bool,
+        paint: &Paint,
+    ) {
+    }
+
+    fn on_draw_path(&mut self, path: &Path, paint: &Paint) {}
+
+    fn on_draw_region(&mut self, region: &Region, paint: &Paint) {}
+
+    fn on_draw_points(&mut self, mode: PointMode, points: &[Point], paint: &Paint) {}
+
+    fn on_draw_text_blob(&mut self, blob: &TextBlob, origin: Point, paint: &Paint) {}
+
+    fn on_draw_image(
+        &mut self,
+        image: &Image,
+        left_top: Point,
+        sampling: &SamplingOptions,
+        paint: Option<&Paint>,
+    ) {
+    }
+
+    fn on_draw_image_rect(
+        &mut self,
+        image: &Image,
+        src: &Rect,
+        dst: &Rect,
+        sampling: &SamplingOptions,
+        paint: Option<&Paint>,
+        constraint: SrcRectConstraint,
+    ) {
+    }
+
+    fn on_draw_vertices(&mut self, vertices: &Vertices, mode: BlendMode, paint: &Paint) {}
+
+    #[allow(clippy::too_many_arguments)]
+    fn on_draw_atlas(
+        &mut self,
+        atlas: &Image,
+        xforms: &[RSXform],
+        tex: &[Rect],
+        colors: Option<&[Color]>,
+        mode: BlendMode,
+        sampling: &SamplingOptions,
+        cull_rect: Option<&Rect>,
+        paint: Option<&Paint>,
+    ) {
+    }
+
+    fn on_draw_patch(
+        &mut self,
+        cubics: &[Point; 12],
+        colors: Option<&[Color; 4]>,
+        tex_coords: Option<&[Point; 4]>,
+        mode: BlendMode,
+        paint: &Paint,
+    ) {
+    }
+
+    fn on_draw_image_lattice(
+        &mut self,
+        image: &Image,
+        lattice: &Lattice,
+        dst: &Rect,
+        filter: FilterMode,
+        paint: Option<&Paint>,
+    ) {
+    }
+
+    fn on_draw_edge_aa_quad(
+        &mut self,
+        rect: &Rect,
+        clip: Option<&[Point; 4]>,
+        aa_flags: QuadAAFlags,
+        color: &Color4f,
+        mode: BlendMode,
+    ) {
+    }
+
+    fn on_draw_edge_aa_image_set(
+        &mut self,
+        set: &[ImageSetEntry],
+        dst_clips: &[Point],
+        pre_view_matrices: &[Matrix],
+        sampling: &SamplingOptions,
+        paint: Option<&Paint>,
+        constraint: SrcRectConstraint,
+    ) {
+    }
+
+    /// Draws the shadow of `path`, see [`crate::utils::shadow_utils::draw_shadow()`].
+    #[allow(clippy::too_many_arguments)]
+    fn on_draw_shadow(
+        &mut self,
+        path: &Path,
+        z_plane_params: &Point3,
+        light_pos: &Point3,
+        light_radius: scalar,
+        ambient_color: Color,
+        spot_color: Color,
+        flags: ShadowFlags,
+    ) {
+    }
+
+    fn on_draw_annotation(&mut self, rect: &Rect, key: &str, value: Option<&Data>) {}
+}
+
+/// A [`Canvas`] that sends its drawing commands to a [`CanvasBackend`].
+///
+/// Clips and matrices are tracked like on any other canvas, so [`Canvas::local_to_device()`] and
+/// [`Canvas::device_clip_bounds()`] can be used, but nothing is drawn.
+pub struct BackendCanvas<B: CanvasBackend> {
+    // Dropped first, because the canvas calls into the backend when it restores its saved states.
+    canvas: OwnedCanvas<'static>,
+    backend: Box<B>,
+}
+
+impl<B: CanvasBackend> Deref for BackendCanvas<B> {
+    type Target = Canvas;
+
+    fn deref(&self) -> &Self::Target {
+        &self.canvas
+    }
+}
+
+impl<B: CanvasBackend> DerefMut for BackendCanvas<B> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.canvas
+    }
+}
+
+impl<B: CanvasBackend + fmt::Debug> fmt::Debug for BackendCanvas<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BackendCanvas")
+            .field("canvas", &self.canvas)
+            .field("backend", &self.backend)
+            .finish()
+    }
+}
+
+impl<B: CanvasBackend> BackendCanvas<B> {
+    /// Creates a canvas of `size` that sends its drawing commands to `backend`.
+    pub fn new(size: impl Into<ISize>, backend: B) -> Self {
+        let size = size.into();
+        let mut backend = Box::new(backend);
+        let param = param(backend.as_mut());
+        let canvas = Canvas::own_from_native_ptr(unsafe {
+            sb::C_RustCanvas_new(size.width, size.height, &param)
+        })
+        .unwrap();
+        Self { canvas, backend }
+    }
+
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    pub fn backend_mut(&mut self) -> &mut B {
+        &mut self.backend
+    }
+
+    /// Drops the canvas and returns the backend.
+    pub fn into_backend(self) -> B {
+        let Self { canvas, backend } = self;
+        drop(canvas);
+        *backend
+    }
+}
+
+impl Canvas {
+    /// Creates a canvas of `size` that sends its drawing commands to `backend`, see
+    /// [`BackendCanvas`].
+    pub fn with_backend<B: CanvasBackend>(size: impl Into<ISize>, backend: B) -> BackendCanvas<B> {
+        BackendCanvas::new(size, backend)
+    }
+}
+
+fn param<B: CanvasBackend>(backend: &mut B) -> sb::RustCanvasBackendParam {
+    unsafe fn as_backend<'a, B>(data: *mut c_void) -> &'a mut B {
+        &mut *(data as *mut B)
+    }
+
+    unsafe fn paint<'a>(paint: *const sb::SkPaint) -> &'a Paint {
+        Paint::from_native_ref(&*paint)
+    }
+
+    unsafe fn optional_paint<'a>(paint: *const sb::SkPaint) -> Option<&'a Paint> {
+        paint
+            .into_option()
+            .map(|paint| Paint::from_native_ref(&*paint))
+    }
+
+    unsafe extern "C" fn save<B: CanvasBackend>(data: *mut c_void) {
+        as_backend::<B>(data).on_save()
+    }
+
+    unsafe extern "C" fn save_layer<B: CanvasBackend>(
+        data: *mut c_void,
+        bounds: *const sb::SkRect,
+        paint: *const sb::SkPaint,
+        backdrop: *const sb::SkImageFilter,
+        flags: sb::SkCanvas_SaveLayerFlags,
+    ) {
+        let backdrop = backdrop as *mut _;
+        as_backend::<B>(data).on_save_layer(
+            bounds.into_option().map(|r| Rect::from_native_ref(&*r)),
+            optional_paint(paint),
+            ImageFilter::from_unshared_ptr_ref(&backdrop).as_ref(),
+            SaveLayerFlags::from_bits_truncate(flags),
+        )
+    }
+
+    unsafe extern "C" fn restore<B: CanvasBackend>(data: *mut c_void) {
+        as_backend::<B>(data).on_restore()
+    }
+
+    unsafe extern "C" fn matrix_changed<B: CanvasBackend>(
+        data: *mut c_void,
+        local_to_device: *const sb::SkM44,
+    ) {
+        as_backend::<B>(data).on_matrix_changed(M44::from_native_ref(&*local_to_device))
+    }
+
+    unsafe extern "C" fn clip_rect<B: CanvasBackend>(
+        data: *mut c_void,
+        rect: *const sb::SkRect,
+        op: sb::SkClipOp,
+        anti_alias: bool,
+    ) {
+        as_backend::<B>(data).on_clip_rect(Rect::from_native_ref(&*rect), op, anti_alias)
+    }
+
+    unsafe extern "C" fn clip_rrect<B: CanvasBackend>(
+        data: *mut c_void,
+        rrect: *const sb::SkRRect,
+        op: sb::SkClipOp,
+        anti_alias: bool,
+    ) {
+        as_backend::<B>(data).on_clip_rrect(RRect::from_native_ref(&*rrect), op, anti_alias)
+    }
+
+    unsafe extern "C" fn clip_path<B: CanvasBackend>(
+        data: *mut c_void,
+        path: *const sb::SkPath,
+        op: sb::SkClipOp,
+        anti_alias: bool,
+    ) {
+        as_backend::<B>(data).on_clip_path(Path::from_native_ref(&*path), op, anti_alias)
+    }
+
+    unsafe extern "C" fn clip_region<B: CanvasBackend>(
+        data: *mut c_void,
+        region: *const sb::SkRegion,
+        op: sb::SkClipOp,
+    ) {
+        as_backend::<B>(data).on_clip_region(Region::from_native_ref(&*region), op)
+    }
+
+    unsafe extern "C" fn draw_paint<B: CanvasBackend>(data: *mut c_void, p: *const sb::SkPaint) {
+        as_backend::<B>(data).on_draw_paint(paint(p))
+    }
+
+    unsafe extern "C" fn draw_rect<B: CanvasBackend>(
+        data: *mut c_void,
+        rect: *const sb::SkRect,
+        p: *const sb::SkPaint,
+    ) {
+        as_backend::<B>(data).on_draw_rect(Rect::from_native_ref(&*rect), paint(p))
+    }
+
+    unsafe extern "C" fn draw_rrect<B: CanvasBackend>(
+        data: *mut c_void,
+        rrect: *const sb::SkRRect,
+        p: *const sb::SkPaint,
+    ) {
+        as_backend::<B>(data).on_draw_rrect(RRect::from_native_ref(&*rrect), paint(p))
+    }
+
+    unsafe extern "C" fn draw_drrect<B: CanvasBackend>(
+        data: *mut c_void,
+        outer: *const sb::SkRRect,
+        inner: *const sb::SkRRect,
+        p: *const sb::SkPaint,
+    ) {
+        as_backend::<B>(data).on_draw_drrect(
+            RRect::from_native_ref(&*outer),
+            RRect::from_native_ref(&*inner),
+            paint(p),
+        )
+    }
+
+    unsafe extern "C" fn draw_oval<B: CanvasBackend>(
+        data: *mut c_void,
+        oval: *const sb::SkRect,
+        p: *const sb::SkPaint,
+    ) {
+        as_backend::<B>(data).on_draw_oval(Rect::from_native_ref(&*oval), paint(p))
+    }
+
+    unsafe extern "C" fn draw_arc<B: CanvasBackend>(
+        data: *mut c_void,
+        oval: *const sb::SkRect,
+        start_angle: scalar,
+        sweep_angle: scalar,
+        use_center: bool,
+        p: *const sb::SkPaint,
+    ) {
+        as_backend::<B>(data).on_draw_arc(
+            Rect::from_native_ref(&*oval),
+            start_angle,
+            sweep_angle,
+            use_center,
+            paint(p),
+        )
+    }
+
+    unsafe extern "C" fn draw_path<B: CanvasBackend>(
+        data: *mut c_void,
+        path: *const sb::SkPath,
+        p: *const sb::SkPaint,
+    ) {
+        as_backend::<B>(data).on_draw_path(Path::from_native_ref(&*path), paint(p))
+    }
+
+    unsafe extern "C" fn draw_region<B: CanvasBackend>(
+        data: *mut c_void,
+        region: *const sb::SkRegion,
+        p: *const sb::SkPaint,
+    ) {
+        as_backend::<B>(data).on_draw_region(Region::from_native_ref(&*region), paint(p))
+    }
+
+    unsafe extern "C" fn draw_points<B: CanvasBackend>(
+        data: *mut c_void,
+        mode: PointMode,
+        count: usize,
+        points: *const sb::SkPoint,
+        p: *const sb::SkPaint,
+    ) {
+        let points = safer::from_raw_parts(Point::from_native_ptr(points), count);
+        as_backend::<B>(data).on_draw_points(mode, points, paint(p))
+    }
+
+    unsafe extern "C" fn draw_text_blob<B: CanvasBackend>(
+        data: *mut c_void,
+        blob: *const sb::SkTextBlob,
+        x: scalar,
+        y: scalar,
+        p: *const sb::SkPaint,
+    ) {
+        let blob = blob as *mut _;
+        as_backend::<B>(data).on_draw_text_blob(
+            TextBlob::from_unshared_ptr_ref(&blob).as_ref().unwrap(),
+            Point::new(x, y),
+            paint(p),
+        )
+    }
+
+    unsafe extern "C" fn draw_image<B: CanvasBackend>(
+        data: *mut c_void,
+        image: *const sb::SkImage,
+        left: scalar,
+        top: scalar,
+        sampling: *const sb::SkSamplingOptions,
+        p: *const sb::SkPaint,
+    ) {
+        let image = image as *mut _;
+        as_backend::<B>(data).on_draw_image(
+            Image::from_unshared_ptr_ref(&image).as_ref().unwrap(),
+            Point::new(left, top),
+            SamplingOptions::from_native_ref(&*sampling),
+            optional_paint(p),
+        )
+    }
+
+    unsafe extern "C" fn draw_image_rect<B: CanvasBackend>(
+        data: *mut c_void,
+        image: *const sb::SkImage,
+        src: *const sb::SkRect,
+        dst: *const sb::SkRect,
+        sampling: *const sb::SkSamplingOptions,
+        p: *const sb::SkPaint,
+        constraint: SrcRectConstraint,
+    ) {
+        let image = image as *mut _;
+        as_backend::<B>(data).on_draw_image_rect(
+            Image::from_unshared_ptr_ref(&image).as_ref().unwrap(),
+            Rect::from_native_ref(&*src),
+            Rect::from_native_ref(&*dst),
+            SamplingOptions::from_native_ref(&*sampling),
+            optional_paint(p),
+            constraint,
+        )
+    }
+
+    unsafe extern "C" fn draw_vertices<B: CanvasBackend>(
+        data: *mut c_void,
+        vertices: *const sb::SkVertices,
+        mode: BlendMode,
+        p: *const sb::SkPaint,
+    ) {
+        let vertices = vertices as *mut _;
+        as_backend::<B>(data).on_draw_vertices(
+            Vertices::from_unshared_ptr_ref(&vertices).as_ref().unwrap(),
+            mode,
+            paint(p),
+        )
+    }
+
+    unsafe extern "C" fn draw_atlas<B: CanvasBackend>(
+        data: *mut c_void,
+        atlas: *const sb::SkImage,
+        xforms: *const sb::SkRSXform,
+        tex: *const sb::SkRect,
+        colors: *const sb::SkColor,
+        count: raw::c_int,
+        mode: BlendMode,
+        sampling: *const sb::SkSamplingOptions,
+        cull_rect: *const sb::SkRect,
+        p: *const sb::SkPaint,
+    ) {
+        let atlas = atlas as *mut _;
+        let count = count.try_into().unwrap();
+        as_backend::<B>(data).on_draw_atlas(
+            Image::from_unshared_ptr_ref(&atlas).as_ref().unwrap(),
+            safer::from_raw_parts(RSXform::from_native_ptr(xforms), count),
+            safer::from_raw_parts(Rect::from_native_ptr(tex), count),
+            colors
+                .into_option()
+                .map(|colors| safer::from_raw_parts(Color::from_native_ptr(colors), count)),
+            mode,
+            SamplingOptions::from_native_ref(&*sampling),
+            cull_rect.into_option().map(|r| Rect::from_native_ref(&*r)),
+            optional_paint(p),
+        )
+    }
+
+    unsafe extern "C" fn draw_patch<B: CanvasBackend>(
+        data: *mut c_void,
+        cubics: *const sb::SkPoint,
+        colors: *const sb::SkColor,
+        tex_coords: *const sb::SkPoint,
+        mode: BlendMode,
+        p: *const sb::SkPaint,
+    ) {
+        as_backend::<B>(data).on_draw_patch(
+            &*(cubics as *const [Point; 12]),
+            colors
+                .into_option()
+                .map(|colors| &*(colors as *const [Color; 4])),
+            tex_coords
+                .into_option()
+                .map(|tex_coords| &*(tex_coords as *const [Point; 4])),
+            mode,
+            paint(p),
+        )
+    }
+
+    unsafe extern "C" fn draw_image_lattice<B: CanvasBackend>(
+        data: *mut c_void,
+        image: *const sb::SkImage,
+        lattice: *const sb::SkCanvas_Lattice,
+        dst: *const sb::SkRect,
+        filter: FilterMode,
+        p: *const sb::SkPaint,
+    ) {
+        let image = image as *mut _;
+        let lattice = &*lattice;
+        let x_divs = safer::from_raw_parts(lattice.fXDivs, lattice.fXCount.try_into().unwrap());
+        let y_divs = safer::from_raw_parts(lattice.fYDivs, lattice.fYCount.try_into().unwrap());
+        let rect_count = (x_divs.len() + 1) * (y_divs.len() + 1);
+        let lattice = Lattice {
+            x_divs,
+            y_divs,
+            rect_types: lattice
+                .fRectTypes
+                .into_option()
+                .map(|rect_types| safer::from_raw_parts(rect_types, rect_count)),
+            bounds: lattice
+                .fBounds
+                .into_option()
+                .map(|bounds| *IRect::from_native_ref(&*bounds)),
+            colors: lattice
+                .fColors
+                .into_option()
+                .map(|colors| safer::from_raw_parts(Color::from_native_ptr(colors), rect_count)),
+        };
+        as_backend::<B>(data).on_draw_image_lattice(
+            Image::from_unshared_ptr_ref(&image).as_ref().unwrap(),
+            &lattice,
+            Rect::from_native_ref(&*dst),
+            filter,
+            optional_paint(p),
+        )
+    }
+
+    unsafe extern "C" fn draw_edge_aa_quad<B: CanvasBackend>(
+        data: *mut c_void,
+        rect: *const sb::SkRect,
+        clip: *const sb::SkPoint,
+        aa_flags: raw::c_uint,
+        color: *const sb::SkColor4f,
+        mode: BlendMode,
+    ) {
+        as_backend::<B>(data).on_draw_edge_aa_quad(
+            Rect::from_native_ref(&*rect),
+            clip.into_option().map(|clip| &*(clip as *const [Point; 4])),
+            QuadAAFlags::from_bits_truncate(aa_flags),
+            Color4f::from_native_ref(&*color),
+            mode,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    unsafe extern "C" fn draw_edge_aa_image_set<B: CanvasBackend>(
+        data: *mut c_void,
+        set: *const sb::C_ImageSetEntry,
+        count: raw::c_int,
+        dst_clips: *const sb::SkPoint,
+        dst_clip_count: raw::c_int,
+        pre_view_matrices: *const sb::SkMatrix,
+        matrix_count: raw::c_int,
+        sampling: *const sb::SkSamplingOptions,
+        p: *const sb::SkPaint,
+        constraint: SrcRectConstraint,
+    ) {
+        let set: Vec<ImageSetEntry> = safer::from_raw_parts(set, count.try_into().unwrap())
+            .iter()
+            .map(|e| {
+                let image = e.image as *mut _;
+                ImageSetEntry {
+                    image: Image::from_unshared_ptr_ref(&image).clone().unwrap(),
+                    src_rect: Rect::from_native_c(e.srcRect),
+                    dst_rect: Rect::from_native_c(e.dstRect),
+                    matrix_index: e.matrixIndex.try_into().ok(),
+                    alpha: e.alpha,
+                    aa_flags: QuadAAFlags::from_bits_truncate(e.aaFlags),
+                    has_clip: e.hasClip,
+                }
+            })
+            .collect();
+        as_backend::<B>(data).on_draw_edge_aa_image_set(
+            &set,
+            safer::from_raw_parts(
+                Point::from_native_ptr(dst_clips),
+                dst_clip_count.try_into().unwrap(),
+            ),
+            safer::from_raw_parts(
+                Matrix::from_native_ptr(pre_view_matrices),
+                matrix_count.try_into().unwrap(),
+            ),
+            SamplingOptions::from_native_ref(&*sampling),
+            optional_paint(p),
+            constraint,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    unsafe extern "C" fn draw_shadow<B: CanvasBackend>(
+        data: *mut c_void,
+        path: *const sb::SkPath,
+        z_plane_params: *const sb::SkPoint3,
+        light_pos: *const sb::SkPoint3,
+        light_radius: scalar,
+        ambient_color: sb::SkColor,
+        spot_color: sb::SkColor,
+        flags: u32,
+    ) {
+        as_backend::<B>(data).on_draw_shadow(
+            Path::from_native_ref(&*path),
+            Point3::from_native_ref(&*z_plane_params),
+            Point3::from_native_ref(&*light_pos),
+            light_radius,
+            Color::from_native_c(ambient_color),
+            Color::from_native_c(spot_color),
+            ShadowFlags::from_bits_truncate(flags),
+        )
+    }
+
+    unsafe extern "C" fn draw_annotation<B: CanvasBackend>(
+        data: *mut c_void,
+        rect: *const sb::SkRect,
+        key: *const raw::c_char,
+        value: *mut sb::SkData,
+    ) {
+        as_backend::<B>(data).on_draw_annotation(
+            Rect::from_native_ref(&*rect),
+            &CStr::from_ptr(key).to_string_lossy(),
+            Data::from_unshared_ptr_ref(&value).as_ref(),
+        )
+    }
+
+    sb::RustCanvasBackendParam {
+        data: backend as *mut B as *mut c_void,
+        save: Some(save::<B>),
+        saveLayer: Some(save_layer::<B>),
+        restore: Some(restore::<B>),
+        matrixChanged: Some(matrix_changed::<B>),
+        clipRect: Some(clip_rect::<B>),
+        clipRRect: Some(clip_rrect::<B>),
+        clipPath: Some(clip_path::<B>),
+        clipRegion: Some(clip_region::<B>),
+        drawPaint: Some(draw_paint::<B>),
+        drawRect: Some(draw_rect::<B>),
+        drawRRect: Some(draw_rrect::<B>),
+        drawDRRect: Some(draw_drrect::<B>),
+        drawOval: Some(draw_oval::<B>),
+        drawArc: Some(draw_arc::<B>),
+        drawPath: Some(draw_path::<B>),
+        drawRegion: Some(draw_region::<B>),
+        drawPoints: Some(draw_points::<B>),
+        drawTextBlob: Some(draw_text_blob::<B>),
+        drawImage: Some(draw_image::<B>),
+        drawImageRect: Some(draw_image_rect::<B>),
+        drawVertices: Some(draw_vertices::<B>),
+        drawAtlas: Some(draw_atlas::<B>),
+        drawPatch: Some(draw_patch::<B>),
+        drawImageLattice: Some(draw_image_lattice::<B>),
+        drawEdgeAAQuad: Some(draw_edge_aa_quad::<B>),
+        drawEdgeAAImageSet: Some(draw_edge_aa_image_set::<B>),
+        drawShadow: Some(draw_shadow::<B>),
+        drawAnnotation: Some(draw_annotation::<B>),
+    }
+}
+
+/// A [`CanvasBackend`] that counts the rectangles drawn into it.
+#[cfg(test)]
+#[derive(Default, Debug)]
+pub(crate) struct RectCounter(pub usize);
+
+#[cfg(test)]
+impl CanvasBackend for RectCounter {
+    fn on_draw_rect(&mut self, _rect: &Rect, _paint: &Paint) {
+        self.0 += 1;
+    }
+}
+
+#[test]
+fn backend_receives_draw_commands() {
+    use crate::{Color, Matrix};
+
+    #[derive(Default, Debug)]
+    struct Recorder {
+        commands: Vec<String>,
+        saves: usize,
+        translate_x: scalar,
+    }
+
+    impl CanvasBackend for Recorder {
+        fn on_save(&mut self) {
+            self.saves += 1;
+        }
+
+        fn on_matrix_changed(&mut self, local_to_device: &M44) {
+            self.translate_x = local_to_device.to_m33().translate_x();
+        }
+
+        fn on_clip_rect(&mut self, rect: &Rect, _op: ClipOp, _anti_alias: bool) {
+            self.commands.push(format!("clip_rect {}", rect.width()));
+        }
+
+        fn on_draw_rect(&mut self, rect: &Rect, paint: &Paint) {
+            assert_eq!(paint.color(), Color::RED);
+            self.commands.push(format!("draw_rect {}", rect.width()));
+        }
+
+        fn on_draw_path(&mut self, _path: &Path, _paint: &Paint) {
+            self.commands.push("draw_path".into());
+        }
+    }
+
+    let mut paint = Paint::default();
+    paint.set_color(Color::RED);
+
+    let mut canvas = Canvas::with_backend((100, 100), Recorder::default());
+    canvas.save();
+    canvas.concat(&Matrix::translate((10, 0)));
+    assert_eq!(canvas.backend().translate_x, 10.0);
+    canvas.clip_rect(Rect::from_wh(50.0, 50.0), None, None);
+    canvas.draw_rect(Rect::from_wh(20.0, 20.0), &paint);
+    canvas.draw_path(&Path::circle((10, 10), 5.0, None), &paint);
+    canvas.restore();
+    assert_eq!(canvas.backend().translate_x, 0.0);
+
+    let recorder = canvas.into_backend();
+    assert_eq!(recorder.saves, 1);
+    assert_eq!(
+        recorder.commands,
+        ["clip_rect 50", "draw_rect 20", "draw_path"]
+    );
+}
+
+#[test]
+fn backend_receives_atlas_patch_lattice_edge_aa_and_shadow_commands() {
+    use crate::{utils::shadow_utils, Color, Surface};
+
+    #[derive(Default, Debug)]
+    struct Recorder {
+        commands: Vec<String>,
+    }
+
+    impl CanvasBackend for Recorder {
+        fn on_draw_atlas(
+            &mut self,
+            _atlas: &Image,
+            xforms: &[RSXform],
+            _tex: &[Rect],
+            colors: Option<&[Color]>,
+            _mode: BlendMode,
+            _sampling: &SamplingOptions,
+            _cull_rect: Option<&Rect>,
+            _paint: Option<&Paint>,
+        ) {
+            assert!(colors.is_none());
+            self.commands.push(format!("draw_atlas {}", xforms.len()));
+        }
+
+        fn on_draw_patch(
+            &mut self,
+            cubics: &[Point; 12],
+            colors: Option<&[Color; 4]>,
+            tex_coords: Option<&[Point; 4]>,
+            _mode: BlendMode,
+            _paint: &Paint,
+        ) {
+            assert_eq!(cubics[3], Point::new(30.0, 0.0));
+            assert_eq!(colors.unwrap()[1], Color::GREEN);
+            assert!(tex_coords.is_none());
+            self.commands.push("draw_patch".into());
+        }
+
+        fn on_draw_image_lattice(
+            &mut self,
+            _image: &Image,
+            lattice: &Lattice,
+            dst: &Rect,
+            _filter: FilterMode,
+            _paint: Option<&Paint>,
+        ) {
+            assert_eq!(lattice.x_divs, [2]);
+            assert_eq!(lattice.y_divs, [2]);
+            self.commands
+                .push(format!("draw_image_lattice {}", dst.width()));
+        }
+
+        fn on_draw_edge_aa_quad(
+            &mut self,
+            rect: &Rect,
+            clip: Option<&[Point; 4]>,
+            aa_flags: QuadAAFlags,
+            _color: &Color4f,
+            _mode: BlendMode,
+        ) {
+            assert!(clip.is_none());
+            assert_eq!(aa_flags, QuadAAFlags::LEFT);
+            self.commands
+                .push(format!("draw_edge_aa_quad {}", rect.width()));
+        }
+
+        fn on_draw_edge_aa_image_set(
+            &mut self,
+            set: &[ImageSetEntry],
+            dst_clips: &[Point],
+            pre_view_matrices: &[Matrix],
+            _sampling: &SamplingOptions,
+            _paint: Option<&Paint>,
+            _constraint: SrcRectConstraint,
+        ) {
+            assert_eq!(set[0].matrix_index, Some(0));
+            assert_eq!(dst_clips.len(), 4);
+            assert_eq!(pre_view_matrices.len(), 1);
+            self.commands
+                .push(format!("draw_edge_aa_image_set {}", set.len()));
+        }
+
+        fn on_draw_shadow(
+            &mut self,
+            _path: &Path,
+            z_plane_params: &Point3,
+            _light_pos: &Point3,
+            light_radius: scalar,
+            _ambient_color: Color,
+            _spot_color: Color,
+            _flags: ShadowFlags,
+        ) {
+            assert_eq!(z_plane_params.z, 4.0);
+            self.commands.push(format!("draw_shadow {}", light_radius));
+        }
+    }
+
+    let mut image = Surface::new_raster_n32_premul((4, 4)).unwrap();
+    image.canvas().clear(Color::BLUE);
+    let image = image.image_snapshot();
+    let paint = Paint::default();
+
+    let mut canvas = Canvas::with_backend((100, 100), Recorder::default());
+    canvas.draw_atlas(
+        &image,
+        &[RSXform::new(1.0, 0.0, (0, 0)); 2],
+        &[Rect::from_wh(2.0, 2.0); 2],
+        None,
+        BlendMode::SrcOver,
+        SamplingOptions::default(),
+        None,
+        None,
+    );
+    let cubics = [
+        Point::new(0.0, 0.0),
+        Point::new(10.0, 0.0),
+        Point::new(20.0, 0.0),
+        Point::new(30.0, 0.0),
+        Point::new(30.0, 10.0),
+        Point::new(30.0, 20.0),
+        Point::new(30.0, 30.0),
+        Point::new(20.0, 30.0),
+        Point::new(10.0, 30.0),
+        Point::new(0.0, 30.0),
+        Point::new(0.0, 20.0),
+        Point::new(0.0, 10.0),
+    ];
+    canvas.draw_patch(
+        &cubics,
+        &[Color::RED, Color::GREEN, Color::BLUE, Color::BLACK],
+        None,
+        BlendMode::Modulate,
+        &paint,
+    );
+    canvas.draw_image_lattice(
+        &image,
+        &Lattice {
+            x_divs: &[2],
+            y_divs: &[2],
+            rect_types: None,
+            bounds: None,
+            colors: None,
+        },
+        Rect::from_wh(20.0, 20.0),
+        FilterMode::Nearest,
+        None,
+    );
+    canvas.experimental_draw_edge_aa_quad(
+        Rect::from_wh(5.0, 5.0),
+        None,
+        QuadAAFlags::LEFT,
+        Color::RED,
+        BlendMode::SrcOver,
+    );
+    let mut entry = ImageSetEntry::new(
+        image,
+        Rect::from_wh(4.0, 4.0),
+        Rect::from_wh(8.0, 8.0),
+        1.0,
+        QuadAAFlags::NONE,
+    );
+    entry.matrix_index = Some(0);
+    entry.has_clip = true;
+    canvas.experimental_draw_edge_aa_image_set(
+        &[entry],
+        &[
+            Point::new(0.0, 0.0),
+            Point::new(8.0, 0.0),
+            Point::new(8.0, 8.0),
+            Point::new(0.0, 8.0),
+        ],
+        &[Matrix::default()],
+        SamplingOptions::default(),
+        None,
+        None,
+    );
+    shadow_utils::draw_shadow(
+        &mut canvas,
+        &Path::rect(Rect::from_xywh(10.0, 10.0, 20.0, 20.0), None),
+        (0.0, 0.0, 4.0),
+        (50.0, 0.0, 60.0),
+        8.0,
+        Color::BLACK,
+        Color::BLACK,
+        None,
+    );
+
+    assert_eq!(
+        canvas.into_backend().commands,
+        [
+            "draw_atlas 2",
+            "draw_patch",
+            "draw_image_lattice 20",
+            "draw_edge_aa_quad 5",
+            "draw_edge_aa_image_set 1",
+            "draw_shadow 8"
+        ]
+    );
+}
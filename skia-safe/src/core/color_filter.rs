@@ -19,6 +19,10 @@ impl NativeFlattenable for SkColorFilter {
         &self.base()
     }
 
+    fn native_flattenable_type() -> sb::SkFlattenable_Type {
+        sb::SkFlattenable_Type::kSkColorFilter_Type
+    }
+
     fn native_deserialize(data: &[u8]) -> *mut Self {
         unsafe { sb::C_SkColorFilter_Deserialize(data.as_ptr() as _, data.len()) }
     }
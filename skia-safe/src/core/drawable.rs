@@ -15,6 +15,10 @@ impl NativeFlattenable for SkDrawable {
         unsafe { &*(self as *const SkDrawable as *const SkFlattenable) }
     }
 
+    fn native_flattenable_type() -> sb::SkFlattenable_Type {
+        sb::SkFlattenable_Type::kSkDrawable_Type
+    }
+
     fn native_deserialize(data: &[u8]) -> *mut Self {
         unsafe { sb::C_SkDrawable_Deserialize(data.as_ptr() as _, data.len()) }
     }
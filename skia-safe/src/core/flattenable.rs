@@ -1,5 +1,5 @@
 use crate::prelude::*;
-use crate::Data;
+use crate::{Data, DeserialProcs, SerialProcs};
 use skia_bindings as sb;
 use skia_bindings::SkFlattenable;
 use std::ffi::CStr;
@@ -9,17 +9,21 @@ use std::ffi::CStr;
 // TODO: FactoryToName()?
 // TODO: Register()?
 // TODO: getFlattenableType()?
-// TODO: serialize() with SkSerialProcs?
 
 pub trait Flattenable: Sized {
     fn type_name(&self) -> &CStr;
     fn serialize(&self) -> Data;
+    /// Serializes with [`SerialProcs`], for example to replace the images of image shaders.
+    fn serialize_with_procs(&self, procs: &mut SerialProcs) -> Data;
     fn deserialize(data: &[u8]) -> Option<Self>;
+    /// Deserializes data that was serialized with [`Flattenable::serialize_with_procs()`].
+    fn deserialize_with_procs(data: &[u8], procs: &mut DeserialProcs) -> Option<Self>;
 }
 
 // TODO: find a way to hide these trait's functions from other crates.
 pub trait NativeFlattenable {
     fn native_flattenable(&self) -> &SkFlattenable;
+    fn native_flattenable_type() -> sb::SkFlattenable_Type;
     fn native_deserialize(data: &[u8]) -> *mut Self;
 }
 
@@ -40,7 +44,27 @@ where
             .unwrap()
     }
 
+    fn serialize_with_procs(&self, procs: &mut SerialProcs) -> Data {
+        let procs = procs.native();
+        Data::from_ptr(unsafe {
+            sb::C_SkFlattenable_serializeWithProcs(self.native().native_flattenable(), &procs)
+        })
+        .unwrap()
+    }
+
     fn deserialize(data: &[u8]) -> Option<Self> {
         RCHandle::from_ptr(N::native_deserialize(data))
     }
+
+    fn deserialize_with_procs(data: &[u8], procs: &mut DeserialProcs) -> Option<Self> {
+        let procs = procs.native();
+        RCHandle::from_ptr(unsafe {
+            sb::C_SkFlattenable_DeserializeWithProcs(
+                N::native_flattenable_type(),
+                data.as_ptr() as _,
+                data.len(),
+                &procs,
+            )
+        } as *mut N)
+    }
 }
@@ -1,11 +1,11 @@
 use crate::prelude::*;
 use crate::{
-    scalar, FontHinting, FontMetrics, GlyphId, Paint, Path, Point, Rect, TextEncoding, Typeface,
-    Unichar,
+    scalar, FontHinting, FontMetrics, GlyphId, IPoint, IRect, Image, Matrix, Paint, Path, Point,
+    Rect, RoundOut, Surface, TextBlob, TextEncoding, Typeface, Unichar,
 };
 use skia_bindings as sb;
-use skia_bindings::{SkFont, SkFont_PrivFlags};
-use std::{fmt, ptr};
+use skia_bindings::{SkFont, SkFont_PrivFlags, SkMatrix, SkPath};
+use std::{ffi::c_void, fmt, ptr};
 
 pub use skia_bindings::SkFont_Edging as Edging;
 #[test]
@@ -402,7 +402,104 @@ impl Font {
         unsafe { self.native().getPath(glyph_id, path.native_mut()) }.if_true_some(path)
     }
 
-    // TODO: getPaths() (needs a function to be passed, but supports a context).
+    /// Calls `path_proc` for each glyph in `glyphs`, in order.
+    ///
+    /// The path passed to `path_proc` is not transformed yet, the [`Matrix`] needs to be applied
+    /// to it to get the path in font space. The path is `None` if the glyph has no outline, for
+    /// example for a space or a bitmap glyph.
+    pub fn get_paths<F>(&self, glyphs: &[GlyphId], mut path_proc: F)
+    where
+        F: FnMut(GlyphId, Option<&Path>, &Matrix),
+    {
+        struct Context<'a, F> {
+            glyphs: &'a [GlyphId],
+            index: usize,
+            path_proc: &'a mut F,
+        }
+
+        unsafe extern "C" fn path_proc_trampoline<F>(
+            path: *const SkPath,
+            matrix: *const SkMatrix,
+            ctx: *mut c_void,
+        ) where
+            F: FnMut(GlyphId, Option<&Path>, &Matrix),
+        {
+            let ctx = &mut *(ctx as *mut Context<F>);
+            let glyph_id = ctx.glyphs[ctx.index];
+            ctx.index += 1;
+            let path = path.into_option().map(|path| Path::from_native_ref(&*path));
+            (ctx.path_proc)(glyph_id, path, Matrix::from_native_ref(&*matrix))
+        }
+
+        let mut ctx = Context {
+            glyphs,
+            index: 0,
+            path_proc: &mut path_proc,
+        };
+
+        unsafe {
+            self.native().getPaths(
+                glyphs.as_ptr(),
+                glyphs.len().try_into().unwrap(),
+                Some(path_proc_trampoline::<F>),
+                &mut ctx as *mut _ as *mut c_void,
+            )
+        }
+    }
+
+    // convenience function
+    /// Returns the transformed paths of `glyphs`, `None` for glyphs that have no outline.
+    pub fn get_paths_vec(&self, glyphs: &[GlyphId]) -> Vec<Option<Path>> {
+        let mut paths = Vec::with_capacity(glyphs.len());
+        self.get_paths(glyphs, |_, path, matrix| {
+            paths.push(path.map(|path| path.with_transform(matrix)))
+        });
+        paths
+    }
+
+    // convenience function
+    /// Returns the bounds of `glyphs`, relative to their origins. If `paint` is provided, its
+    /// stroke and path effect are included.
+    pub fn get_bounds_vec(&self, glyphs: &[GlyphId], paint: Option<&Paint>) -> Vec<Rect> {
+        let mut bounds = vec![Rect::default(); glyphs.len()];
+        self.get_bounds(glyphs, &mut bounds, paint);
+        bounds
+    }
+
+    /// Returns the bounds of a single glyph relative to its origin, see [`Self::get_bounds_vec()`].
+    pub fn glyph_bounds(&self, glyph_id: GlyphId, paint: Option<&Paint>) -> Rect {
+        let mut bounds = [Rect::default()];
+        self.get_bounds(&[glyph_id], &mut bounds, paint);
+        bounds[0]
+    }
+
+    /// Renders the glyph into a raster image.
+    ///
+    /// Works for outline, bitmap and color glyphs. Returns the image and the offset of its top left
+    /// corner relative to the glyph's origin, or `None` if the glyph does not cover any pixels.
+    ///
+    /// If `paint` is `None`, the glyph is drawn in black with anti-aliasing enabled.
+    pub fn glyph_image(&self, glyph_id: GlyphId, paint: Option<&Paint>) -> Option<(Image, IPoint)> {
+        let bounds: IRect = self.glyph_bounds(glyph_id, paint).round_out();
+        if bounds.is_empty() {
+            return None;
+        }
+
+        let blob = TextBlob::from_text(&glyph_id.to_ne_bytes(), TextEncoding::GlyphId, self)?;
+        let mut surface = Surface::new_raster_n32_premul(bounds.size())?;
+        let mut default_paint = Paint::default();
+        default_paint.set_anti_alias(true);
+        let paint = paint.unwrap_or(&default_paint);
+        surface.canvas().draw_text_blob(
+            &blob,
+            Point::new(-bounds.left as scalar, -bounds.top as scalar),
+            paint,
+        );
+        Some((
+            surface.image_snapshot(),
+            IPoint::new(bounds.left, bounds.top),
+        ))
+    }
 
     pub fn metrics(&self) -> (scalar, FontMetrics) {
         let mut line_spacing = 0.0;
@@ -430,3 +527,16 @@ fn test_flags() {
     font.set_embolden(false);
     assert!(!font.is_embolden());
 }
+
+#[test]
+fn get_paths_reports_every_glyph_in_order() {
+    let font = Font::new(Typeface::default(), 16.0);
+    let glyphs = font.str_to_glyphs_vec("ab c");
+    let mut reported = Vec::new();
+    font.get_paths(&glyphs, |glyph_id, _, _| reported.push(glyph_id));
+    assert_eq!(reported, glyphs);
+
+    let paths = font.get_paths_vec(&glyphs);
+    assert_eq!(paths.len(), glyphs.len());
+    assert_eq!(paths[0], font.get_path(glyphs[0]));
+}
@@ -1,7 +1,9 @@
 use crate::prelude::*;
-use skia_bindings::{self as sb, SkFontArguments, SkFontArguments_VariationPosition};
+use skia_bindings::{self as sb, SkFontArguments};
 use std::{fmt, marker::PhantomData, mem};
 
+pub use palette::Palette;
+
 #[derive(Debug)]
 pub struct VariationPosition<'a> {
     pub coordinates: &'a [variation_position::Coordinate],
@@ -26,6 +28,52 @@ pub mod variation_position {
     }
 }
 
+pub mod palette {
+    use crate::Color;
+
+    /// Selects a palette of a color font's `CPAL` table and overrides some of its colors.
+    ///
+    /// Skia m91 does not support palettes in [`super::FontArguments`], they are applied by
+    /// [`crate::Typeface::clone_with_palette()`], which rewrites the `CPAL` table of the font
+    /// data, so that the selected palette becomes the font's default palette.
+    #[derive(Clone, PartialEq, Eq, Default, Debug)]
+    pub struct Palette {
+        /// The index of the palette, palette `0` is used if the index is out of range.
+        pub index: usize,
+        pub overrides: Vec<Override>,
+    }
+
+    /// Replaces the color of a palette entry, overrides of entries that are out of range are
+    /// ignored.
+    #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+    pub struct Override {
+        pub index: u16,
+        pub color: Color,
+    }
+
+    impl Palette {
+        pub fn new(index: usize) -> Self {
+            Self {
+                index,
+                overrides: Vec::new(),
+            }
+        }
+
+        pub fn with_override(mut self, index: u16, color: impl Into<Color>) -> Self {
+            self.overrides.push(Override {
+                index,
+                color: color.into(),
+            });
+            self
+        }
+
+        /// `true` if the palette is the font's default palette without any overrides.
+        pub fn is_default(&self) -> bool {
+            self.index == 0 && self.overrides.is_empty()
+        }
+    }
+}
+
 #[repr(C)]
 pub struct FontArguments<'a> {
     args: SkFontArguments,
@@ -78,14 +126,13 @@ impl FontArguments<'_> {
     // because it borrows the coordinates referenced by VariationPosition.
     //
     // If we would return `Self`, position's Coordinates would not be borrowed.
-    #[cfg(tux_wasm)]
     pub fn set_variation_design_position(mut self, position: VariationPosition) -> FontArguments {
-        let position = SkFontArguments_VariationPosition {
-            coordinates: position.coordinates.native().as_ptr(),
-            coordinateCount: position.coordinates.len().try_into().unwrap(),
-        };
         unsafe {
-            sb::C_SkFontArguments_setVariationDesignPosition(self.native_mut(), position);
+            sb::C_SkFontArguments_setVariationDesignPositionCoordinates(
+                self.native_mut(),
+                position.coordinates.native().as_ptr(),
+                position.coordinates.len().try_into().unwrap(),
+            );
             // note: we are _not_ returning Self here, but VariationPosition with a
             // changed lifetime.
             mem::transmute(self)
@@ -1,4 +1,5 @@
 pub use variation::Axis as VariationAxis;
+pub use variation::NamedInstance as VariationNamedInstance;
 
 pub mod variation {
     use crate::prelude::*;
@@ -44,4 +45,15 @@ pub mod variation {
             self
         }
     }
+
+    /// A named instance of a variable font, for example "Condensed SemiBold".
+    ///
+    /// See [`crate::Typeface::variation_named_instances()`].
+    #[derive(Clone, PartialEq, Debug)]
+    pub struct NamedInstance {
+        /// The localized subfamily name, English if available.
+        pub name: Option<String>,
+        pub post_script_name: Option<String>,
+        pub coordinates: Vec<crate::font_arguments::variation_position::Coordinate>,
+    }
 }
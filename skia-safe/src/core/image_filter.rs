@@ -24,6 +24,10 @@ impl NativeFlattenable for SkImageFilter {
         self.base()
     }
 
+    fn native_flattenable_type() -> sb::SkFlattenable_Type {
+        sb::SkFlattenable_Type::kSkImageFilter_Type
+    }
+
     fn native_deserialize(data: &[u8]) -> *mut Self {
         unsafe { sb::C_SkImageFilter_Deserialize(data.as_ptr() as _, data.len()) }
     }
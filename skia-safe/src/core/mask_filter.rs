@@ -18,6 +18,10 @@ impl NativeFlattenable for SkMaskFilter {
         self.base()
     }
 
+    fn native_flattenable_type() -> sb::SkFlattenable_Type {
+        sb::SkFlattenable_Type::kSkMaskFilter_Type
+    }
+
     fn native_deserialize(data: &[u8]) -> *mut Self {
         unsafe { sb::C_SkMaskFilter_Deserialize(data.as_ptr() as _, data.len()) }
     }
@@ -91,6 +91,10 @@ impl NativeFlattenable for SkPathEffect {
         self.base()
     }
 
+    fn native_flattenable_type() -> sb::SkFlattenable_Type {
+        sb::SkFlattenable_Type::kSkPathEffect_Type
+    }
+
     fn native_deserialize(data: &[u8]) -> *mut Self {
         unsafe { sb::C_SkPathEffect_Deserialize(data.as_ptr() as _, data.len()) }
     }
@@ -1,6 +1,9 @@
-use crate::{prelude::*, Canvas, Data, FilterMode, Matrix, Rect, Shader, TileMode};
+use crate::{
+    prelude::*, AutoCanvasRestore, Canvas, Data, DeserialProcs, FilterMode, Matrix, Rect,
+    SerialProcs, Shader, TileMode,
+};
 use skia_bindings::{self as sb, SkPicture, SkRefCntBase};
-use std::fmt;
+use std::{ffi::c_void, fmt};
 
 pub type Picture = RCHandle<SkPicture>;
 unsafe impl Sync for Picture {}
@@ -24,8 +27,6 @@ impl fmt::Debug for Picture {
 impl Picture {
     // TODO: wrap MakeFromStream
 
-    // TODO: may support SkSerialProces in MakeFromData?
-
     pub fn from_data(data: &Data) -> Option<Picture> {
         Picture::from_ptr(unsafe { sb::C_SkPicture_MakeFromData(data.native()) })
     }
@@ -36,12 +37,73 @@ impl Picture {
         })
     }
 
-    // TODO: AbortCallback and the function that use it.
+    /// Recreates a picture that was serialized with [`Picture::serialize_with_procs()`].
+    pub fn from_data_with_procs(data: &Data, procs: &mut DeserialProcs) -> Option<Picture> {
+        let procs = procs.native();
+        Picture::from_ptr(unsafe { sb::C_SkPicture_MakeFromDataWithProcs(data.native(), &procs) })
+    }
+
+    /// Recreates a picture that was serialized with [`Picture::serialize_with_procs()`].
+    pub fn from_bytes_with_procs(bytes: &[u8], procs: &mut DeserialProcs) -> Option<Picture> {
+        let procs = procs.native();
+        Picture::from_ptr(unsafe {
+            sb::C_SkPicture_MakeFromData2WithProcs(bytes.as_ptr() as _, bytes.len(), &procs)
+        })
+    }
 
     pub fn playback(&self, canvas: &mut Canvas) {
         unsafe { sb::C_SkPicture_playback(self.native(), canvas.native_mut()) }
     }
 
+    /// Replays the drawing commands on the specified canvas, and calls `abort` before each
+    /// command. Playback stops as soon as `abort` returns `true`.
+    ///
+    /// Pictures that consist of a single command may be played back without calling `abort`.
+    pub fn playback_with_abort(&self, canvas: &mut Canvas, mut abort: impl FnMut() -> bool) {
+        let param = abort_param(&mut abort);
+        unsafe { sb::C_SkPicture_playbackWithAbort(self.native(), canvas.native_mut(), &param) }
+    }
+
+    /// Replays the drawing commands that intersect `rect` on the specified canvas.
+    ///
+    /// Only pictures that were recorded with a [`crate::BBHFactory`], like
+    /// [`crate::RTreeFactory`], skip the commands outside of `rect`. Drawing is clipped to `rect`
+    /// in either case.
+    pub fn playback_in_rect(&self, canvas: &mut Canvas, rect: impl AsRef<Rect>) {
+        let mut canvas = AutoCanvasRestore::guard(canvas, true);
+        canvas.clip_rect(rect, None, None);
+        self.playback(&mut canvas);
+    }
+
+    /// Returns the indices of the drawing commands whose bounds intersect `rect`, in ascending
+    /// order.
+    ///
+    /// The bounds are computed by recording the picture again, and they are conservative, so
+    /// commands that do not touch `rect` may be included. If the picture can't be recorded with
+    /// the same commands, for example because it contains nested pictures of a single command,
+    /// these are the indices of all commands if the picture's cull rect intersects `rect`.
+    ///
+    /// This approximates the commands [`Self::playback_in_rect()`] replays for pictures that were
+    /// recorded with an [`crate::RTreeFactory`], but the sets can differ: playback searches with
+    /// the anti-aliased bounds of the clip instead of `rect`, and pictures recorded without a
+    /// [`crate::BBHFactory`] replay all commands.
+    pub fn ops_in_rect(&self, rect: impl AsRef<Rect>) -> Vec<usize> {
+        unsafe extern "C" fn push_op(data: *mut c_void, index: usize) {
+            (*(data as *mut Vec<usize>)).push(index)
+        }
+
+        let mut ops = Vec::new();
+        unsafe {
+            sb::C_SkPicture_opsInRect(
+                self.native(),
+                rect.as_ref().native(),
+                &mut ops as *mut Vec<usize> as *mut c_void,
+                Some(push_op),
+            )
+        }
+        ops
+    }
+
     pub fn cull_rect(&self) -> Rect {
         Rect::from_native_c(unsafe { sb::C_SkPicture_cullRect(self.native()) })
     }
@@ -50,12 +112,16 @@ impl Picture {
         unsafe { sb::C_SkPicture_uniqueID(self.native()) }
     }
 
-    // TODO: support SkSerialProcs in serialize()?
-
     pub fn serialize(&self) -> Data {
         Data::from_ptr(unsafe { sb::C_SkPicture_serialize(self.native()) }).unwrap()
     }
 
+    pub fn serialize_with_procs(&self, procs: &mut SerialProcs) -> Data {
+        let procs = procs.native();
+        Data::from_ptr(unsafe { sb::C_SkPicture_serializeWithProcs(self.native(), &procs) })
+            .unwrap()
+    }
+
     pub fn new_placeholder(cull: impl AsRef<Rect>) -> Picture {
         Picture::from_ptr(unsafe { sb::C_SkPicture_MakePlaceholder(cull.as_ref().native()) })
             .unwrap()
@@ -108,3 +174,69 @@ impl Picture {
         .unwrap()
     }
 }
+
+fn abort_param<F: FnMut() -> bool>(abort: &mut F) -> sb::RustPictureAbortParam {
+    unsafe extern "C" fn abort_trampoline<F: FnMut() -> bool>(data: *mut c_void) -> bool {
+        (*(data as *mut F))()
+    }
+
+    sb::RustPictureAbortParam {
+        data: abort as *mut F as *mut c_void,
+        abort: Some(abort_trampoline::<F>),
+    }
+}
+
+#[test]
+fn playback_with_abort_stops_playback() {
+    use crate::{core::RectCounter, Paint, PictureRecorder};
+
+    let mut recorder = PictureRecorder::new();
+    let canvas = recorder.begin_recording(&Rect::new(0.0, 0.0, 100.0, 100.0), None);
+    for i in 0..10 {
+        canvas.draw_rect(Rect::from_xywh(i as f32, 0.0, 5.0, 5.0), &Paint::default());
+    }
+    let picture = recorder.finish_recording_as_picture(None).unwrap();
+
+    let mut canvas = Canvas::with_backend((100, 100), RectCounter::default());
+    let mut calls = 0;
+    picture.playback_with_abort(&mut canvas, || {
+        calls += 1;
+        calls > 3
+    });
+    assert_eq!(canvas.backend().0, 3);
+
+    let mut canvas = Canvas::with_backend((100, 100), RectCounter::default());
+    picture.playback_with_abort(&mut canvas, || false);
+    assert_eq!(canvas.backend().0, 10);
+}
+
+#[test]
+fn ops_in_rect_with_rtree() {
+    use crate::{core::RectCounter, Paint, PictureRecorder, RTreeFactory};
+
+    fn record(bbh_factory: Option<&mut crate::BBHFactory>) -> Picture {
+        let mut recorder = PictureRecorder::new();
+        let canvas = recorder.begin_recording(&Rect::new(0.0, 0.0, 100.0, 100.0), bbh_factory);
+        for i in 0..10 {
+            let x = i as f32 * 10.0;
+            canvas.draw_rect(Rect::from_xywh(x, 0.0, 5.0, 5.0), &Paint::default());
+        }
+        recorder.finish_recording_as_picture(None).unwrap()
+    }
+
+    let mut rtree = RTreeFactory::new();
+    let picture = record(Some(&mut rtree));
+    let rect = Rect::from_xywh(20.0, 0.0, 25.0, 5.0);
+    assert_eq!(picture.ops_in_rect(&rect), [2, 3, 4]);
+    assert!(picture
+        .ops_in_rect(Rect::from_xywh(0.0, 50.0, 100.0, 50.0))
+        .is_empty());
+
+    let mut canvas = Canvas::with_backend((100, 100), RectCounter::default());
+    picture.playback_in_rect(&mut canvas, &rect);
+    assert_eq!(canvas.backend().0, 3);
+    assert_eq!(canvas.save_count(), 1);
+
+    let picture = record(None);
+    assert_eq!(picture.ops_in_rect(&rect), [2, 3, 4]);
+}
@@ -0,0 +1,272 @@
+use crate::{prelude::*, Data, Image, Picture, Typeface};
+use skia_bindings as sb;
+use std::{ffi::c_void, fmt};
+
+/// Replaces the default serialization of the pictures, images, and typefaces that are referenced
+/// by a serialized [`Picture`] or [`crate::Flattenable`].
+///
+/// Each closure returns the data to embed instead of the object, or `None` to use Skia's default
+/// serialization. Data that was produced by a closure needs to be resolved by the corresponding
+/// closure of [`DeserialProcs`] when it is deserialized, with [`Picture::from_data_with_procs()`]
+/// or [`crate::Flattenable::deserialize_with_procs()`].
+///
+/// Skia can't tell empty data apart from no data, so typefaces that serialize to empty data use
+/// the default serialization.
+#[derive(Default)]
+pub struct SerialProcs<'a> {
+    picture: Option<Box<dyn FnMut(&Picture) -> Option<Data> + 'a>>,
+    image: Option<Box<dyn FnMut(&Image) -> Option<Data> + 'a>>,
+    typeface: Option<Box<dyn FnMut(&Typeface) -> Option<Data> + 'a>>,
+}
+
+impl fmt::Debug for SerialProcs<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SerialProcs")
+            .field("picture", &self.picture.is_some())
+            .field("image", &self.image.is_some())
+            .field("typeface", &self.typeface.is_some())
+            .finish()
+    }
+}
+
+impl<'a> SerialProcs<'a> {
+    pub fn picture(self, picture: impl FnMut(&Picture) -> Option<Data> + 'a) -> Self {
+        Self {
+            picture: Some(Box::new(picture)),
+            ..self
+        }
+    }
+
+    /// Serializes images, for example as a content hash that refers to an image that is
+    /// transmitted separately.
+    pub fn image(self, image: impl FnMut(&Image) -> Option<Data> + 'a) -> Self {
+        Self {
+            image: Some(Box::new(image)),
+            ..self
+        }
+    }
+
+    /// Serializes typefaces, for example as an identifier of a font that is available on both
+    /// sides.
+    pub fn typeface(self, typeface: impl FnMut(&Typeface) -> Option<Data> + 'a) -> Self {
+        Self {
+            typeface: Some(Box::new(typeface)),
+            ..self
+        }
+    }
+
+    /// The returned native procs refer to `self` and must not outlive it.
+    pub(crate) fn native(&mut self) -> sb::RustSerialProcs {
+        unsafe fn procs<'a, 'b>(data: *mut c_void) -> &'a mut SerialProcs<'b> {
+            &mut *(data as *mut SerialProcs)
+        }
+
+        unsafe extern "C" fn picture(
+            data: *mut c_void,
+            picture: *mut sb::SkPicture,
+        ) -> *mut sb::SkData {
+            let picture = Picture::from_unshared_ptr_ref(&picture).as_ref().unwrap();
+            (procs(data).picture.as_mut().unwrap())(picture).into_ptr_or_null()
+        }
+
+        unsafe extern "C" fn image(data: *mut c_void, image: *mut sb::SkImage) -> *mut sb::SkData {
+            let image = Image::from_unshared_ptr_ref(&image).as_ref().unwrap();
+            (procs(data).image.as_mut().unwrap())(image).into_ptr_or_null()
+        }
+
+        unsafe extern "C" fn typeface(
+            data: *mut c_void,
+            typeface: *mut sb::SkTypeface,
+        ) -> *mut sb::SkData {
+            let typeface = Typeface::from_unshared_ptr_ref(&typeface).as_ref().unwrap();
+            (procs(data).typeface.as_mut().unwrap())(typeface).into_ptr_or_null()
+        }
+
+        sb::RustSerialProcs {
+            data: self as *mut SerialProcs as *mut c_void,
+            picture: self.picture.as_ref().map(|_| picture as _),
+            image: self.image.as_ref().map(|_| image as _),
+            typeface: self.typeface.as_ref().map(|_| typeface as _),
+        }
+    }
+}
+
+/// Resolves the pictures, images, and typefaces that were serialized by the closures of
+/// [`SerialProcs`].
+///
+/// Each closure receives the data the corresponding serialization closure returned.
+#[derive(Default)]
+pub struct DeserialProcs<'a> {
+    picture: Option<Box<dyn FnMut(&[u8]) -> Option<Picture> + 'a>>,
+    image: Option<Box<dyn FnMut(&[u8]) -> Option<Image> + 'a>>,
+    typeface: Option<Box<dyn FnMut(&[u8]) -> Option<Typeface> + 'a>>,
+}
+
+impl fmt::Debug for DeserialProcs<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeserialProcs")
+            .field("picture", &self.picture.is_some())
+            .field("image", &self.image.is_some())
+            .field("typeface", &self.typeface.is_some())
+            .finish()
+    }
+}
+
+impl<'a> DeserialProcs<'a> {
+    pub fn picture(self, picture: impl FnMut(&[u8]) -> Option<Picture> + 'a) -> Self {
+        Self {
+            picture: Some(Box::new(picture)),
+            ..self
+        }
+    }
+
+    /// Resolves images. If `None` is returned, Skia tries to decode the data as an encoded image.
+    pub fn image(self, image: impl FnMut(&[u8]) -> Option<Image> + 'a) -> Self {
+        Self {
+            image: Some(Box::new(image)),
+            ..self
+        }
+    }
+
+    pub fn typeface(self, typeface: impl FnMut(&[u8]) -> Option<Typeface> + 'a) -> Self {
+        Self {
+            typeface: Some(Box::new(typeface)),
+            ..self
+        }
+    }
+
+    /// The returned native procs refer to `self` and must not outlive it.
+    pub(crate) fn native(&mut self) -> sb::RustDeserialProcs {
+        unsafe fn procs<'a, 'b>(data: *mut c_void) -> &'a mut DeserialProcs<'b> {
+            &mut *(data as *mut DeserialProcs)
+        }
+
+        unsafe fn bytes<'a>(bytes: *const c_void, length: usize) -> &'a [u8] {
+            if bytes.is_null() {
+                return &[];
+            }
+            safer::from_raw_parts(bytes as *const u8, length)
+        }
+
+        unsafe extern "C" fn picture(
+            data: *mut c_void,
+            bytes_ptr: *const c_void,
+            length: usize,
+        ) -> *mut sb::SkPicture {
+            (procs(data).picture.as_mut().unwrap())(bytes(bytes_ptr, length)).into_ptr_or_null()
+        }
+
+        unsafe extern "C" fn image(
+            data: *mut c_void,
+            bytes_ptr: *const c_void,
+            length: usize,
+        ) -> *mut sb::SkImage {
+            (procs(data).image.as_mut().unwrap())(bytes(bytes_ptr, length)).into_ptr_or_null()
+        }
+
+        unsafe extern "C" fn typeface(
+            data: *mut c_void,
+            bytes_ptr: *const c_void,
+            length: usize,
+        ) -> *mut sb::SkTypeface {
+            (procs(data).typeface.as_mut().unwrap())(bytes(bytes_ptr, length)).into_ptr_or_null()
+        }
+
+        sb::RustDeserialProcs {
+            data: self as *mut DeserialProcs as *mut c_void,
+            picture: self.picture.as_ref().map(|_| picture as _),
+            image: self.image.as_ref().map(|_| image as _),
+            typeface: self.typeface.as_ref().map(|_| typeface as _),
+        }
+    }
+}
+
+#[test]
+fn replace_images_with_ids() {
+    use crate::{Canvas, CanvasBackend, PictureRecorder, Point, Rect, SamplingOptions, Surface};
+    use std::collections::HashMap;
+
+    #[derive(Default)]
+    struct ImageIds(Vec<u32>);
+
+    impl CanvasBackend for ImageIds {
+        fn on_draw_image(
+            &mut self,
+            image: &Image,
+            _left_top: Point,
+            _sampling: &SamplingOptions,
+            _paint: Option<&crate::Paint>,
+        ) {
+            self.0.push(image.unique_id());
+        }
+    }
+
+    let mut surface = Surface::new_raster_n32_premul((64, 64)).unwrap();
+    surface.canvas().clear(crate::Color::RED);
+    let image = surface.image_snapshot();
+
+    let mut recorder = PictureRecorder::new();
+    let canvas = recorder.begin_recording(&Rect::new(0.0, 0.0, 100.0, 100.0), None);
+    canvas.draw_image(&image, (0, 0), None);
+    let picture = recorder.finish_recording_as_picture(None).unwrap();
+
+    let mut images = HashMap::new();
+    let data = picture.serialize_with_procs(&mut SerialProcs::default().image(|image| {
+        images.insert(image.unique_id(), image.clone());
+        Some(Data::new_copy(&image.unique_id().to_le_bytes()))
+    }));
+    assert_eq!(images.len(), 1);
+    assert!(data.size() < picture.serialize().size());
+
+    let mut resolved = 0;
+    let deserialized = Picture::from_data_with_procs(
+        &data,
+        &mut DeserialProcs::default().image(|bytes| {
+            resolved += 1;
+            let mut id = [0; 4];
+            id.copy_from_slice(bytes);
+            images.get(&u32::from_le_bytes(id)).cloned()
+        }),
+    )
+    .unwrap();
+    assert_eq!(resolved, 1);
+
+    let mut canvas = Canvas::with_backend((100, 100), ImageIds::default());
+    deserialized.playback(&mut canvas);
+    assert_eq!(canvas.backend().0, [image.unique_id()]);
+}
+
+#[test]
+fn replace_shader_images_with_ids() {
+    use crate::{Flattenable, SamplingOptions, Shader, Surface};
+
+    let mut surface = Surface::new_raster_n32_premul((64, 64)).unwrap();
+    surface.canvas().clear(crate::Color::RED);
+    let image = surface.image_snapshot();
+    let shader = image
+        .to_shader(None, SamplingOptions::default(), None)
+        .unwrap();
+
+    let mut serialized = 0;
+    let data = shader.serialize_with_procs(&mut SerialProcs::default().image(|image| {
+        serialized += 1;
+        Some(Data::new_copy(&image.unique_id().to_le_bytes()))
+    }));
+    assert_eq!(serialized, 1);
+    assert!(data.size() < shader.serialize().size());
+
+    let mut ids = Vec::new();
+    let deserialized = Shader::deserialize_with_procs(
+        &data,
+        &mut DeserialProcs::default().image(|bytes| {
+            ids.push(bytes.to_vec());
+            Some(image.clone())
+        }),
+    )
+    .unwrap();
+    assert_eq!(ids, [image.unique_id().to_le_bytes().to_vec()]);
+    assert_eq!(
+        deserialized.image().unwrap().0.unique_id(),
+        image.unique_id()
+    );
+}
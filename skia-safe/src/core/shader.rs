@@ -53,6 +53,10 @@ impl NativeFlattenable for SkShader {
         &self.base()
     }
 
+    fn native_flattenable_type() -> sb::SkFlattenable_Type {
+        sb::SkFlattenable_Type::kSkShader_Type
+    }
+
     fn native_deserialize(data: &[u8]) -> *mut Self {
         unsafe { sb::C_SkShader_Deserialize(data.as_ptr() as _, data.len()) }
     }
@@ -1,12 +1,15 @@
 use crate::{
-    font_arguments,
-    font_parameters::VariationAxis,
+    font_arguments::{self, VariationPosition},
+    font_parameters::{VariationAxis, VariationNamedInstance},
     interop::{self, MemoryStream, NativeStreamBase, StreamAsset},
     prelude::*,
-    Data, FontArguments, FontStyle, GlyphId, Rect, Unichar,
+    scalar, Data, Font, FontArguments, FontStyle, GlyphId, IPoint, Image, Path, Rect, Unichar,
 };
 use skia_bindings::{self as sb, SkRefCntBase, SkTypeface, SkTypeface_LocalizedStrings};
 use std::{ffi, fmt, ptr};
+use tables::Table;
+
+pub mod tables;
 
 pub type FontId = skia_bindings::SkFontID;
 pub type FontTableTag = skia_bindings::SkFontTableTag;
@@ -107,6 +110,33 @@ impl Typeface {
         }
     }
 
+    /// Returns the named instances of a variable font, `None` if the typeface has no `fvar` table.
+    pub fn variation_named_instances(&self) -> Option<Vec<VariationNamedInstance>> {
+        let fvar = self.copy_table_data(tables::Fvar::TAG)?;
+        let fvar = tables::Fvar::parse(&fvar)?;
+        let name = self.copy_table_data(tables::Name::TAG);
+        let name = name.as_ref().and_then(|name| tables::Name::parse(name));
+        let find_name = |name_id| name.and_then(|name| name.find(name_id));
+
+        Some(
+            fvar.instances()
+                .map(|instance| VariationNamedInstance {
+                    name: find_name(instance.subfamily_name_id()),
+                    post_script_name: instance.post_script_name_id().and_then(find_name),
+                    coordinates: instance.coordinates(),
+                })
+                .collect(),
+        )
+    }
+
+    /// Creates a typeface positioned at the design coordinates of the named instance.
+    pub fn clone_with_named_instance(&self, instance: &VariationNamedInstance) -> Option<Typeface> {
+        let arguments = FontArguments::new().set_variation_design_position(VariationPosition {
+            coordinates: &instance.coordinates,
+        });
+        self.clone_with_arguments(&arguments)
+    }
+
     pub fn unique_id(&self) -> FontId {
         self.native().fUniqueID
     }
@@ -144,6 +174,26 @@ impl Typeface {
         Typeface::from_ptr(unsafe { sb::C_SkTypeface_makeClone(self.native(), arguments.native()) })
     }
 
+    /// Creates a typeface that uses `palette` as its default palette.
+    ///
+    /// Skia m91 does not support palettes, so this copies the font data and rewrites its `CPAL`
+    /// table. Fonts without a `CPAL` table and the default palette are returned as is.
+    ///
+    /// Returns `None` if the font data is not available or its `CPAL` table is malformed.
+    pub fn clone_with_palette(&self, palette: &font_arguments::Palette) -> Option<Typeface> {
+        if palette.is_default() {
+            return Some(self.clone());
+        }
+        let cpal = match self.copy_table_data(tables::Cpal::TAG) {
+            Some(cpal) => cpal,
+            None => return Some(self.clone()),
+        };
+        let cpal = tables::Cpal::parse(&cpal)?.with_palette(palette)?;
+        let (mut data, ttc_index) = self.to_font_data()?;
+        tables::replace_table(&mut data, ttc_index, tables::Cpal::TAG, &cpal)?;
+        Typeface::from_data(Data::new_copy(&data), ttc_index)
+    }
+
     // TODO: serialize(Write)?
 
     // TODO: return Data as impl Deref<[u8]> / Borrow<[u8]> here?
@@ -208,6 +258,40 @@ impl Typeface {
         Data::from_ptr(unsafe { sb::C_SkTypeface_copyTableData(self.native(), tag) })
     }
 
+    /// Creates a standalone font that contains only the outlines and metrics of the given
+    /// glyphs. Glyph ids are retained, so that already shaped text can be rendered with the
+    /// subset. The missing glyph (`0`) is always included.
+    ///
+    /// Returns `None` if the font data is not available or the font can not be subsetted.
+    ///
+    /// Requires the `textlayout` feature, because Skia uses HarfBuzz to create the subset.
+    #[cfg(feature = "textlayout")]
+    pub fn subset(&self, glyphs: &[GlyphId]) -> Option<Data> {
+        Data::from_ptr(unsafe {
+            sb::C_SkTypeface_subset(self.native(), glyphs.as_ptr(), glyphs.len())
+        })
+    }
+
+    /// Creates a subset that contains the glyphs the typeface's cmap maps `unichars` to.
+    ///
+    /// See [`Self::subset()`].
+    #[cfg(feature = "textlayout")]
+    pub fn subset_unichars(&self, unichars: &[Unichar]) -> Option<Data> {
+        let mut glyphs = vec![0; unichars.len()];
+        self.unichars_to_glyphs(unichars, &mut glyphs);
+        self.subset(&glyphs)
+    }
+
+    /// Creates a subset that contains the glyphs that are needed to render `str` without
+    /// shaping.
+    ///
+    /// See [`Self::subset()`].
+    #[cfg(feature = "textlayout")]
+    pub fn subset_str(&self, str: impl AsRef<str>) -> Option<Data> {
+        let unichars: Vec<Unichar> = str.as_ref().chars().map(|c| c as Unichar).collect();
+        self.subset_unichars(&unichars)
+    }
+
     pub fn units_per_em(&self) -> Option<i32> {
         let units = unsafe { self.native().getUnitsPerEm() };
         if units != 0 {
@@ -266,6 +350,27 @@ impl Typeface {
 
     // TODO: createScalerContext()
 
+    /// Returns the outline of the glyph at the given font size.
+    pub fn glyph_path(&self, glyph_id: GlyphId, size: scalar) -> Option<Path> {
+        Font::from_typeface(self.clone(), size).get_path(glyph_id)
+    }
+
+    /// Returns the outlines of the glyphs at the given font size, `None` for glyphs that have no
+    /// outline.
+    pub fn glyph_paths(&self, glyphs: &[GlyphId], size: scalar) -> Vec<Option<Path>> {
+        Font::from_typeface(self.clone(), size).get_paths_vec(glyphs)
+    }
+
+    /// Returns the bounds of the glyphs at the given font size, relative to their origins.
+    pub fn glyphs_bounds(&self, glyphs: &[GlyphId], size: scalar) -> Vec<Rect> {
+        Font::from_typeface(self.clone(), size).get_bounds_vec(glyphs, None)
+    }
+
+    /// Renders the glyph at the given font size, see [`Font::glyph_image()`].
+    pub fn glyph_image(&self, glyph_id: GlyphId, size: scalar) -> Option<(Image, IPoint)> {
+        Font::from_typeface(self.clone(), size).glyph_image(glyph_id, None)
+    }
+
     pub fn bounds(&self) -> Rect {
         Rect::from_native_c(unsafe { sb::C_SkTypeface_getBounds(self.native()) })
     }
@@ -307,7 +412,8 @@ impl Iterator for LocalizedStringsIter {
 
 #[cfg(test)]
 mod tests {
-    use super::{SerializeBehavior, Typeface};
+    use super::{tables, SerializeBehavior, Typeface};
+    use crate::Data;
 
     #[test]
     fn serialize_and_deserialize_default_typeface() {
@@ -341,6 +447,64 @@ mod tests {
         assert!(any);
     }
 
+    #[test]
+    fn palette_is_ignored_for_fonts_without_cpal_table() {
+        use crate::font_arguments::Palette;
+
+        let tf = Typeface::default();
+        assert!(tf.copy_table_data(tables::Cpal::TAG).is_none());
+        let clone = tf
+            .clone_with_palette(&Palette::new(1).with_override(0, crate::Color::RED))
+            .unwrap();
+        assert_eq!(clone.unique_id(), tf.unique_id());
+    }
+
+    #[test]
+    fn palette_replaces_the_default_palette_of_fonts_with_cpal_table() {
+        use crate::{font_arguments::Palette, Color};
+        use tables::{Cpal, Table};
+
+        #[rustfmt::skip]
+        let cpal: &[u8] = &[
+            0, 0, // version
+            0, 2, 0, 2, 0, 4, // entries per palette, palettes, color records
+            0, 0, 0, 16, // color records offset
+            0, 0, 0, 2, // color record indices
+            0, 0, 255, 255, 0, 255, 0, 255, // palette 0 (BGRA)
+            255, 0, 0, 255, 0, 0, 0, 255, // palette 1 (BGRA)
+        ];
+        let (data, ttc_index) = Typeface::default().to_font_data().unwrap();
+        let data = tables::with_table(&data, ttc_index, Cpal::TAG, cpal).unwrap();
+        let tf = Typeface::from_data(Data::new_copy(&data), None).unwrap();
+
+        let palette = Palette::new(1).with_override(1, Color::WHITE);
+        let clone = tf.clone_with_palette(&palette).unwrap();
+        let cpal = clone.copy_table_data(Cpal::TAG).unwrap();
+        let cpal = Cpal::parse(&cpal).unwrap();
+        assert_eq!(cpal.num_palettes(), 1);
+        assert_eq!(
+            cpal.palette(0).unwrap().colors().collect::<Vec<_>>(),
+            [Color::BLUE, Color::WHITE]
+        );
+        assert_eq!(clone.family_name(), tf.family_name());
+    }
+
+    #[test]
+    #[cfg(feature = "textlayout")]
+    fn subset_of_the_default_typeface_retains_glyph_ids() {
+        let tf = Typeface::default();
+        let subset = tf.subset_str("abc").unwrap();
+        assert!(subset.size() < tf.to_font_data().unwrap().0.len());
+
+        let subset = Typeface::from_data(subset, None).unwrap();
+        let max_glyph = "abc"
+            .chars()
+            .map(|c| tf.unichar_to_glyph(c as _))
+            .max()
+            .unwrap();
+        assert!(subset.count_glyphs() > max_glyph.into());
+    }
+
     #[test]
     fn get_font_data_of_default() {
         let tf = Typeface::default();
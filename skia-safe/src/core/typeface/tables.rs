@@ -0,0 +1,244 @@
+//! Parsers for commonly used OpenType tables.
+//!
+//! The parsers do not copy, they borrow the table data returned by
+//! [`Typeface::copy_table_data()`]:
+//!
+//! ```ignore
+//! use skia_safe::typeface::tables::{Os2, Table};
+//! let data = typeface.copy_table_data(Os2::TAG)?;
+//! let os2 = Os2::parse(&data)?;
+//! let permission = os2.embedding_permission();
+//! ```
+//!
+//! Reference: <https://docs.microsoft.com/en-us/typography/opentype/spec/>
+
+use super::FontTableTag;
+
+mod cmap;
+pub use cmap::*;
+
+mod cpal;
+pub use cpal::*;
+
+mod fvar;
+pub use fvar::*;
+
+mod head;
+pub use head::*;
+
+mod hhea;
+pub use hhea::*;
+
+mod name;
+pub use name::*;
+
+mod os2;
+pub use os2::*;
+
+mod post;
+pub use post::*;
+
+/// A parsed OpenType table that borrows its data.
+pub trait Table<'a>: Sized {
+    const TAG: FontTableTag;
+
+    /// Parses the table, returns `None` if the data is too short or otherwise malformed.
+    fn parse(data: &'a [u8]) -> Option<Self>;
+}
+
+const fn tag(tag: &[u8; 4]) -> FontTableTag {
+    u32::from_be_bytes(*tag)
+}
+
+/// A 16.16 fixed point number converted to a float.
+fn fixed_to_f32(v: i32) -> f32 {
+    v as f32 / 65536.0
+}
+
+fn read_u8(data: &[u8], offset: usize) -> Option<u8> {
+    data.get(offset).copied()
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let b = data.get(offset..offset.checked_add(2)?)?;
+    Some(u16::from_be_bytes([b[0], b[1]]))
+}
+
+fn read_i16(data: &[u8], offset: usize) -> Option<i16> {
+    read_u16(data, offset).map(|v| v as i16)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let b = data.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn read_i32(data: &[u8], offset: usize) -> Option<i32> {
+    read_u32(data, offset).map(|v| v as i32)
+}
+
+fn read_i64(data: &[u8], offset: usize) -> Option<i64> {
+    let b = data.get(offset..offset.checked_add(8)?)?;
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(b);
+    Some(i64::from_be_bytes(bytes))
+}
+
+fn read_fixed(data: &[u8], offset: usize) -> Option<f32> {
+    read_i32(data, offset).map(fixed_to_f32)
+}
+
+fn read_slice(data: &[u8], offset: usize, len: usize) -> Option<&[u8]> {
+    data.get(offset..offset.checked_add(len)?)
+}
+
+const TABLE_RECORD_SIZE: usize = 16;
+
+/// Returns the offset of the table directory of the font at `ttc_index`.
+fn offset_table(font_data: &[u8], ttc_index: usize) -> Option<usize> {
+    const TTC_TAG: FontTableTag = tag(b"ttcf");
+
+    if read_u32(font_data, 0)? == TTC_TAG {
+        Some(read_u32(font_data, 12 + ttc_index * 4)? as usize)
+    } else {
+        Some(0)
+    }
+}
+
+/// The checksum of a table is the sum of the big endian u32s of the zero padded table.
+fn checksum(table: &[u8]) -> u32 {
+    table.chunks(4).fold(0u32, |sum, chunk| {
+        let mut bytes = [0u8; 4];
+        bytes[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(bytes))
+    })
+}
+
+/// Replaces the table `tag` of the font at `ttc_index` in `font_data` by `table`, which must not
+/// be larger than the table it replaces.
+pub(crate) fn replace_table(
+    font_data: &mut [u8],
+    ttc_index: usize,
+    tag: FontTableTag,
+    table: &[u8],
+) -> Option<()> {
+    let offset_table = offset_table(font_data, ttc_index)?;
+    let num_tables = read_u16(font_data, offset_table + 4)? as usize;
+    let record = (0..num_tables)
+        .map(|i| offset_table + 12 + i * TABLE_RECORD_SIZE)
+        .find(|record| read_u32(font_data, *record) == Some(tag))?;
+    let offset = read_u32(font_data, record + 8)? as usize;
+    let length = read_u32(font_data, record + 12)? as usize;
+    if table.len() > length {
+        return None;
+    }
+
+    let target = font_data.get_mut(offset..offset.checked_add(length)?)?;
+    target[..table.len()].copy_from_slice(table);
+    target[table.len()..].iter_mut().for_each(|b| *b = 0);
+    font_data[record + 4..record + 8].copy_from_slice(&checksum(table).to_be_bytes());
+    font_data[record + 12..record + 16].copy_from_slice(&(table.len() as u32).to_be_bytes());
+    Some(())
+}
+
+/// Creates a single font from the font at `ttc_index` in `font_data` that contains `table` as
+/// the table `tag`, in addition to or instead of the font's own.
+#[cfg(test)]
+pub(crate) fn with_table(
+    font_data: &[u8],
+    ttc_index: usize,
+    tag: FontTableTag,
+    table: &[u8],
+) -> Option<Vec<u8>> {
+    let offset_table = offset_table(font_data, ttc_index)?;
+    let num_tables = read_u16(font_data, offset_table + 4)? as usize;
+    let mut tables = Vec::with_capacity(num_tables + 1);
+    for i in 0..num_tables {
+        let record = offset_table + 12 + i * TABLE_RECORD_SIZE;
+        let record_tag = read_u32(font_data, record)?;
+        let offset = read_u32(font_data, record + 8)? as usize;
+        let length = read_u32(font_data, record + 12)? as usize;
+        if record_tag != tag {
+            tables.push((record_tag, read_slice(font_data, offset, length)?));
+        }
+    }
+    tables.push((tag, table));
+    tables.sort_by_key(|(tag, _)| *tag);
+
+    let num_tables = tables.len() as u16;
+    let entry_selector = 15 - num_tables.leading_zeros() as u16;
+    let search_range = (1 << entry_selector) * 16;
+    let mut data = Vec::new();
+    data.extend_from_slice(read_slice(font_data, offset_table, 4)?); // sfnt version
+    data.extend_from_slice(&num_tables.to_be_bytes());
+    data.extend_from_slice(&search_range.to_be_bytes());
+    data.extend_from_slice(&entry_selector.to_be_bytes());
+    data.extend_from_slice(&(num_tables * 16 - search_range).to_be_bytes());
+
+    let mut offset = 12 + tables.len() * TABLE_RECORD_SIZE;
+    for (tag, table) in &tables {
+        data.extend_from_slice(&tag.to_be_bytes());
+        data.extend_from_slice(&checksum(table).to_be_bytes());
+        data.extend_from_slice(&(offset as u32).to_be_bytes());
+        data.extend_from_slice(&(table.len() as u32).to_be_bytes());
+        offset += (table.len() + 3) & !3;
+    }
+    for (_, table) in &tables {
+        data.extend_from_slice(table);
+        data.resize((data.len() + 3) & !3, 0);
+    }
+    Some(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_u32, replace_table, tag, Cmap, Head, Hhea, Name, NameId, Os2, Post, Table};
+    use crate::Typeface;
+
+    #[test]
+    fn parse_tables_of_the_default_typeface() {
+        let tf = Typeface::default();
+
+        let head = tf.copy_table_data(Head::TAG).unwrap();
+        let head = Head::parse(&head).unwrap();
+        assert_eq!(Some(head.units_per_em() as i32), tf.units_per_em());
+
+        let hhea = tf.copy_table_data(Hhea::TAG).unwrap();
+        assert!(Hhea::parse(&hhea).unwrap().number_of_h_metrics() > 0);
+
+        let os2 = tf.copy_table_data(Os2::TAG).unwrap();
+        assert!(Os2::parse(&os2).unwrap().weight_class() > 0);
+
+        let post = tf.copy_table_data(Post::TAG).unwrap();
+        assert!(Post::parse(&post).is_some());
+
+        let name = tf.copy_table_data(Name::TAG).unwrap();
+        let name = Name::parse(&name).unwrap();
+        assert!(name
+            .records()
+            .filter(|r| r.name_id() == NameId::FAMILY)
+            .any(|r| r.string().is_some()));
+
+        let glyph = tf.unichar_to_glyph('A' as _);
+        let cmap = tf.copy_table_data(Cmap::TAG).unwrap();
+        let cmap = Cmap::parse(&cmap).unwrap();
+        assert_eq!(cmap.glyph_id('A' as u32), Some(glyph));
+        assert!(cmap.mappings().any(|(c, g)| c == 'A' as u32 && g == glyph));
+    }
+
+    #[test]
+    fn replace_table_in_font_data() {
+        #[rustfmt::skip]
+        let mut data = vec![
+            0, 1, 0, 0, 0, 1, 0, 16, 0, 0, 0, 0, // offset table with one table
+            b'C', b'P', b'A', b'L', 0, 0, 0, 0, 0, 0, 0, 28, 0, 0, 0, 8, // table record
+            1, 2, 3, 4, 5, 6, 7, 8, // table
+        ];
+        assert!(replace_table(&mut data, 0, tag(b"CPAL"), &[1; 9]).is_none());
+        assert!(replace_table(&mut data, 0, tag(b"COLR"), &[1; 4]).is_none());
+        replace_table(&mut data, 0, tag(b"CPAL"), &[0, 0, 0, 1, 0, 2]).unwrap();
+        assert_eq!(&data[28..], [0, 0, 0, 1, 0, 2, 0, 0]);
+        assert_eq!(read_u32(&data, 16), Some(0x0002_0001));
+        assert_eq!(read_u32(&data, 24), Some(6));
+    }
+}
@@ -0,0 +1,375 @@
+use super::{read_slice, read_u16, read_u32, read_u8, tag, Table};
+use crate::{typeface::FontTableTag, GlyphId, Unichar};
+use std::{cmp::Ordering, convert::TryInto, fmt};
+
+/// The character to glyph index mapping table `cmap`.
+#[derive(Copy, Clone)]
+pub struct Cmap<'a> {
+    data: &'a [u8],
+    num_tables: u16,
+}
+
+impl fmt::Debug for Cmap<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cmap")
+            .field("subtables", &self.subtables().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+const ENCODING_RECORD_SIZE: usize = 8;
+
+impl<'a> Table<'a> for Cmap<'a> {
+    const TAG: FontTableTag = tag(b"cmap");
+
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        let num_tables = read_u16(data, 2)?;
+        read_slice(data, 4, num_tables as usize * ENCODING_RECORD_SIZE)?;
+        Some(Cmap { data, num_tables })
+    }
+}
+
+impl<'a> Cmap<'a> {
+    /// Returns all subtables with a supported format.
+    pub fn subtables(&self) -> impl Iterator<Item = CmapSubtable<'a>> {
+        let cmap = *self;
+        (0..self.num_tables as usize).filter_map(move |i| cmap.subtable(i))
+    }
+
+    fn subtable(&self, index: usize) -> Option<CmapSubtable<'a>> {
+        let record = 4 + index * ENCODING_RECORD_SIZE;
+        let offset = read_u32(self.data, record + 4)? as usize;
+        CmapSubtable::parse(
+            read_u16(self.data, record)?,
+            read_u16(self.data, record + 2)?,
+            self.data.get(offset..)?,
+        )
+    }
+
+    /// Returns the subtable that is preferred for mapping Unicode code points, subtables
+    /// covering the full Unicode range are preferred over the ones covering the BMP only.
+    pub fn unicode_subtable(&self) -> Option<CmapSubtable<'a>> {
+        self.subtables()
+            .filter_map(|st| st.unicode_priority().map(|p| (p, st)))
+            .max_by_key(|(p, _)| *p)
+            .map(|(_, st)| st)
+    }
+
+    /// Maps a Unicode code point to a glyph, using [`Self::unicode_subtable()`].
+    pub fn glyph_id(&self, unichar: impl Into<u32>) -> Option<GlyphId> {
+        self.unicode_subtable()?.glyph_id(unichar)
+    }
+
+    /// All Unicode code points covered by the font and the glyphs they map to, in ascending code
+    /// point order.
+    pub fn mappings(&self) -> Mappings<'a> {
+        match self.unicode_subtable() {
+            Some(subtable) => subtable.mappings(),
+            None => Mappings::empty(),
+        }
+    }
+
+    /// All Unicode code points covered by the font.
+    pub fn coverage(&self) -> impl Iterator<Item = Unichar> + 'a {
+        self.mappings().map(|(c, _)| c as Unichar)
+    }
+}
+
+/// A subtable of the `cmap` table with the format 0, 4, 6, 10, 12 or 13.
+#[derive(Copy, Clone)]
+pub struct CmapSubtable<'a> {
+    platform_id: u16,
+    encoding_id: u16,
+    format: u16,
+    data: &'a [u8],
+}
+
+impl fmt::Debug for CmapSubtable<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CmapSubtable")
+            .field("platform_id", &self.platform_id)
+            .field("encoding_id", &self.encoding_id)
+            .field("format", &self.format)
+            .finish()
+    }
+}
+
+// The highest valid Unicode code point, used to limit the ranges of corrupt tables.
+const MAX_CODE_POINT: u32 = 0x10_FFFF;
+
+impl<'a> CmapSubtable<'a> {
+    fn parse(platform_id: u16, encoding_id: u16, data: &'a [u8]) -> Option<Self> {
+        let format = read_u16(data, 0)?;
+        let header_size = match format {
+            0 => 6 + 256,
+            4 => 14,
+            6 => 10,
+            10 => 20,
+            12 | 13 => 16,
+            _ => return None,
+        };
+        read_slice(data, 0, header_size)?;
+        let subtable = CmapSubtable {
+            platform_id,
+            encoding_id,
+            format,
+            data,
+        };
+        // check that the segment arrays are accessible.
+        let segments = subtable.segment_count();
+        match format {
+            4 => read_slice(data, 16, segments * 8)?,
+            12 | 13 => read_slice(data, 16, segments * 12)?,
+            _ => data,
+        };
+        Some(subtable)
+    }
+
+    pub fn platform_id(&self) -> u16 {
+        self.platform_id
+    }
+
+    pub fn encoding_id(&self) -> u16 {
+        self.encoding_id
+    }
+
+    pub fn format(&self) -> u16 {
+        self.format
+    }
+
+    /// Returns `Some` if the subtable maps Unicode code points, higher values are preferred.
+    pub fn unicode_priority(&self) -> Option<u8> {
+        let full_range = matches!(self.format, 10 | 12 | 13);
+        let priority = match (self.platform_id, self.encoding_id) {
+            (3, 10) | (0, 4) | (0, 6) if full_range => 4,
+            (0, 0..=3) | (3, 1) => 3,
+            (3, 0) => 1,
+            _ => return None,
+        };
+        Some(priority)
+    }
+
+    /// Maps a character code to a glyph, returns `None` if the subtable does not
+    /// contain a mapping for the code or if it maps to the missing glyph.
+    pub fn glyph_id(&self, code: impl Into<u32>) -> Option<GlyphId> {
+        let code = code.into();
+        let segment = self.find_segment(code)?;
+        self.glyph_in_segment(segment, code).filter(|g| *g != 0)
+    }
+
+    /// Iterates all codes with their glyphs in ascending code order. Codes that map to the missing
+    /// glyph are skipped.
+    pub fn mappings(&self) -> Mappings<'a> {
+        Mappings {
+            subtable: Some(*self),
+            segment: 0,
+            code: 0,
+        }
+    }
+
+    fn segment_count(&self) -> usize {
+        match self.format {
+            4 => read_u16(self.data, 6).unwrap() as usize / 2,
+            12 | 13 => read_u32(self.data, 12).unwrap() as usize,
+            _ => 1,
+        }
+    }
+
+    /// The inclusive code range of a segment.
+    fn segment(&self, index: usize) -> Option<(u32, u32)> {
+        let data = self.data;
+        let (start, end) = match self.format {
+            0 => (0, 255),
+            4 => {
+                let segments = self.segment_count();
+                let end = read_u16(data, 14 + index * 2)? as u32;
+                let start = read_u16(data, 16 + segments * 2 + index * 2)? as u32;
+                (start, end)
+            }
+            6 => {
+                let first = read_u16(data, 6)? as u32;
+                let count = read_u16(data, 8)? as u32;
+                if count == 0 {
+                    return None;
+                }
+                (first, first + count - 1)
+            }
+            10 => {
+                let first = read_u32(data, 12)?;
+                let count = read_u32(data, 16)?;
+                if count == 0 {
+                    return None;
+                }
+                (first, first.checked_add(count - 1)?)
+            }
+            12 | 13 => {
+                let group = 16 + index * 12;
+                (read_u32(data, group)?, read_u32(data, group + 4)?)
+            }
+            _ => return None,
+        };
+        if start > end {
+            return None;
+        }
+        Some((start, end.min(MAX_CODE_POINT)))
+    }
+
+    fn find_segment(&self, code: u32) -> Option<usize> {
+        // segments are sorted by their code ranges.
+        let (mut low, mut high) = (0, self.segment_count());
+        while low < high {
+            let mid = (low + high) / 2;
+            let (start, end) = self.segment(mid)?;
+            match (code.cmp(&start), code.cmp(&end)) {
+                (Ordering::Less, _) => high = mid,
+                (_, Ordering::Greater) => low = mid + 1,
+                _ => return Some(mid),
+            }
+        }
+        None
+    }
+
+    fn glyph_in_segment(&self, index: usize, code: u32) -> Option<GlyphId> {
+        let data = self.data;
+        match self.format {
+            0 => read_u8(data, 6 + code as usize).map(GlyphId::from),
+            4 => {
+                let segments = self.segment_count();
+                let start = read_u16(data, 16 + segments * 2 + index * 2)?;
+                let id_delta = read_u16(data, 16 + segments * 4 + index * 2)?;
+                let id_range_offset_pos = 16 + segments * 6 + index * 2;
+                let id_range_offset = read_u16(data, id_range_offset_pos)? as usize;
+                if id_range_offset == 0 {
+                    Some((code as u16).wrapping_add(id_delta))
+                } else {
+                    let glyph_pos = id_range_offset_pos
+                        + id_range_offset
+                        + (code as usize - start as usize) * 2;
+                    match read_u16(data, glyph_pos)? {
+                        0 => None,
+                        glyph => Some(glyph.wrapping_add(id_delta)),
+                    }
+                }
+            }
+            6 => {
+                let first = read_u16(data, 6)? as usize;
+                read_u16(data, 10 + (code as usize - first) * 2)
+            }
+            10 => {
+                let first = read_u32(data, 12)? as usize;
+                read_u16(data, 20 + (code as usize - first) * 2)
+            }
+            12 => {
+                let group = 16 + index * 12;
+                let start = read_u32(data, group)?;
+                let start_glyph = read_u32(data, group + 8)?;
+                let glyph = start_glyph.checked_add(code - start)?;
+                glyph.try_into().ok()
+            }
+            13 => {
+                let glyph = read_u32(data, 16 + index * 12 + 8)?;
+                glyph.try_into().ok()
+            }
+            _ => None,
+        }
+    }
+}
+
+/// An iterator over the `(code, glyph)` pairs of a cmap subtable.
+#[derive(Clone, Debug)]
+pub struct Mappings<'a> {
+    subtable: Option<CmapSubtable<'a>>,
+    segment: usize,
+    code: u32,
+}
+
+impl Mappings<'_> {
+    fn empty() -> Self {
+        Mappings {
+            subtable: None,
+            segment: 0,
+            code: 0,
+        }
+    }
+}
+
+impl Iterator for Mappings<'_> {
+    type Item = (u32, GlyphId);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let subtable = self.subtable?;
+        while self.segment < subtable.segment_count() {
+            let (start, end) = match subtable.segment(self.segment) {
+                Some(range) => range,
+                None => {
+                    self.segment += 1;
+                    continue;
+                }
+            };
+            if self.code < start {
+                self.code = start;
+            }
+            if self.code > end {
+                self.segment += 1;
+                continue;
+            }
+            let code = self.code;
+            self.code += 1;
+            // format 4 tables end with a segment that maps 0xffff to the missing glyph.
+            if subtable.format == 4 && code == 0xffff {
+                continue;
+            }
+            match subtable.glyph_in_segment(self.segment, code) {
+                Some(glyph) if glyph != 0 => return Some((code, glyph)),
+                _ => {}
+            }
+        }
+        self.subtable = None;
+        None
+    }
+}
+
+#[test]
+fn format_4_mappings() {
+    #[rustfmt::skip]
+    let data: &[u8] = &[
+        0, 0, 0, 1, // version, num tables
+        0, 3, 0, 1, 0, 0, 0, 12, // Windows, Unicode BMP
+        0, 4, 0, 42, 0, 0, // format, length, language
+        0, 6, 0, 0, 0, 0, 0, 0, // segCountX2, searchRange, entrySelector, rangeShift
+        0, 0x42, 0, 0x61, 0xff, 0xff, // end codes
+        0, 0, // reserved pad
+        0, 0x41, 0, 0x61, 0xff, 0xff, // start codes
+        0xff, 0xc3, 0, 0, 0, 1, // id deltas
+        0, 0, 0, 4, 0, 0, // id range offsets
+        0, 9, // glyph id array
+    ];
+
+    let cmap = Cmap::parse(data).unwrap();
+    assert_eq!(cmap.subtables().count(), 1);
+    assert_eq!(cmap.glyph_id('A'), Some(4));
+    assert_eq!(cmap.glyph_id('B'), Some(5));
+    assert_eq!(cmap.glyph_id('a'), Some(9));
+    assert_eq!(cmap.glyph_id('C'), None);
+    assert_eq!(
+        cmap.mappings().collect::<Vec<_>>(),
+        [(0x41, 4), (0x42, 5), (0x61, 9)]
+    );
+}
+
+#[test]
+fn format_12_mappings() {
+    #[rustfmt::skip]
+    let data: &[u8] = &[
+        0, 0, 0, 1, // version, num tables
+        0, 3, 0, 10, 0, 0, 0, 12, // Windows, Unicode full repertoire
+        0, 12, 0, 0, 0, 0, 0, 40, 0, 0, 0, 0, // format, reserved, length, language
+        0, 0, 0, 2, // num groups
+        0, 0, 0, 0x30, 0, 0, 0, 0x31, 0, 0, 0, 1,
+        0, 1, 0xf6, 0x00, 0, 1, 0xf6, 0x00, 0, 0, 0, 3,
+    ];
+
+    let cmap = Cmap::parse(data).unwrap();
+    assert_eq!(cmap.glyph_id(0x1f600u32), Some(3));
+    assert_eq!(cmap.coverage().collect::<Vec<_>>(), [0x30, 0x31, 0x1f600]);
+}
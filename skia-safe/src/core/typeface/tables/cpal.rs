@@ -0,0 +1,251 @@
+use super::{read_slice, read_u16, read_u32, tag, NameId, Table};
+use crate::{font_arguments, typeface::FontTableTag, Color};
+use std::fmt;
+
+/// The color palette table `CPAL` of color fonts.
+#[derive(Copy, Clone)]
+pub struct Cpal<'a> {
+    data: &'a [u8],
+    num_palette_entries: usize,
+    num_palettes: usize,
+}
+
+impl fmt::Debug for Cpal<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cpal")
+            .field("palettes", &self.palettes().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+bitflags! {
+    pub struct PaletteType: u32 {
+        const USABLE_WITH_LIGHT_BACKGROUND = 0x0001;
+        const USABLE_WITH_DARK_BACKGROUND = 0x0002;
+    }
+}
+
+const COLOR_RECORD_SIZE: usize = 4;
+const NO_NAME_ID: u16 = 0xffff;
+
+impl<'a> Table<'a> for Cpal<'a> {
+    const TAG: FontTableTag = tag(b"CPAL");
+
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        let version = read_u16(data, 0)?;
+        let num_palettes = read_u16(data, 4)? as usize;
+        let num_color_records = read_u16(data, 6)? as usize;
+        let color_records_offset = read_u32(data, 8)? as usize;
+        let header_size = 12 + num_palettes * 2 + if version >= 1 { 12 } else { 0 };
+        read_slice(data, 0, header_size)?;
+        read_slice(
+            data,
+            color_records_offset,
+            num_color_records * COLOR_RECORD_SIZE,
+        )?;
+        Some(Cpal {
+            data,
+            num_palette_entries: read_u16(data, 2)?.into(),
+            num_palettes,
+        })
+    }
+}
+
+impl<'a> Cpal<'a> {
+    pub fn version(&self) -> u16 {
+        read_u16(self.data, 0).unwrap()
+    }
+
+    /// The number of colors in each palette.
+    pub fn num_palette_entries(&self) -> usize {
+        self.num_palette_entries
+    }
+
+    pub fn num_palettes(&self) -> usize {
+        self.num_palettes
+    }
+
+    pub fn palettes(&self) -> impl Iterator<Item = Palette<'a>> {
+        let cpal = *self;
+        (0..self.num_palettes).filter_map(move |i| cpal.palette(i))
+    }
+
+    pub fn palette(&self, index: usize) -> Option<Palette<'a>> {
+        if index >= self.num_palettes {
+            return None;
+        }
+        let first_color = read_u16(self.data, 12 + index * 2)? as usize;
+        let color_records_offset = read_u32(self.data, 8)? as usize;
+        let colors = read_slice(
+            self.data,
+            color_records_offset + first_color * COLOR_RECORD_SIZE,
+            self.num_palette_entries * COLOR_RECORD_SIZE,
+        )?;
+        Some(Palette {
+            cpal: *self,
+            index,
+            colors,
+        })
+    }
+
+    /// Creates a version 0 table with the single palette `palette` selects.
+    pub(crate) fn with_palette(&self, palette: &font_arguments::Palette) -> Option<Vec<u8>> {
+        let selected = self.palette(palette.index).or_else(|| self.palette(0))?;
+        let mut colors = selected.colors.to_vec();
+        for o in &palette.overrides {
+            let offset = o.index as usize * COLOR_RECORD_SIZE;
+            if let Some(record) = colors.get_mut(offset..offset + COLOR_RECORD_SIZE) {
+                let c = o.color;
+                record.copy_from_slice(&[c.b(), c.g(), c.r(), c.a()]);
+            }
+        }
+
+        let num_entries = self.num_palette_entries as u16;
+        let mut table = Vec::with_capacity(14 + colors.len());
+        table.extend_from_slice(&0u16.to_be_bytes()); // version
+        table.extend_from_slice(&num_entries.to_be_bytes());
+        table.extend_from_slice(&1u16.to_be_bytes()); // palettes
+        table.extend_from_slice(&num_entries.to_be_bytes()); // color records
+        table.extend_from_slice(&14u32.to_be_bytes()); // color records offset
+        table.extend_from_slice(&0u16.to_be_bytes()); // color record index
+        table.extend_from_slice(&colors);
+        Some(table)
+    }
+
+    /// Returns the name id of the palette entry's label, available since version 1.
+    pub fn palette_entry_label(&self, entry: usize) -> Option<NameId> {
+        let offset = self.version_1_array_offset(8)?;
+        read_u16(self.data, offset + entry * 2)
+            .filter(|id| *id != NO_NAME_ID)
+            .map(NameId)
+    }
+
+    /// Offsets to the optional arrays of version 1 tables, `None` if not available.
+    fn version_1_array_offset(&self, field: usize) -> Option<usize> {
+        if self.version() < 1 {
+            return None;
+        }
+        match read_u32(self.data, 12 + self.num_palettes * 2 + field)? {
+            0 => None,
+            offset => Some(offset as usize),
+        }
+    }
+}
+
+/// A color palette of the `CPAL` table.
+#[derive(Copy, Clone)]
+pub struct Palette<'a> {
+    cpal: Cpal<'a>,
+    index: usize,
+    colors: &'a [u8],
+}
+
+impl fmt::Debug for Palette<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Palette")
+            .field("index", &self.index)
+            .field("palette_type", &self.palette_type())
+            .field("label", &self.label())
+            .field("colors", &self.colors().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl<'a> Palette<'a> {
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The unpremultiplied sRGB colors of the palette.
+    pub fn colors(&self) -> impl Iterator<Item = Color> + 'a {
+        self.colors
+            .chunks_exact(COLOR_RECORD_SIZE)
+            .map(|bgra| Color::from_argb(bgra[3], bgra[2], bgra[1], bgra[0]))
+    }
+
+    pub fn color(&self, entry: usize) -> Option<Color> {
+        self.colors().nth(entry)
+    }
+
+    /// Available since version 1, empty for version 0 tables.
+    pub fn palette_type(&self) -> PaletteType {
+        self.cpal
+            .version_1_array_offset(0)
+            .and_then(|offset| read_u32(self.cpal.data, offset + self.index * 4))
+            .map(PaletteType::from_bits_truncate)
+            .unwrap_or_else(PaletteType::empty)
+    }
+
+    /// The name id of the palette's label, available since version 1.
+    pub fn label(&self) -> Option<NameId> {
+        let offset = self.cpal.version_1_array_offset(4)?;
+        read_u16(self.cpal.data, offset + self.index * 2)
+            .filter(|id| *id != NO_NAME_ID)
+            .map(NameId)
+    }
+}
+
+#[test]
+fn palettes() {
+    #[rustfmt::skip]
+    let data: &[u8] = &[
+        0, 1, // version
+        0, 2, 0, 2, 0, 3, // entries per palette, palettes, color records
+        0, 0, 0, 28, // color records offset
+        0, 0, 0, 1, // color record indices
+        0, 0, 0, 40, // palette types
+        0, 0, 0, 48, // palette labels
+        0, 0, 0, 0, // no palette entry labels
+        0, 0, 255, 255, 0, 255, 0, 255, 255, 0, 0, 128, // color records (BGRA)
+        0, 0, 0, 1, 0, 0, 0, 2, // palette types
+        1, 0, 0xff, 0xff, // palette labels
+    ];
+
+    let cpal = Cpal::parse(data).unwrap();
+    let palettes: Vec<_> = cpal.palettes().collect();
+    assert_eq!(palettes.len(), 2);
+    assert_eq!(
+        palettes[0].colors().collect::<Vec<_>>(),
+        [Color::RED, Color::GREEN]
+    );
+    assert_eq!(palettes[1].color(1), Some(Color::from_argb(128, 0, 0, 255)));
+    assert_eq!(
+        palettes[1].palette_type(),
+        PaletteType::USABLE_WITH_DARK_BACKGROUND
+    );
+    assert_eq!(palettes[0].label(), Some(NameId(256)));
+    assert_eq!(palettes[1].label(), None);
+    assert_eq!(cpal.palette_entry_label(0), None);
+}
+
+#[test]
+fn with_palette() {
+    #[rustfmt::skip]
+    let data: &[u8] = &[
+        0, 0, // version
+        0, 2, 0, 2, 0, 4, // entries per palette, palettes, color records
+        0, 0, 0, 16, // color records offset
+        0, 0, 0, 2, // color record indices
+        0, 0, 255, 255, 0, 255, 0, 255, // palette 0 (BGRA)
+        255, 0, 0, 255, 0, 0, 0, 255, // palette 1 (BGRA)
+    ];
+    let cpal = Cpal::parse(data).unwrap();
+
+    let palette = font_arguments::Palette::new(1).with_override(1, Color::WHITE);
+    let table = cpal.with_palette(&palette).unwrap();
+    let cpal = Cpal::parse(&table).unwrap();
+    assert_eq!(cpal.num_palettes(), 1);
+    assert_eq!(
+        cpal.palette(0).unwrap().colors().collect::<Vec<_>>(),
+        [Color::BLUE, Color::WHITE]
+    );
+
+    let table = Cpal::parse(data)
+        .unwrap()
+        .with_palette(&font_arguments::Palette::new(5).with_override(7, Color::WHITE))
+        .unwrap();
+    assert_eq!(
+        Cpal::parse(&table).unwrap().palette(0).unwrap().color(0),
+        Some(Color::RED)
+    );
+}
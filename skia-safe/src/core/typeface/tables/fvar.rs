@@ -0,0 +1,186 @@
+use super::{read_fixed, read_slice, read_u16, read_u32, tag, NameId, Table};
+use crate::{font_arguments::variation_position::Coordinate, typeface::FontTableTag, FourByteTag};
+use std::fmt;
+
+/// The font variations table `fvar`.
+#[derive(Copy, Clone)]
+pub struct Fvar<'a> {
+    data: &'a [u8],
+    axes_offset: usize,
+    axis_count: usize,
+    axis_size: usize,
+    instance_count: usize,
+    instance_size: usize,
+}
+
+impl fmt::Debug for Fvar<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Fvar")
+            .field("axes", &self.axes().collect::<Vec<_>>())
+            .field("instances", &self.instances().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+const AXIS_RECORD_SIZE: usize = 20;
+
+impl<'a> Table<'a> for Fvar<'a> {
+    const TAG: FontTableTag = tag(b"fvar");
+
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        if read_u16(data, 0)? != 1 {
+            return None;
+        }
+        let fvar = Fvar {
+            data,
+            axes_offset: read_u16(data, 4)?.into(),
+            axis_count: read_u16(data, 8)?.into(),
+            axis_size: read_u16(data, 10)?.into(),
+            instance_count: read_u16(data, 12)?.into(),
+            instance_size: read_u16(data, 14)?.into(),
+        };
+        if fvar.axis_size < AXIS_RECORD_SIZE || fvar.instance_size < 4 + fvar.axis_count * 4 {
+            return None;
+        }
+        read_slice(
+            data,
+            fvar.axes_offset,
+            fvar.axis_count * fvar.axis_size + fvar.instance_count * fvar.instance_size,
+        )?;
+        Some(fvar)
+    }
+}
+
+impl<'a> Fvar<'a> {
+    pub fn axis_count(&self) -> usize {
+        self.axis_count
+    }
+
+    pub fn axes(&self) -> impl Iterator<Item = FvarAxis> + 'a {
+        let fvar = *self;
+        (0..self.axis_count).filter_map(move |i| fvar.axis(i))
+    }
+
+    pub fn axis(&self, index: usize) -> Option<FvarAxis> {
+        if index >= self.axis_count {
+            return None;
+        }
+        let offset = self.axes_offset + index * self.axis_size;
+        let data = self.data;
+        Some(FvarAxis {
+            tag: FourByteTag::new(read_u32(data, offset)?),
+            min: read_fixed(data, offset + 4)?,
+            def: read_fixed(data, offset + 8)?,
+            max: read_fixed(data, offset + 12)?,
+            hidden: read_u16(data, offset + 16)? & 0x0001 != 0,
+            name_id: NameId(read_u16(data, offset + 18)?),
+        })
+    }
+
+    pub fn instance_count(&self) -> usize {
+        self.instance_count
+    }
+
+    pub fn instances(&self) -> impl Iterator<Item = NamedInstance<'a>> {
+        let fvar = *self;
+        (0..self.instance_count).filter_map(move |i| fvar.instance(i))
+    }
+
+    pub fn instance(&self, index: usize) -> Option<NamedInstance<'a>> {
+        if index >= self.instance_count {
+            return None;
+        }
+        let offset =
+            self.axes_offset + self.axis_count * self.axis_size + index * self.instance_size;
+        Some(NamedInstance {
+            fvar: *self,
+            data: read_slice(self.data, offset, self.instance_size)?,
+        })
+    }
+}
+
+/// A variation axis record of the `fvar` table.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct FvarAxis {
+    pub tag: FourByteTag,
+    pub min: f32,
+    pub def: f32,
+    pub max: f32,
+    pub hidden: bool,
+    /// The id of the axis name in the `name` table.
+    pub name_id: NameId,
+}
+
+/// A named instance of the `fvar` table, for example "Condensed SemiBold".
+#[derive(Copy, Clone)]
+pub struct NamedInstance<'a> {
+    fvar: Fvar<'a>,
+    data: &'a [u8],
+}
+
+impl fmt::Debug for NamedInstance<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NamedInstance")
+            .field("subfamily_name_id", &self.subfamily_name_id())
+            .field("post_script_name_id", &self.post_script_name_id())
+            .field("coordinates", &self.coordinates())
+            .finish()
+    }
+}
+
+impl NamedInstance<'_> {
+    /// The id of the instance's subfamily name in the `name` table.
+    pub fn subfamily_name_id(&self) -> NameId {
+        NameId(read_u16(self.data, 0).unwrap())
+    }
+
+    /// The id of the instance's PostScript name in the `name` table, if available.
+    pub fn post_script_name_id(&self) -> Option<NameId> {
+        if self.data.len() >= 4 + self.fvar.axis_count * 4 + 2 {
+            read_u16(self.data, 4 + self.fvar.axis_count * 4)
+                .filter(|id| *id != 0xffff)
+                .map(NameId)
+        } else {
+            None
+        }
+    }
+
+    /// The instance's position in the design space, one coordinate for each axis.
+    pub fn coordinates(&self) -> Vec<Coordinate> {
+        self.fvar
+            .axes()
+            .enumerate()
+            .map(|(i, axis)| Coordinate {
+                axis: axis.tag,
+                value: read_fixed(self.data, 4 + i * 4).unwrap(),
+            })
+            .collect()
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn named_instances() {
+    #[rustfmt::skip]
+    let data: &[u8] = &[
+        0, 1, 0, 0, // version
+        0, 16, 0, 2, // axes offset, reserved
+        0, 1, 0, 20, // axis count, axis size
+        0, 2, 0, 10, // instance count, instance size
+        b'w', b'g', b'h', b't', 0, 100, 0, 0, 1, 144, 0, 0, 3, 132, 0, 0, 0, 0, 1, 0,
+        1, 1, 0, 0, 1, 144, 0, 0, 1, 2,
+        1, 3, 0, 0, 2, 188, 0, 0, 0xff, 0xff,
+    ];
+    let fvar = Fvar::parse(data).unwrap();
+    let axis = fvar.axis(0).unwrap();
+    assert_eq!(axis.tag, FourByteTag::from_chars('w', 'g', 'h', 't'));
+    assert_eq!((axis.min, axis.def, axis.max), (100.0, 400.0, 900.0));
+    assert_eq!(axis.name_id, NameId(256));
+
+    let instances: Vec<_> = fvar.instances().collect();
+    assert_eq!(instances.len(), 2);
+    assert_eq!(instances[0].subfamily_name_id(), NameId(257));
+    assert_eq!(instances[0].post_script_name_id(), Some(NameId(258)));
+    assert_eq!(instances[1].coordinates()[0].value, 700.0);
+    assert_eq!(instances[1].post_script_name_id(), None);
+}
@@ -0,0 +1,85 @@
+use super::{read_fixed, read_i16, read_i64, read_slice, read_u16, read_u32, tag, Table};
+use crate::typeface::FontTableTag;
+
+/// The font header table `head`.
+#[derive(Copy, Clone, Debug)]
+pub struct Head<'a> {
+    data: &'a [u8],
+}
+
+bitflags! {
+    pub struct MacStyle: u16 {
+        const BOLD = 1 << 0;
+        const ITALIC = 1 << 1;
+        const UNDERLINE = 1 << 2;
+        const OUTLINE = 1 << 3;
+        const SHADOW = 1 << 4;
+        const CONDENSED = 1 << 5;
+        const EXTENDED = 1 << 6;
+    }
+}
+
+const SIZE: usize = 54;
+const MAGIC_NUMBER: u32 = 0x5F0F_3CF5;
+
+impl<'a> Table<'a> for Head<'a> {
+    const TAG: FontTableTag = tag(b"head");
+
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        read_slice(data, 0, SIZE)?;
+        if read_u32(data, 12)? != MAGIC_NUMBER {
+            return None;
+        }
+        Some(Head { data })
+    }
+}
+
+impl Head<'_> {
+    pub fn font_revision(&self) -> f32 {
+        read_fixed(self.data, 4).unwrap()
+    }
+
+    pub fn flags(&self) -> u16 {
+        self.u16(16)
+    }
+
+    pub fn units_per_em(&self) -> u16 {
+        self.u16(18)
+    }
+
+    /// Seconds since 12:00 midnight, January 1, 1904, UTC.
+    pub fn created(&self) -> i64 {
+        read_i64(self.data, 20).unwrap()
+    }
+
+    /// Seconds since 12:00 midnight, January 1, 1904, UTC.
+    pub fn modified(&self) -> i64 {
+        read_i64(self.data, 28).unwrap()
+    }
+
+    /// The bounding box of all glyphs, `(x_min, y_min, x_max, y_max)` in font units.
+    pub fn bounds(&self) -> (i16, i16, i16, i16) {
+        (self.i16(36), self.i16(38), self.i16(40), self.i16(42))
+    }
+
+    pub fn mac_style(&self) -> MacStyle {
+        MacStyle::from_bits_truncate(self.u16(44))
+    }
+
+    pub fn lowest_rec_ppem(&self) -> u16 {
+        self.u16(46)
+    }
+
+    /// `0` for short offsets, `1` for long offsets in the `loca` table.
+    pub fn index_to_loc_format(&self) -> i16 {
+        self.i16(50)
+    }
+
+    fn u16(&self, offset: usize) -> u16 {
+        read_u16(self.data, offset).unwrap()
+    }
+
+    fn i16(&self, offset: usize) -> i16 {
+        read_i16(self.data, offset).unwrap()
+    }
+}
@@ -0,0 +1,73 @@
+use super::{read_i16, read_slice, read_u16, tag, Table};
+use crate::typeface::FontTableTag;
+
+/// The horizontal header table `hhea`.
+#[derive(Copy, Clone, Debug)]
+pub struct Hhea<'a> {
+    data: &'a [u8],
+}
+
+const SIZE: usize = 36;
+
+impl<'a> Table<'a> for Hhea<'a> {
+    const TAG: FontTableTag = tag(b"hhea");
+
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        read_slice(data, 0, SIZE)?;
+        Some(Hhea { data })
+    }
+}
+
+impl Hhea<'_> {
+    pub fn ascender(&self) -> i16 {
+        self.i16(4)
+    }
+
+    pub fn descender(&self) -> i16 {
+        self.i16(6)
+    }
+
+    pub fn line_gap(&self) -> i16 {
+        self.i16(8)
+    }
+
+    pub fn advance_width_max(&self) -> u16 {
+        self.u16(10)
+    }
+
+    pub fn min_left_side_bearing(&self) -> i16 {
+        self.i16(12)
+    }
+
+    pub fn min_right_side_bearing(&self) -> i16 {
+        self.i16(14)
+    }
+
+    pub fn x_max_extent(&self) -> i16 {
+        self.i16(16)
+    }
+
+    pub fn caret_slope_rise(&self) -> i16 {
+        self.i16(18)
+    }
+
+    pub fn caret_slope_run(&self) -> i16 {
+        self.i16(20)
+    }
+
+    pub fn caret_offset(&self) -> i16 {
+        self.i16(22)
+    }
+
+    pub fn number_of_h_metrics(&self) -> u16 {
+        self.u16(34)
+    }
+
+    fn u16(&self, offset: usize) -> u16 {
+        read_u16(self.data, offset).unwrap()
+    }
+
+    fn i16(&self, offset: usize) -> i16 {
+        read_i16(self.data, offset).unwrap()
+    }
+}
@@ -0,0 +1,272 @@
+use super::{read_slice, read_u16, tag, Table};
+use crate::typeface::FontTableTag;
+use std::{char, fmt};
+
+/// The naming table `name`.
+#[derive(Copy, Clone)]
+pub struct Name<'a> {
+    data: &'a [u8],
+    format: u16,
+    count: u16,
+    storage_offset: u16,
+}
+
+impl fmt::Debug for Name<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Name")
+            .field("format", &self.format)
+            .field("count", &self.count)
+            .finish()
+    }
+}
+
+/// Well known name ids.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct NameId(pub u16);
+
+impl NameId {
+    pub const COPYRIGHT: Self = Self(0);
+    pub const FAMILY: Self = Self(1);
+    pub const SUBFAMILY: Self = Self(2);
+    pub const UNIQUE_ID: Self = Self(3);
+    pub const FULL_NAME: Self = Self(4);
+    pub const VERSION: Self = Self(5);
+    pub const POST_SCRIPT_NAME: Self = Self(6);
+    pub const TRADEMARK: Self = Self(7);
+    pub const MANUFACTURER: Self = Self(8);
+    pub const DESIGNER: Self = Self(9);
+    pub const DESCRIPTION: Self = Self(10);
+    pub const VENDOR_URL: Self = Self(11);
+    pub const DESIGNER_URL: Self = Self(12);
+    pub const LICENSE: Self = Self(13);
+    pub const LICENSE_URL: Self = Self(14);
+    pub const TYPOGRAPHIC_FAMILY: Self = Self(16);
+    pub const TYPOGRAPHIC_SUBFAMILY: Self = Self(17);
+    pub const COMPATIBLE_FULL: Self = Self(18);
+    pub const SAMPLE_TEXT: Self = Self(19);
+    pub const POST_SCRIPT_CID: Self = Self(20);
+    pub const WWS_FAMILY: Self = Self(21);
+    pub const WWS_SUBFAMILY: Self = Self(22);
+    pub const LIGHT_BACKGROUND_PALETTE: Self = Self(23);
+    pub const DARK_BACKGROUND_PALETTE: Self = Self(24);
+    pub const VARIATIONS_POST_SCRIPT_NAME_PREFIX: Self = Self(25);
+}
+
+pub mod platform_id {
+    pub const UNICODE: u16 = 0;
+    pub const MACINTOSH: u16 = 1;
+    pub const WINDOWS: u16 = 3;
+}
+
+const NAME_RECORD_SIZE: usize = 12;
+
+impl<'a> Table<'a> for Name<'a> {
+    const TAG: FontTableTag = tag(b"name");
+
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        let name = Name {
+            data,
+            format: read_u16(data, 0)?,
+            count: read_u16(data, 2)?,
+            storage_offset: read_u16(data, 4)?,
+        };
+        // make sure that all the records are available.
+        read_slice(data, 6, name.count as usize * NAME_RECORD_SIZE)?;
+        Some(name)
+    }
+}
+
+impl<'a> Name<'a> {
+    pub fn format(&self) -> u16 {
+        self.format
+    }
+
+    pub fn count(&self) -> usize {
+        self.count.into()
+    }
+
+    /// Returns all the name records, including the ones with strings that can not be decoded.
+    pub fn records(&self) -> impl Iterator<Item = NameRecord<'a>> {
+        let name = *self;
+        (0..self.count as usize).filter_map(move |i| name.record(i))
+    }
+
+    pub fn record(&self, index: usize) -> Option<NameRecord<'a>> {
+        if index >= self.count() {
+            return None;
+        }
+        let offset = 6 + index * NAME_RECORD_SIZE;
+        let data = self.data;
+        let length = read_u16(data, offset + 8)? as usize;
+        let string_offset = read_u16(data, offset + 10)? as usize;
+        Some(NameRecord {
+            name: *self,
+            platform_id: read_u16(data, offset)?,
+            encoding_id: read_u16(data, offset + 2)?,
+            language_id: read_u16(data, offset + 4)?,
+            name_id: NameId(read_u16(data, offset + 6)?),
+            bytes: self.storage(string_offset, length)?,
+        })
+    }
+
+    /// Returns the first decodable string with the given name id, preferring English records.
+    pub fn find(&self, name_id: NameId) -> Option<String> {
+        let is_english = |r: &NameRecord| match r.platform_id {
+            platform_id::WINDOWS => r.language_id == 0x0409,
+            platform_id::MACINTOSH => r.language_id == 0,
+            _ => false,
+        };
+        let records = || self.records().filter(move |r| r.name_id == name_id);
+        records()
+            .filter(is_english)
+            .find_map(|r| r.string())
+            .or_else(|| records().find_map(|r| r.string()))
+    }
+
+    /// The BCP 47 language tag of a `language_id` >= `0x8000` of a format 1 naming table.
+    pub fn language_tag(&self, language_id: u16) -> Option<String> {
+        if self.format != 1 || language_id < 0x8000 {
+            return None;
+        }
+        let records_end = 6 + self.count() * NAME_RECORD_SIZE;
+        let lang_tag_count = read_u16(self.data, records_end)?;
+        let index = (language_id - 0x8000) as usize;
+        if index >= lang_tag_count as usize {
+            return None;
+        }
+        let offset = records_end + 2 + index * 4;
+        let length = read_u16(self.data, offset)? as usize;
+        let string_offset = read_u16(self.data, offset + 2)? as usize;
+        decode_utf16_be(self.storage(string_offset, length)?)
+    }
+
+    fn storage(&self, offset: usize, length: usize) -> Option<&'a [u8]> {
+        read_slice(self.data, self.storage_offset as usize + offset, length)
+    }
+}
+
+/// A name record of the naming table.
+#[derive(Copy, Clone)]
+pub struct NameRecord<'a> {
+    name: Name<'a>,
+    platform_id: u16,
+    encoding_id: u16,
+    language_id: u16,
+    name_id: NameId,
+    bytes: &'a [u8],
+}
+
+impl fmt::Debug for NameRecord<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NameRecord")
+            .field("platform_id", &self.platform_id)
+            .field("encoding_id", &self.encoding_id)
+            .field("language_id", &self.language_id)
+            .field("name_id", &self.name_id)
+            .field("string", &self.string())
+            .finish()
+    }
+}
+
+impl<'a> NameRecord<'a> {
+    pub fn platform_id(&self) -> u16 {
+        self.platform_id
+    }
+
+    pub fn encoding_id(&self) -> u16 {
+        self.encoding_id
+    }
+
+    /// The platform specific language id, for example a Windows LCID like `0x0409` (en-US).
+    pub fn language_id(&self) -> u16 {
+        self.language_id
+    }
+
+    /// The BCP 47 language tag, available only for language tag records of format 1 naming
+    /// tables.
+    pub fn language_tag(&self) -> Option<String> {
+        self.name.language_tag(self.language_id)
+    }
+
+    pub fn name_id(&self) -> NameId {
+        self.name_id
+    }
+
+    /// The raw, encoded bytes of the string.
+    pub fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Decodes the string.
+    ///
+    /// Supports the Unicode and Windows platforms (UTF-16BE) and the Macintosh platform with
+    /// the Roman encoding. Returns `None` for all other encodings.
+    pub fn string(&self) -> Option<String> {
+        match (self.platform_id, self.encoding_id) {
+            (platform_id::UNICODE, _)
+            | (platform_id::WINDOWS, 0..=1)
+            | (platform_id::WINDOWS, 10) => decode_utf16_be(self.bytes),
+            (platform_id::MACINTOSH, 0) => Some(decode_mac_roman(self.bytes)),
+            _ => None,
+        }
+    }
+}
+
+fn decode_utf16_be(bytes: &[u8]) -> Option<String> {
+    if bytes.len() % 2 != 0 {
+        return None;
+    }
+    let units = bytes
+        .chunks_exact(2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]));
+    char::decode_utf16(units)
+        .collect::<Result<String, _>>()
+        .ok()
+}
+
+fn decode_mac_roman(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&b| {
+            if b < 0x80 {
+                b as char
+            } else {
+                MAC_ROMAN_HIGH[(b - 0x80) as usize]
+            }
+        })
+        .collect()
+}
+
+const MAC_ROMAN_HIGH: [char; 128] = [
+    'Ä', 'Å', 'Ç', 'É', 'Ñ', 'Ö', 'Ü', 'á', 'à', 'â', 'ä', 'ã', 'å', 'ç', 'é', 'è', //
+    'ê', 'ë', 'í', 'ì', 'î', 'ï', 'ñ', 'ó', 'ò', 'ô', 'ö', 'õ', 'ú', 'ù', 'û', 'ü', //
+    '†', '°', '¢', '£', '§', '•', '¶', 'ß', '®', '©', '™', '´', '¨', '≠', 'Æ', 'Ø', //
+    '∞', '±', '≤', '≥', '¥', 'µ', '∂', '∑', '∏', 'π', '∫', 'ª', 'º', 'Ω', 'æ', 'ø', //
+    '¿', '¡', '¬', '√', 'ƒ', '≈', '∆', '«', '»', '…', '\u{a0}', 'À', 'Ã', 'Õ', 'Œ', 'œ', //
+    '–', '—', '“', '”', '‘', '’', '÷', '◊', 'ÿ', 'Ÿ', '⁄', '€', '‹', '›', 'ﬁ', 'ﬂ', //
+    '‡', '·', '‚', '„', '‰', 'Â', 'Ê', 'Á', 'Ë', 'È', 'Í', 'Î', 'Ï', 'Ì', 'Ó', 'Ô', //
+    '\u{f8ff}', 'Ò', 'Ú', 'Û', 'Ù', 'ı', 'ˆ', '˜', '¯', '˘', '˙', '˚', '¸', '˝', '˛', 'ˇ', //
+];
+
+#[test]
+fn parse_name_table() {
+    #[rustfmt::skip]
+    let data: &[u8] = &[
+        0, 0, // format
+        0, 2, // count
+        0, 30, // storage offset
+        // Windows, Unicode BMP, en-US, family
+        0, 3, 0, 1, 0x04, 0x09, 0, 1, 0, 4, 0, 0,
+        // Macintosh, Roman, English, family
+        0, 1, 0, 0, 0, 0, 0, 1, 0, 2, 0, 4,
+        // storage
+        0, b'A', 0, b'b', b'X', 0x8a,
+    ];
+    let name = Name::parse(data).unwrap();
+    let records: Vec<_> = name.records().collect();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].string().unwrap(), "Ab");
+    assert_eq!(records[1].string().unwrap(), "Xä");
+    assert_eq!(name.find(NameId::FAMILY).unwrap(), "Ab");
+    assert_eq!(name.find(NameId::FULL_NAME), None);
+}
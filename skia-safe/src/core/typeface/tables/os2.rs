@@ -0,0 +1,253 @@
+use super::{read_i16, read_slice, read_u16, read_u32, tag, Table};
+use crate::typeface::FontTableTag;
+
+/// The OS/2 and Windows metrics table `OS/2`.
+#[derive(Copy, Clone, Debug)]
+pub struct Os2<'a> {
+    data: &'a [u8],
+}
+
+/// The embedding licensing rights of a font, see [`Os2::embedding_permission()`].
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum EmbeddingPermission {
+    /// The font may be embedded and permanently installed on the remote system.
+    Installable,
+    /// The font must not be embedded, modified or exchanged without permission from the legal
+    /// owner.
+    Restricted,
+    /// The font may be embedded and temporarily loaded for viewing and printing only.
+    PreviewAndPrint,
+    /// The font may be embedded and temporarily loaded for viewing, printing and editing.
+    Editable,
+}
+
+bitflags! {
+    /// Additional embedding restrictions, see [`Os2::embedding_flags()`].
+    pub struct EmbeddingFlags: u16 {
+        /// The font must not be subsetted prior to embedding.
+        const NO_SUBSETTING = 0x0100;
+        /// Only bitmaps contained in the font may be embedded.
+        const BITMAP_EMBEDDING_ONLY = 0x0200;
+    }
+}
+
+bitflags! {
+    pub struct FsSelection: u16 {
+        const ITALIC = 1 << 0;
+        const UNDERSCORE = 1 << 1;
+        const NEGATIVE = 1 << 2;
+        const OUTLINED = 1 << 3;
+        const STRIKEOUT = 1 << 4;
+        const BOLD = 1 << 5;
+        const REGULAR = 1 << 6;
+        const USE_TYPO_METRICS = 1 << 7;
+        const WWS = 1 << 8;
+        const OBLIQUE = 1 << 9;
+    }
+}
+
+/// The size of the version 0 table.
+const VERSION_0_SIZE: usize = 78;
+
+impl<'a> Table<'a> for Os2<'a> {
+    const TAG: FontTableTag = tag(b"OS/2");
+
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        read_slice(data, 0, VERSION_0_SIZE)?;
+        Some(Os2 { data })
+    }
+}
+
+impl Os2<'_> {
+    pub fn version(&self) -> u16 {
+        self.u16(0)
+    }
+
+    pub fn x_avg_char_width(&self) -> i16 {
+        self.i16(2)
+    }
+
+    /// The visual weight class, `400` is regular and `700` is bold.
+    pub fn weight_class(&self) -> u16 {
+        self.u16(4)
+    }
+
+    /// The width class, `1` (ultra-condensed) to `9` (ultra-expanded), `5` is normal.
+    pub fn width_class(&self) -> u16 {
+        self.u16(6)
+    }
+
+    /// The raw `fsType` field.
+    pub fn fs_type(&self) -> u16 {
+        self.u16(8)
+    }
+
+    /// Returns the least restrictive embedding permission defined by the `fsType` field.
+    pub fn embedding_permission(&self) -> EmbeddingPermission {
+        let fs_type = self.fs_type();
+        if fs_type & 0x000f == 0 {
+            EmbeddingPermission::Installable
+        } else if fs_type & 0x0008 != 0 {
+            EmbeddingPermission::Editable
+        } else if fs_type & 0x0004 != 0 {
+            EmbeddingPermission::PreviewAndPrint
+        } else {
+            EmbeddingPermission::Restricted
+        }
+    }
+
+    pub fn embedding_flags(&self) -> EmbeddingFlags {
+        EmbeddingFlags::from_bits_truncate(self.fs_type())
+    }
+
+    pub fn subscript_size(&self) -> (i16, i16) {
+        (self.i16(10), self.i16(12))
+    }
+
+    pub fn subscript_offset(&self) -> (i16, i16) {
+        (self.i16(14), self.i16(16))
+    }
+
+    pub fn superscript_size(&self) -> (i16, i16) {
+        (self.i16(18), self.i16(20))
+    }
+
+    pub fn superscript_offset(&self) -> (i16, i16) {
+        (self.i16(22), self.i16(24))
+    }
+
+    pub fn strikeout_size(&self) -> i16 {
+        self.i16(26)
+    }
+
+    pub fn strikeout_position(&self) -> i16 {
+        self.i16(28)
+    }
+
+    pub fn family_class(&self) -> i16 {
+        self.i16(30)
+    }
+
+    pub fn panose(&self) -> [u8; 10] {
+        let mut panose = [0u8; 10];
+        panose.copy_from_slice(&self.data[32..42]);
+        panose
+    }
+
+    /// The four 32 bit `ulUnicodeRange` fields, the bit assignments are defined in the OpenType
+    /// specification.
+    pub fn unicode_ranges(&self) -> [u32; 4] {
+        [self.u32(42), self.u32(46), self.u32(50), self.u32(54)]
+    }
+
+    /// Returns `true` if the Unicode range with the bit number `bit` (0 to 127) is
+    /// considered functional.
+    pub fn has_unicode_range(&self, bit: usize) -> bool {
+        bit < 128 && self.unicode_ranges()[bit / 32] & (1 << (bit % 32)) != 0
+    }
+
+    pub fn vendor_id(&self) -> [u8; 4] {
+        let mut id = [0u8; 4];
+        id.copy_from_slice(&self.data[58..62]);
+        id
+    }
+
+    pub fn fs_selection(&self) -> FsSelection {
+        FsSelection::from_bits_truncate(self.u16(62))
+    }
+
+    pub fn first_char_index(&self) -> u16 {
+        self.u16(64)
+    }
+
+    pub fn last_char_index(&self) -> u16 {
+        self.u16(66)
+    }
+
+    pub fn typo_ascender(&self) -> i16 {
+        self.i16(68)
+    }
+
+    pub fn typo_descender(&self) -> i16 {
+        self.i16(70)
+    }
+
+    pub fn typo_line_gap(&self) -> i16 {
+        self.i16(72)
+    }
+
+    pub fn win_ascent(&self) -> u16 {
+        self.u16(74)
+    }
+
+    pub fn win_descent(&self) -> u16 {
+        self.u16(76)
+    }
+
+    /// The two 32 bit `ulCodePageRange` fields, available since version 1.
+    pub fn code_page_ranges(&self) -> Option<[u32; 2]> {
+        Some([read_u32(self.data, 78)?, read_u32(self.data, 82)?])
+    }
+
+    /// Available since version 2.
+    pub fn x_height(&self) -> Option<i16> {
+        read_i16(self.data, 86)
+    }
+
+    /// Available since version 2.
+    pub fn cap_height(&self) -> Option<i16> {
+        read_i16(self.data, 88)
+    }
+
+    /// Available since version 2.
+    pub fn default_char(&self) -> Option<u16> {
+        read_u16(self.data, 90)
+    }
+
+    /// Available since version 2.
+    pub fn break_char(&self) -> Option<u16> {
+        read_u16(self.data, 92)
+    }
+
+    /// Available since version 2.
+    pub fn max_context(&self) -> Option<u16> {
+        read_u16(self.data, 94)
+    }
+
+    /// The lower and upper optical point size in TWIPs, available since version 5.
+    pub fn optical_point_size_range(&self) -> Option<(u16, u16)> {
+        Some((read_u16(self.data, 96)?, read_u16(self.data, 98)?))
+    }
+
+    fn u16(&self, offset: usize) -> u16 {
+        read_u16(self.data, offset).unwrap()
+    }
+
+    fn i16(&self, offset: usize) -> i16 {
+        read_i16(self.data, offset).unwrap()
+    }
+
+    fn u32(&self, offset: usize) -> u32 {
+        read_u32(self.data, offset).unwrap()
+    }
+}
+
+#[test]
+fn embedding_permissions() {
+    let mut data = [0u8; VERSION_0_SIZE];
+    let permission = |data: &[u8]| Os2::parse(data).unwrap().embedding_permission();
+
+    assert_eq!(permission(&data), EmbeddingPermission::Installable);
+    data[9] = 0x02;
+    assert_eq!(permission(&data), EmbeddingPermission::Restricted);
+    data[9] = 0x06;
+    assert_eq!(permission(&data), EmbeddingPermission::PreviewAndPrint);
+    data[9] = 0x08;
+    data[8] = 0x03;
+    assert_eq!(permission(&data), EmbeddingPermission::Editable);
+    assert_eq!(
+        Os2::parse(&data).unwrap().embedding_flags(),
+        EmbeddingFlags::NO_SUBSETTING | EmbeddingFlags::BITMAP_EMBEDDING_ONLY
+    );
+    assert!(Os2::parse(&data[..VERSION_0_SIZE - 1]).is_none());
+}
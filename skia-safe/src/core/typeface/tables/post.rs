@@ -0,0 +1,134 @@
+use super::{read_fixed, read_i16, read_slice, read_u16, read_u32, read_u8, tag, Table};
+use crate::{typeface::FontTableTag, GlyphId};
+use std::str;
+
+/// The PostScript table `post`.
+#[derive(Copy, Clone, Debug)]
+pub struct Post<'a> {
+    data: &'a [u8],
+}
+
+const HEADER_SIZE: usize = 32;
+
+impl<'a> Table<'a> for Post<'a> {
+    const TAG: FontTableTag = tag(b"post");
+
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        read_slice(data, 0, HEADER_SIZE)?;
+        Some(Post { data })
+    }
+}
+
+impl<'a> Post<'a> {
+    /// The version of the table, `1.0`, `2.0`, `2.5` or `3.0`.
+    pub fn version(&self) -> f32 {
+        // the version is not a regular 16.16 fixed number, for example 2.5 is stored as 0x00025000.
+        let version = read_u32(self.data, 0).unwrap();
+        (version >> 16) as f32 + ((version >> 12) & 0xf) as f32 / 10.0
+    }
+
+    /// The italic angle in counter-clockwise degrees from the vertical.
+    pub fn italic_angle(&self) -> f32 {
+        read_fixed(self.data, 4).unwrap()
+    }
+
+    pub fn underline_position(&self) -> i16 {
+        read_i16(self.data, 8).unwrap()
+    }
+
+    pub fn underline_thickness(&self) -> i16 {
+        read_i16(self.data, 10).unwrap()
+    }
+
+    pub fn is_fixed_pitch(&self) -> bool {
+        read_u32(self.data, 12).unwrap() != 0
+    }
+
+    /// Returns the PostScript name of the glyph.
+    ///
+    /// Glyph names are available for version `1.0` and `2.0` tables only.
+    pub fn glyph_name(&self, glyph_id: GlyphId) -> Option<&'a str> {
+        let version = read_u32(self.data, 0)?;
+        match version {
+            0x0001_0000 => STANDARD_MAC_GLYPH_NAMES.get(glyph_id as usize).copied(),
+            0x0002_0000 => self.glyph_name_v2(glyph_id),
+            _ => None,
+        }
+    }
+
+    fn glyph_name_v2(&self, glyph_id: GlyphId) -> Option<&'a str> {
+        let data = self.data;
+        let num_glyphs = read_u16(data, HEADER_SIZE)?;
+        if glyph_id >= num_glyphs {
+            return None;
+        }
+        let index = read_u16(data, HEADER_SIZE + 2 + glyph_id as usize * 2)? as usize;
+        if index < STANDARD_MAC_GLYPH_NAMES.len() {
+            return Some(STANDARD_MAC_GLYPH_NAMES[index]);
+        }
+
+        // skip the pascal strings before the requested one.
+        let mut offset = HEADER_SIZE + 2 + num_glyphs as usize * 2;
+        for _ in 0..index - STANDARD_MAC_GLYPH_NAMES.len() {
+            offset += 1 + read_u8(data, offset)? as usize;
+        }
+        let len = read_u8(data, offset)? as usize;
+        str::from_utf8(read_slice(data, offset + 1, len)?).ok()
+    }
+}
+
+#[rustfmt::skip]
+const STANDARD_MAC_GLYPH_NAMES: [&str; 258] = [
+    ".notdef", ".null", "nonmarkingreturn", "space", "exclam", "quotedbl", "numbersign", "dollar",
+    "percent", "ampersand", "quotesingle", "parenleft", "parenright", "asterisk", "plus", "comma",
+    "hyphen", "period", "slash", "zero", "one", "two", "three", "four", "five", "six", "seven",
+    "eight", "nine", "colon", "semicolon", "less", "equal", "greater", "question", "at", "A", "B",
+    "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M", "N", "O", "P", "Q", "R", "S", "T", "U",
+    "V", "W", "X", "Y", "Z", "bracketleft", "backslash", "bracketright", "asciicircum",
+    "underscore", "grave", "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n",
+    "o", "p", "q", "r", "s", "t", "u", "v", "w", "x", "y", "z", "braceleft", "bar", "braceright",
+    "asciitilde", "Adieresis", "Aring", "Ccedilla", "Eacute", "Ntilde", "Odieresis", "Udieresis",
+    "aacute", "agrave", "acircumflex", "adieresis", "atilde", "aring", "ccedilla", "eacute",
+    "egrave", "ecircumflex", "edieresis", "iacute", "igrave", "icircumflex", "idieresis", "ntilde",
+    "oacute", "ograve", "ocircumflex", "odieresis", "otilde", "uacute", "ugrave", "ucircumflex",
+    "udieresis", "dagger", "degree", "cent", "sterling", "section", "bullet", "paragraph",
+    "germandbls", "registered", "copyright", "trademark", "acute", "dieresis", "notequal", "AE",
+    "Oslash", "infinity", "plusminus", "lessequal", "greaterequal", "yen", "mu", "partialdiff",
+    "summation", "product", "pi", "integral", "ordfeminine", "ordmasculine", "Omega", "ae",
+    "oslash", "questiondown", "exclamdown", "logicalnot", "radical", "florin", "approxequal",
+    "Delta", "guillemotleft", "guillemotright", "ellipsis", "nonbreakingspace", "Agrave", "Atilde",
+    "Otilde", "OE", "oe", "endash", "emdash", "quotedblleft", "quotedblright", "quoteleft",
+    "quoteright", "divide", "lozenge", "ydieresis", "Ydieresis", "fraction", "currency",
+    "guilsinglleft", "guilsinglright", "fi", "fl", "daggerdbl", "periodcentered", "quotesinglbase",
+    "quotedblbase", "perthousand", "Acircumflex", "Ecircumflex", "Aacute", "Edieresis", "Egrave",
+    "Iacute", "Icircumflex", "Idieresis", "Igrave", "Oacute", "Ocircumflex", "apple", "Ograve",
+    "Uacute", "Ucircumflex", "Ugrave", "dotlessi", "circumflex", "tilde", "macron", "breve",
+    "dotaccent", "ring", "cedilla", "hungarumlaut", "ogonek", "caron", "Lslash", "lslash",
+    "Scaron", "scaron", "Zcaron", "zcaron", "brokenbar", "Eth", "eth", "Yacute", "yacute", "Thorn",
+    "thorn", "minus", "multiply", "onesuperior", "twosuperior", "threesuperior", "onehalf",
+    "onequarter", "threequarters", "franc", "Gbreve", "gbreve", "Idotaccent", "Scedilla",
+    "scedilla", "Cacute", "cacute", "Ccaron", "ccaron", "dcroat",
+];
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn glyph_names_of_a_version_2_table() {
+    #[rustfmt::skip]
+    let data: &[u8] = &[
+        0, 2, 0, 0, // version
+        0xff, 0xf1, 0, 0, // italic angle -15
+        0, 0, 0, 0, 0, 0, 0, 1, // underline, fixed pitch
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 3, // num glyphs
+        0, 0, 1, 3, 1, 2, // glyph name indices
+        2, b'a', b'b', 3, b'x', b'y', b'z',
+    ];
+    let post = Post::parse(data).unwrap();
+    assert_eq!(post.version(), 2.0);
+    assert_eq!(post.italic_angle(), -15.0);
+    assert!(post.is_fixed_pitch());
+    assert_eq!(post.glyph_name(0), Some(".notdef"));
+    assert_eq!(post.glyph_name(1), Some("xyz"));
+    assert_eq!(post.glyph_name(2), Some("ab"));
+    assert_eq!(post.glyph_name(3), None);
+}
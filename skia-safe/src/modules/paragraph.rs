@@ -4,9 +4,18 @@ use std::ops::Index;
 mod dart_types;
 pub use dart_types::*;
 
+mod editor;
+pub use editor::*;
+
 mod font_collection;
 pub use font_collection::*;
 
+mod line_breaker;
+pub use line_breaker::*;
+
+mod markup;
+pub use markup::*;
+
 mod metrics;
 pub use metrics::*;
 
@@ -20,6 +29,9 @@ pub use paragraph_builder::*;
 mod paragraph_cache;
 pub use paragraph_cache::*;
 
+mod paragraph_painter;
+pub use paragraph_painter::*;
+
 mod paragraph_style;
 pub use paragraph_style::*;
 
@@ -50,3 +62,28 @@ impl FontFamilies<'_> {
         self.0.iter().map(|str| str.as_str())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{FontCollection, Paragraph, ParagraphBuilder, ParagraphStyle, TextStyle};
+    use crate::{icu, scalar, FontMgr};
+
+    /// Initializes ICU and returns a builder that uses the default font manager.
+    pub fn builder() -> ParagraphBuilder {
+        icu::init();
+
+        let mut font_collection = FontCollection::new();
+        font_collection.set_default_font_manager(FontMgr::new(), None);
+        ParagraphBuilder::new(&ParagraphStyle::new(), font_collection)
+    }
+
+    /// Lays out `text` in the default text style.
+    pub fn layout(text: &str, width: scalar) -> Paragraph {
+        let mut builder = builder();
+        builder.push_style(&TextStyle::new());
+        builder.add_text(text);
+        let mut paragraph = builder.build();
+        paragraph.layout(width);
+        paragraph
+    }
+}
@@ -0,0 +1,426 @@
+use super::{Paragraph, RectHeightStyle, RectWidthStyle, TextDirection};
+use crate::{prelude::*, scalar, Point, Rect};
+use skia_bindings as sb;
+use std::{ffi::c_void, ops::Range};
+
+/// A selection in the text of a paragraph.
+///
+/// `anchor` and `focus` are UTF-8 offsets. The focus is the end that moves when the selection is
+/// extended, and where the caret is drawn.
+#[derive(Copy, Clone, PartialEq, Default, Debug)]
+pub struct Selection {
+    pub anchor: usize,
+    pub focus: usize,
+    /// The horizontal caret position that is kept while moving up and down between lines.
+    pub preferred_x: Option<scalar>,
+}
+
+impl Selection {
+    pub fn caret(offset: usize) -> Self {
+        Selection {
+            anchor: offset,
+            focus: offset,
+            preferred_x: None,
+        }
+    }
+
+    pub fn is_collapsed(&self) -> bool {
+        self.anchor == self.focus
+    }
+
+    pub fn range(&self) -> Range<usize> {
+        self.anchor.min(self.focus)..self.anchor.max(self.focus)
+    }
+}
+
+/// Caret movements for [`Editor::move_selection()`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Movement {
+    /// To the previous grapheme boundary in logical order.
+    GraphemeBackward,
+    /// To the next grapheme boundary in logical order.
+    GraphemeForward,
+    /// To the start of the current or previous word.
+    WordBackward,
+    /// To the end of the current or next word.
+    WordForward,
+    LineStart,
+    LineEnd,
+    /// To the line above, keeping the horizontal position.
+    LineUp,
+    /// To the line below, keeping the horizontal position.
+    LineDown,
+    TextStart,
+    TextEnd,
+}
+
+/// Caret navigation and selection for the text of a laid out [`Paragraph`].
+///
+/// All offsets are UTF-8 offsets into the paragraph's [`Paragraph::text()`]. Grapheme boundaries
+/// are computed with ICU's character break iterator, word and line boundaries are taken from the
+/// paragraph, which computes them with ICU, too.
+///
+/// The editor captures the layout of the paragraph, create a new one after the paragraph was
+/// changed or laid out again.
+#[derive(Debug)]
+pub struct Editor<'a> {
+    paragraph: &'a Paragraph,
+    text: &'a str,
+    grapheme_boundaries: Vec<usize>,
+    /// The UTF-8 and UTF-16 offsets of all character boundaries, including the end of the text.
+    char_boundaries: Vec<(usize, usize)>,
+    lines: Vec<Line>,
+}
+
+#[derive(Clone, Debug)]
+struct Line {
+    range: Range<usize>,
+    left: scalar,
+    top: scalar,
+    baseline: scalar,
+    bottom: scalar,
+}
+
+impl<'a> Editor<'a> {
+    /// Creates an editor for `paragraph`, which must have been laid out.
+    pub fn new(paragraph: &'a Paragraph) -> Self {
+        let text = paragraph.text();
+        let mut char_boundaries = Vec::with_capacity(text.len() + 1);
+        let mut utf16 = 0;
+        for (utf8, c) in text.char_indices() {
+            char_boundaries.push((utf8, utf16));
+            utf16 += c.len_utf16();
+        }
+        char_boundaries.push((text.len(), utf16));
+
+        let grapheme_boundaries = grapheme_boundaries(text)
+            .unwrap_or_else(|| char_boundaries.iter().map(|(utf8, _)| *utf8).collect());
+
+        let line_metrics = paragraph.get_line_metrics();
+        let line_count = line_metrics.as_slice().len();
+        let lines = line_metrics
+            .iter()
+            .enumerate()
+            .map(|(i, lm)| {
+                let end = if i + 1 == line_count {
+                    text.len()
+                } else if lm.hard_break {
+                    text.get(..lm.end_including_newline)
+                        .unwrap_or(text)
+                        .trim_end_matches(&['\r', '\n'][..])
+                        .len()
+                } else {
+                    lm.end_excluding_whitespaces
+                };
+                Line {
+                    range: lm.start_index..end.max(lm.start_index),
+                    left: lm.left as scalar,
+                    top: (lm.baseline - lm.ascent) as scalar,
+                    baseline: lm.baseline as scalar,
+                    bottom: (lm.baseline + lm.descent) as scalar,
+                }
+            })
+            .collect();
+
+        Editor {
+            paragraph,
+            text,
+            grapheme_boundaries,
+            char_boundaries,
+            lines,
+        }
+    }
+
+    pub fn paragraph(&self) -> &'a Paragraph {
+        self.paragraph
+    }
+
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    pub fn next_grapheme_boundary(&self, offset: usize) -> usize {
+        let i = match self.grapheme_boundaries.binary_search(&offset) {
+            Ok(i) => i + 1,
+            Err(i) => i,
+        };
+        self.grapheme_boundaries
+            .get(i)
+            .copied()
+            .unwrap_or_else(|| self.text.len())
+    }
+
+    pub fn prev_grapheme_boundary(&self, offset: usize) -> usize {
+        let i = match self.grapheme_boundaries.binary_search(&offset) {
+            Ok(i) | Err(i) => i,
+        };
+        i.checked_sub(1)
+            .map(|i| self.grapheme_boundaries[i])
+            .unwrap_or(0)
+    }
+
+    /// Returns the range of the word at `offset`, which may also be a run of whitespace or
+    /// punctuation.
+    pub fn word_boundary(&self, offset: usize) -> Range<usize> {
+        let range = self
+            .paragraph
+            .get_word_boundary(self.to_utf16(offset) as u32);
+        self.from_utf16(range.start)..self.from_utf16(range.end)
+    }
+
+    /// Returns the end of the word at or after `offset`.
+    pub fn next_word_boundary(&self, mut offset: usize) -> usize {
+        while offset < self.text.len() {
+            let word = self.word_boundary(offset);
+            if word.end > offset && !self.is_whitespace(word.clone()) {
+                return word.end;
+            }
+            offset = word.end.max(self.next_grapheme_boundary(offset));
+        }
+        self.text.len()
+    }
+
+    /// Returns the start of the word before `offset`.
+    pub fn prev_word_boundary(&self, mut offset: usize) -> usize {
+        while offset > 0 {
+            let prev = self.prev_grapheme_boundary(offset);
+            let word = self.word_boundary(prev);
+            if word.start < offset && !self.is_whitespace(word.clone()) {
+                return word.start;
+            }
+            offset = word.start.min(prev);
+        }
+        0
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Returns the line the caret at `offset` is placed on.
+    ///
+    /// At soft line breaks, the caret is placed at the start of the following line.
+    pub fn line_number(&self, offset: usize) -> usize {
+        self.lines
+            .iter()
+            .skip(1)
+            .position(|next_line| offset < next_line.range.start)
+            .unwrap_or_else(|| self.lines.len().saturating_sub(1))
+    }
+
+    /// Returns the range of the text of a line, excluding the trailing line break and the
+    /// whitespace at soft line breaks.
+    pub fn line_range(&self, line_number: usize) -> Range<usize> {
+        self.lines
+            .get(line_number)
+            .map(|line| line.range.clone())
+            .unwrap_or_else(|| self.text.len()..self.text.len())
+    }
+
+    /// Returns the bounds of the caret at `offset`, a zero width rectangle that spans the height
+    /// of the line.
+    pub fn caret_rect(&self, offset: usize) -> Rect {
+        let line = match self.lines.get(self.line_number(offset)) {
+            Some(line) => line,
+            None => return Rect::new(0.0, 0.0, 0.0, self.paragraph.height()),
+        };
+
+        let next = self.next_grapheme_boundary(offset);
+        let prev = self.prev_grapheme_boundary(offset);
+        let x = if offset < line.range.end && next <= line.range.end {
+            self.grapheme_box(offset..next)
+                .map(|(rect, direction)| match direction {
+                    TextDirection::LTR => rect.left,
+                    TextDirection::RTL => rect.right,
+                })
+        } else {
+            None
+        }
+        .or_else(|| {
+            if offset > line.range.start && prev >= line.range.start {
+                self.grapheme_box(prev..offset)
+                    .map(|(rect, direction)| match direction {
+                        TextDirection::LTR => rect.right,
+                        TextDirection::RTL => rect.left,
+                    })
+            } else {
+                None
+            }
+        })
+        .unwrap_or(line.left);
+
+        Rect::new(x, line.top, x, line.bottom)
+    }
+
+    /// Returns the grapheme boundary closest to `point`.
+    pub fn offset_at(&self, point: impl Into<Point>) -> usize {
+        let position = self.paragraph.get_glyph_position_at_coordinate(point);
+        let offset = self.from_utf16(position.position.max(0) as usize);
+        if self.grapheme_boundaries.binary_search(&offset).is_ok() {
+            offset
+        } else {
+            self.prev_grapheme_boundary(offset)
+        }
+    }
+
+    /// Returns the rectangles that cover the text in `range`.
+    pub fn selection_rects(&self, range: Range<usize>) -> Vec<Rect> {
+        self.paragraph
+            .get_rects_for_range(
+                self.to_utf16(range.start)..self.to_utf16(range.end),
+                RectHeightStyle::Max,
+                RectWidthStyle::Tight,
+            )
+            .iter()
+            .map(|text_box| text_box.rect)
+            .collect()
+    }
+
+    /// Moves the focus of `selection`. If `extend` is `false`, the selection collapses to a caret.
+    pub fn move_selection(
+        &self,
+        selection: Selection,
+        movement: Movement,
+        extend: bool,
+    ) -> Selection {
+        if !extend && !selection.is_collapsed() {
+            match movement {
+                Movement::GraphemeBackward => return Selection::caret(selection.range().start),
+                Movement::GraphemeForward => return Selection::caret(selection.range().end),
+                _ => {}
+            }
+        }
+
+        let focus = selection.focus;
+        let mut preferred_x = None;
+        let focus = match movement {
+            Movement::GraphemeBackward => self.prev_grapheme_boundary(focus),
+            Movement::GraphemeForward => self.next_grapheme_boundary(focus),
+            Movement::WordBackward => self.prev_word_boundary(focus),
+            Movement::WordForward => self.next_word_boundary(focus),
+            Movement::LineStart => self.line_range(self.line_number(focus)).start,
+            Movement::LineEnd => self.line_range(self.line_number(focus)).end,
+            Movement::LineUp | Movement::LineDown => {
+                let x = selection
+                    .preferred_x
+                    .unwrap_or_else(|| self.caret_rect(focus).left);
+                preferred_x = Some(x);
+                let line_number = self.line_number(focus);
+                let target = if movement == Movement::LineUp {
+                    line_number.checked_sub(1)
+                } else {
+                    Some(line_number + 1).filter(|l| *l < self.lines.len())
+                };
+                match target {
+                    Some(target) => self.offset_at((x, self.lines[target].baseline)),
+                    None if movement == Movement::LineUp => 0,
+                    None => self.text.len(),
+                }
+            }
+            Movement::TextStart => 0,
+            Movement::TextEnd => self.text.len(),
+        };
+
+        Selection {
+            anchor: if extend { selection.anchor } else { focus },
+            focus,
+            preferred_x,
+        }
+    }
+
+    fn grapheme_box(&self, range: Range<usize>) -> Option<(Rect, TextDirection)> {
+        self.paragraph
+            .get_rects_for_range(
+                self.to_utf16(range.start)..self.to_utf16(range.end),
+                RectHeightStyle::Tight,
+                RectWidthStyle::Tight,
+            )
+            .iter()
+            .next()
+            .map(|text_box| (text_box.rect, text_box.direct))
+    }
+
+    fn is_whitespace(&self, range: Range<usize>) -> bool {
+        self.text[range].chars().all(char::is_whitespace)
+    }
+
+    /// The paragraph's text indices are UTF-16 based.
+    fn to_utf16(&self, utf8: usize) -> usize {
+        self.convert(utf8, |(utf8, _)| *utf8, |(_, utf16)| *utf16)
+    }
+
+    fn from_utf16(&self, utf16: usize) -> usize {
+        self.convert(utf16, |(_, utf16)| *utf16, |(utf8, _)| *utf8)
+    }
+
+    /// Maps an offset to the other encoding, offsets inside a character are moved to its start.
+    fn convert(
+        &self,
+        offset: usize,
+        from: impl Fn(&(usize, usize)) -> usize,
+        to: impl Fn(&(usize, usize)) -> usize,
+    ) -> usize {
+        let i = self
+            .char_boundaries
+            .binary_search_by_key(&offset, from)
+            .unwrap_or_else(|i| i.saturating_sub(1));
+        to(&self.char_boundaries[i])
+    }
+}
+
+/// Returns the UTF-8 offsets of the grapheme cluster boundaries of `text`, including `0` and
+/// `text.len()`, or `None` if ICU is not available.
+fn grapheme_boundaries(text: &str) -> Option<Vec<usize>> {
+    let mut boundaries: Vec<usize> = Vec::new();
+    unsafe extern "C" fn visitor(context: *mut c_void, utf8_offset: usize) {
+        (*(context as *mut Vec<usize>)).push(utf8_offset)
+    }
+    unsafe {
+        sb::C_SkUnicode_visitGraphemeBoundaries(
+            text.as_ptr() as _,
+            text.len(),
+            &mut boundaries as *mut Vec<usize> as *mut c_void,
+            Some(visitor),
+        )
+    }
+    .if_true_some(boundaries)
+}
+
+#[test]
+#[serial_test::serial]
+fn test_caret_navigation() {
+    let text = "Hello World";
+    let paragraph = super::tests::layout(text, 256.0);
+
+    let editor = Editor::new(&paragraph);
+    assert_eq!(editor.line_count(), 1);
+    assert_eq!(editor.next_grapheme_boundary(0), 1);
+    assert_eq!(editor.prev_grapheme_boundary(text.len()), text.len() - 1);
+    assert_eq!(editor.next_word_boundary(0), 5);
+    assert_eq!(editor.prev_word_boundary(text.len()), 6);
+    assert_eq!(editor.line_range(0), 0..text.len());
+    assert!(editor.caret_rect(0).left < editor.caret_rect(6).left);
+    assert_eq!(editor.offset_at(editor.caret_rect(6).center()), 6);
+
+    let selection = editor.move_selection(Selection::caret(0), Movement::WordForward, true);
+    assert_eq!(selection.range(), 0..5);
+    let selection = editor.move_selection(selection, Movement::GraphemeForward, false);
+    assert_eq!(selection, Selection::caret(5));
+    let selection = editor.move_selection(selection, Movement::LineDown, false);
+    assert_eq!(selection.focus, text.len());
+    assert!(selection.preferred_x.is_some());
+    assert!(!editor.selection_rects(0..5).is_empty());
+}
+
+#[test]
+#[serial_test::serial]
+fn test_grapheme_boundaries() {
+    crate::icu::init();
+
+    // Ligatures do not affect grapheme boundaries, combining marks belong to their base.
+    assert_eq!(
+        grapheme_boundaries("fie\u{301}").unwrap(),
+        [0, 1, 2, "fie\u{301}".len()]
+    );
+    assert_eq!(grapheme_boundaries("").unwrap(), [0]);
+}
@@ -0,0 +1,491 @@
+use super::{Paragraph, ParagraphBuilder, ParagraphPainter};
+use crate::{prelude::*, Canvas, Font, Paint, Point, TextBlob, Vector};
+use skia_bindings as sb;
+use std::{collections::HashMap, ffi::c_void, iter, ptr};
+
+const SOFT_HYPHEN: char = '\u{ad}';
+const ZERO_WIDTH_SPACE: char = '\u{200b}';
+
+/// Supplies line break opportunities in addition to the ones ICU finds.
+///
+/// Used with [`ParagraphBuilder::set_line_breaker()`] and
+/// [`ParagraphBuilder::add_text_with_line_breaker()`].
+pub trait LineBreaker {
+    /// Returns the additional break opportunities of `text` in ascending order.
+    fn line_breaks(&self, text: &str) -> Vec<LineBreak>;
+}
+
+/// A line break opportunity before the UTF-8 offset `offset`.
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct LineBreak {
+    pub offset: usize,
+    /// If the line is broken here, [`Paragraph::paint()`] shows a hyphen at the end of the line.
+    pub hyphenate: bool,
+}
+
+impl NativeTransmutable<sb::InsertedBreak> for LineBreak {}
+
+impl LineBreak {
+    pub fn new(offset: usize, hyphenate: bool) -> Self {
+        Self { offset, hyphenate }
+    }
+}
+
+/// A [`LineBreaker`] that allows breaks after each character `F` returns `true` for, for example
+/// after the slashes of URLs.
+#[derive(Copy, Clone, Debug)]
+pub struct BreakAfter<F: Fn(char) -> bool>(pub F);
+
+impl<F: Fn(char) -> bool> LineBreaker for BreakAfter<F> {
+    fn line_breaks(&self, text: &str) -> Vec<LineBreak> {
+        text.char_indices()
+            .filter(|(_, c)| (self.0)(*c))
+            .map(|(i, c)| LineBreak::new(i + c.len_utf8(), false))
+            .filter(|b| b.offset < text.len())
+            .collect()
+    }
+}
+
+/// A dictionary-based hyphenator that uses Liang's algorithm with TeX hyphenation patterns, as
+/// they are available for most languages from the `hyph-utf8` project.
+#[derive(Clone, Debug)]
+pub struct Hyphenator {
+    patterns: HashMap<String, Vec<u8>>,
+    /// The number of characters of the longest pattern.
+    max_pattern_len: usize,
+    exceptions: HashMap<String, Vec<usize>>,
+    left_min: usize,
+    right_min: usize,
+}
+
+impl Hyphenator {
+    /// Creates a hyphenator from whitespace separated patterns, for example `"hy3ph he2n"`.
+    ///
+    /// Returns `None` if a pattern is invalid.
+    pub fn new(patterns: &str) -> Option<Self> {
+        let patterns: HashMap<String, Vec<u8>> = patterns
+            .split_whitespace()
+            .map(parse_pattern)
+            .collect::<Option<_>>()?;
+        let max_pattern_len = patterns
+            .keys()
+            .map(|letters| letters.chars().count())
+            .max()
+            .unwrap_or_default();
+        Some(Self {
+            patterns,
+            max_pattern_len,
+            exceptions: HashMap::new(),
+            left_min: 2,
+            right_min: 3,
+        })
+    }
+
+    /// Adds words with explicit hyphens that override the patterns, for example `"ta-ble"`.
+    pub fn add_exceptions(&mut self, exceptions: &str) -> &mut Self {
+        for exception in exceptions.split_whitespace() {
+            let mut hyphens = Vec::new();
+            let mut word = String::new();
+            for c in exception.chars() {
+                if c == '-' {
+                    hyphens.push(word.chars().count());
+                } else {
+                    word.extend(c.to_lowercase());
+                }
+            }
+            self.exceptions.insert(word, hyphens);
+        }
+        self
+    }
+
+    /// Sets the minimum number of characters before the first and after the last hyphen of a word,
+    /// 2 and 3 by default.
+    pub fn set_min_lengths(&mut self, left_min: usize, right_min: usize) -> &mut Self {
+        self.left_min = left_min.max(1);
+        self.right_min = right_min.max(1);
+        self
+    }
+
+    /// Returns the UTF-8 offsets in `word` at which it can be hyphenated.
+    pub fn hyphenate(&self, word: &str) -> Vec<usize> {
+        let offsets: Vec<usize> = word.char_indices().map(|(i, _)| i).collect();
+        let len = offsets.len();
+        if len < self.left_min + self.right_min {
+            return Vec::new();
+        }
+        let dotted: String = iter::once('.')
+            .chain(word.chars().flat_map(char::to_lowercase))
+            .chain(iter::once('.'))
+            .collect();
+        let boundaries: Vec<usize> = dotted
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(iter::once(dotted.len()))
+            .collect();
+        // The dots add two characters, lowercasing must not add any.
+        if boundaries.len() != len + 3 {
+            return Vec::new();
+        }
+        let lowercase = &dotted[1..dotted.len() - 1];
+
+        let positions: Vec<usize> = match self.exceptions.get(lowercase) {
+            Some(hyphens) => hyphens.clone(),
+            None => {
+                let chars = boundaries.len() - 1;
+                let mut points = vec![0u8; chars + 1];
+                for start in 0..chars {
+                    for end in start + 1..=chars.min(start + self.max_pattern_len) {
+                        let key = &dotted[boundaries[start]..boundaries[end]];
+                        if let Some(values) = self.patterns.get(key) {
+                            for (i, v) in values.iter().enumerate() {
+                                let point = &mut points[start + i];
+                                *point = (*point).max(*v);
+                            }
+                        }
+                    }
+                }
+                // The point between the word's characters `i - 1` and `i` is at `i + 1`, because of
+                // the leading dot.
+                (1..len).filter(|i| points[i + 1] % 2 == 1).collect()
+            }
+        };
+
+        positions
+            .into_iter()
+            .filter(|i| *i >= self.left_min && *i <= len - self.right_min)
+            .map(|i| offsets[i])
+            .collect()
+    }
+}
+
+impl LineBreaker for Hyphenator {
+    fn line_breaks(&self, text: &str) -> Vec<LineBreak> {
+        let mut breaks = Vec::new();
+        let mut rest = text;
+        while let Some(start) = rest.find(char::is_alphabetic) {
+            let word = &rest[start..];
+            let len = word
+                .find(|c: char| !c.is_alphabetic())
+                .unwrap_or_else(|| word.len());
+            let word_offset = text.len() - word.len();
+            breaks.extend(
+                self.hyphenate(&word[..len])
+                    .into_iter()
+                    .map(|offset| LineBreak::new(word_offset + offset, true)),
+            );
+            rest = &word[len..];
+        }
+        breaks
+    }
+}
+
+/// Parses a pattern like `hy3ph` into its letters and the values between them.
+fn parse_pattern(pattern: &str) -> Option<(String, Vec<u8>)> {
+    let mut letters = String::new();
+    let mut values = vec![0];
+    for c in pattern.chars() {
+        match c.to_digit(10) {
+            Some(digit) => *values.last_mut().unwrap() = digit as u8,
+            None => {
+                letters.push(c);
+                values.push(0);
+            }
+        }
+    }
+    if letters.is_empty() {
+        return None;
+    }
+    Some((letters, values))
+}
+
+type BoxedLineBreaker = Box<dyn LineBreaker + Send + Sync>;
+
+impl ParagraphBuilder {
+    /// Sets the line breaker that is applied to the text added with [`Self::add_text()`] from now
+    /// on, `None` removes it.
+    ///
+    /// Skia's line breaking can not be extended and [`super::ParagraphStyle`] has no room for a
+    /// line breaker (last checked: m91), so the break opportunities are inserted into the text
+    /// Skia lays out, see [`Self::add_text_with_line_breaker()`].
+    pub fn set_line_breaker(&mut self, line_breaker: Option<BoxedLineBreaker>) -> &mut Self {
+        unsafe extern "C" fn drop_line_breaker(line_breaker: *mut c_void) {
+            drop(Box::from_raw(line_breaker as *mut BoxedLineBreaker))
+        }
+
+        let line_breaker = line_breaker
+            .map(|line_breaker| Box::into_raw(Box::new(line_breaker)) as *mut c_void)
+            .unwrap_or(ptr::null_mut());
+        unsafe {
+            sb::C_ParagraphBuilder_setLineBreaker(
+                self.native_mut(),
+                line_breaker,
+                Some(drop_line_breaker),
+            )
+        }
+        self
+    }
+
+    pub(super) fn line_breaker_ptr(&self) -> Option<*const (dyn LineBreaker + Send + Sync)> {
+        let line_breaker = unsafe { sb::C_ParagraphBuilder_lineBreaker(self.native()) };
+        unsafe { (line_breaker as *const BoxedLineBreaker).as_ref() }
+            .map(|line_breaker| &**line_breaker as *const _)
+    }
+
+    /// Adds `text` with the additional break opportunities of `line_breaker` instead of the one set
+    /// with [`Self::set_line_breaker()`].
+    ///
+    /// Skia lays out the text with a soft hyphen (`U+00AD`) at each hyphenation point and a zero
+    /// width space (`U+200B`) at each other break opportunity. The paragraph's text and all
+    /// offsets the paragraph takes and returns don't include these characters, see
+    /// [`Paragraph::inserted_breaks()`].
+    pub fn add_text_with_line_breaker<L: LineBreaker + ?Sized>(
+        &mut self,
+        text: impl AsRef<str>,
+        line_breaker: &L,
+    ) -> &mut Self {
+        let text = text.as_ref();
+        let mut last = 0;
+        for line_break in line_breaker.line_breaks(text) {
+            let offset = line_break.offset;
+            if offset <= last || offset >= text.len() || !text.is_char_boundary(offset) {
+                continue;
+            }
+            let before = &text[..offset];
+            self.add_text_unbroken(&text[last..offset]);
+            if !before.ends_with(SOFT_HYPHEN) && !before.ends_with(ZERO_WIDTH_SPACE) {
+                unsafe { sb::C_ParagraphBuilder_addBreak(self.native_mut(), line_break.hyphenate) }
+            }
+            last = offset;
+        }
+        self.add_text_unbroken(&text[last..])
+    }
+}
+
+/// A soft hyphen at which a line of a [`Paragraph`] was broken.
+#[derive(Clone, Debug)]
+pub struct SoftHyphen {
+    pub line_number: usize,
+    /// The UTF-8 offset in [`Paragraph::text()`] at which the line ends.
+    pub offset: usize,
+    /// The position of the hyphen on the line's baseline, relative to the paragraph.
+    pub position: Point,
+    /// The font of the text before the hyphen.
+    pub font: Font,
+}
+
+impl Paragraph {
+    /// The break opportunities a [`LineBreaker`] inserted, in ascending order of their UTF-8
+    /// offsets in [`Self::text()`].
+    pub fn inserted_breaks(&self) -> &[LineBreak] {
+        unsafe {
+            let mut count = 0;
+            let ptr = sb::C_Paragraph_insertedBreaks(self.native(), &mut count);
+            safer::from_raw_parts(LineBreak::from_native_ptr(ptr), count)
+        }
+    }
+
+    /// Returns the soft hyphens at which lines were broken by the last [`Self::layout()`].
+    ///
+    /// This includes the hyphenation points of a [`LineBreaker`] and the soft hyphens that are
+    /// part of the text.
+    pub fn soft_hyphens(&self) -> &[SoftHyphen] {
+        let soft_hyphens = unsafe { sb::C_Paragraph_softHyphens(self.native()) };
+        unsafe { (soft_hyphens as *const Vec<SoftHyphen>).as_ref() }
+            .map(|soft_hyphens| soft_hyphens.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Finds the soft hyphens of the current layout and keeps them for painting.
+    pub(super) fn update_soft_hyphens(&mut self) {
+        unsafe extern "C" fn drop_soft_hyphens(soft_hyphens: *mut c_void) {
+            drop(Box::from_raw(soft_hyphens as *mut Vec<SoftHyphen>))
+        }
+
+        let soft_hyphens = self.find_soft_hyphens();
+        let soft_hyphens = if soft_hyphens.is_empty() {
+            ptr::null_mut()
+        } else {
+            Box::into_raw(Box::new(soft_hyphens)) as *mut c_void
+        };
+        unsafe {
+            sb::C_Paragraph_setSoftHyphens(self.native_mut(), soft_hyphens, Some(drop_soft_hyphens))
+        }
+    }
+
+    fn find_soft_hyphens(&self) -> Vec<SoftHyphen> {
+        let text = self.text();
+        let hyphenation_points: Vec<usize> = self
+            .inserted_breaks()
+            .iter()
+            .filter(|line_break| line_break.hyphenate)
+            .map(|line_break| line_break.offset)
+            .collect();
+        if hyphenation_points.is_empty() && !text.contains(SOFT_HYPHEN) {
+            return Vec::new();
+        }
+        let line_metrics = self.get_line_metrics();
+        let hyphenated_line_ends: Vec<Option<usize>> = line_metrics
+            .as_slice()
+            .iter()
+            .map(|metrics| {
+                let end = metrics.end_index;
+                let hyphenated = hyphenation_points.binary_search(&end).is_ok()
+                    || text
+                        .get(..end)
+                        .map_or(false, |line| line.ends_with(SOFT_HYPHEN));
+                if hyphenated {
+                    Some(end)
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        // The hyphen is placed behind the last run of the line.
+        let mut hyphens = Vec::new();
+        let mut last_run = None;
+        self.visit(|line_number, info| match info {
+            Some(info) => {
+                let baseline = info.positions().last().map_or(0.0, |p| p.y);
+                let end = info.origin() + Vector::new(info.advance_x(), baseline);
+                last_run = Some((end, info.font().clone()));
+            }
+            None => {
+                if let (Some((position, font)), Some(Some(offset))) =
+                    (last_run.take(), hyphenated_line_ends.get(line_number))
+                {
+                    hyphens.push(SoftHyphen {
+                        line_number,
+                        offset: *offset,
+                        position,
+                        font,
+                    })
+                }
+            }
+        });
+        hyphens
+    }
+
+    /// Paints a hyphen at each soft hyphen a line was broken at, Skia does not (last checked:
+    /// m91).
+    pub(super) fn paint_soft_hyphens(&self, canvas: &mut Canvas, p: Point) {
+        for hyphen in self.soft_hyphens() {
+            let paint = self.soft_hyphen_paint(hyphen);
+            canvas.draw_str("-", p + hyphen.position, &hyphen.font, &paint);
+        }
+    }
+
+    /// Sends the hyphens [`Self::paint_soft_hyphens()`] paints to `painter`.
+    pub(super) fn paint_soft_hyphens_with_painter(
+        &self,
+        painter: &mut impl ParagraphPainter,
+        p: Point,
+    ) {
+        for hyphen in self.soft_hyphens() {
+            if let Some(blob) = TextBlob::from_str("-", &hyphen.font) {
+                let paint = self.soft_hyphen_paint(hyphen);
+                painter.draw_text_blob(&blob, p + hyphen.position, &paint);
+            }
+        }
+    }
+
+    /// The text paint of the style of the text before the hyphen.
+    fn soft_hyphen_paint(&self, hyphen: &SoftHyphen) -> Paint {
+        let style = match self.get_style_at(hyphen.offset.saturating_sub(1)) {
+            Some(style) => style,
+            None => return Paint::default(),
+        };
+        style.foreground().cloned().unwrap_or_else(|| {
+            let mut paint = Paint::default();
+            paint.set_color(style.color());
+            paint
+        })
+    }
+}
+
+#[test]
+fn hyphenate_with_patterns_and_exceptions() {
+    let mut hyphenator = Hyphenator::new("hy3ph he2n hena4 hen5at 1na n2at 1tio 2io o2n").unwrap();
+    assert_eq!(hyphenator.hyphenate("hyphenation"), [2, 6]);
+    assert_eq!(hyphenator.hyphenate("Hyphenation"), [2, 6]);
+    assert!(hyphenator.hyphenate("hyp").is_empty());
+
+    hyphenator.add_exceptions("hy-phen-ation");
+    assert_eq!(hyphenator.hyphenate("hyphenation"), [2, 6]);
+    hyphenator.add_exceptions("hyphen-ation");
+    assert_eq!(hyphenator.hyphenate("hyphenation"), [6]);
+
+    assert_eq!(
+        hyphenator.line_breaks("a hyphenation."),
+        [LineBreak::new(8, true)]
+    );
+    assert!(Hyphenator::new("12").is_none());
+}
+
+#[test]
+fn break_after_characters() {
+    let breaker = BreakAfter(|c| c == '/');
+    assert_eq!(breaker.line_breaks("a/b/"), [LineBreak::new(2, false)]);
+}
+
+#[test]
+fn test_line_break_layout() {
+    LineBreak::test_layout()
+}
+
+#[test]
+#[serial_test::serial]
+fn soft_hyphens_at_line_breaks() {
+    use crate::{
+        textlayout::{RectHeightStyle, RectWidthStyle, TextStyle},
+        Color,
+    };
+
+    let hyphenator = Hyphenator::new("hy3ph he2n hena4 hen5at 1na n2at 1tio 2io o2n").unwrap();
+    let text = "hyphenation ".repeat(10);
+
+    let mut builder = super::tests::builder();
+    builder.set_line_breaker(Some(Box::new(hyphenator)));
+    let mut style = TextStyle::new();
+    style.set_color(Color::BLACK);
+    builder.push_style(&style);
+    builder.add_text(&text);
+    let mut paragraph = builder.build();
+    paragraph.layout(40.0);
+
+    // The offsets don't include the inserted breaks.
+    assert_eq!(paragraph.text(), text);
+    assert_eq!(paragraph.inserted_breaks().len(), 20);
+    assert_eq!(
+        paragraph.inserted_breaks()[..2],
+        [LineBreak::new(2, true), LineBreak::new(6, true)]
+    );
+    let line_metrics = paragraph.get_line_metrics();
+    assert_eq!(line_metrics[0].start_index, 0);
+    assert!([2, 6].contains(&line_metrics[0].end_index));
+    assert!(line_metrics.as_slice().last().unwrap().end_index <= text.len());
+    let end = paragraph.get_glyph_position_at_coordinate((1000.0, 1000.0));
+    assert!(end.position as usize <= text.len());
+    let boxes = paragraph.get_rects_for_range(0..2, RectHeightStyle::Tight, RectWidthStyle::Tight);
+    assert_eq!(boxes.as_slice().len(), 1);
+
+    assert!(paragraph.line_number() > 10);
+    let hyphens = paragraph.soft_hyphens();
+    assert!(!hyphens.is_empty());
+    assert!(hyphens.iter().all(|h| h.position.x > 0.0));
+
+    // The hyphen is painted behind the end of the line.
+    let hyphen = &hyphens[0];
+    let (width, height) = (64, paragraph.height().ceil() as i32);
+    let mut pixels = vec![0u32; (width * height) as usize];
+    let mut canvas = Canvas::from_raster_direct_n32((width, height), &mut pixels, None).unwrap();
+    paragraph.paint(&mut canvas, (0, 0));
+    drop(canvas);
+    let font_size = hyphen.font.size();
+    let (x, y) = (hyphen.position.x as i32, hyphen.position.y as i32);
+    let painted = (x..(x + (font_size / 2.0) as i32).min(width))
+        .flat_map(|x| (y - (font_size / 2.0) as i32..y).map(move |y| (x, y)))
+        .any(|(x, y)| pixels[(y * width + x) as usize] != 0);
+    assert!(painted);
+}
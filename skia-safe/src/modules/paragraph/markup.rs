@@ -0,0 +1,538 @@
+use super::{
+    ParagraphBuilder, PlaceholderAlignment, PlaceholderStyle, TextBaseline, TextDecoration,
+    TextStyle,
+};
+use crate::{
+    font_style::{Slant, Weight},
+    scalar, Color, FontStyle,
+};
+use std::{error, fmt, ops::Range};
+
+/// The text, links and images of markup added with [`ParagraphBuilder::add_markup()`].
+///
+/// All ranges and offsets refer to the paragraph's text, see [`super::Paragraph::text()`], which
+/// includes the text that was added to the builder before the markup.
+#[derive(Clone, PartialEq, Default, Debug)]
+pub struct Markup {
+    /// The plain text of the markup, images are represented by the object replacement character
+    /// `U+FFFC`.
+    pub text: String,
+    pub links: Vec<MarkupLink>,
+    /// The images in the order of their placeholders, see
+    /// [`super::Paragraph::get_rects_for_placeholders()`].
+    pub images: Vec<MarkupImage>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct MarkupLink {
+    pub target: String,
+    /// The UTF-8 range of the link's text.
+    pub text_range: Range<usize>,
+    /// The UTF-16 range of the link's text, as expected by
+    /// [`super::Paragraph::get_rects_for_range()`].
+    pub utf16_range: Range<usize>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct MarkupImage {
+    pub name: String,
+    /// The id of the image's placeholder, see [`super::Paragraph::get_placeholder_boxes()`].
+    pub id: usize,
+    /// The UTF-8 offset of the image's placeholder character.
+    pub text_offset: usize,
+    pub width: scalar,
+    pub height: scalar,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum MarkupError {
+    /// A tag that is not supported.
+    UnknownTag { offset: usize, tag: String },
+    /// A tag value or attribute that can not be parsed.
+    InvalidValue { offset: usize, tag: String },
+    /// A closing tag that does not match the innermost open tag.
+    UnexpectedClosingTag { offset: usize, tag: String },
+    /// A tag that is not closed at the end of the markup.
+    UnclosedTag { offset: usize, tag: String },
+    /// A `<` without a matching `>`.
+    UnterminatedTag { offset: usize },
+}
+
+impl fmt::Display for MarkupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MarkupError::UnknownTag { offset, tag } => {
+                write!(f, "unknown tag <{}> at {}", tag, offset)
+            }
+            MarkupError::InvalidValue { offset, tag } => {
+                write!(f, "invalid value of tag <{}> at {}", tag, offset)
+            }
+            MarkupError::UnexpectedClosingTag { offset, tag } => {
+                write!(f, "unexpected closing tag </{}> at {}", tag, offset)
+            }
+            MarkupError::UnclosedTag { offset, tag } => {
+                write!(f, "tag <{}> at {} is not closed", tag, offset)
+            }
+            MarkupError::UnterminatedTag { offset } => {
+                write!(f, "tag at {} is not terminated", offset)
+            }
+        }
+    }
+}
+
+impl error::Error for MarkupError {}
+
+impl ParagraphBuilder {
+    /// Parses `markup` and adds its text and styles to the paragraph.
+    ///
+    /// Supported are `<b>`, `<i>`, `<u>`, `<color=#RRGGBB>` or `<color=#AARRGGBB>`,
+    /// `<size=points>`, `<font=family>` and `<link=target>`, all of which must be closed with the
+    /// matching closing tag, for example `</b>`. `<image=name width=w height=h>` adds a
+    /// placeholder, the optional `align` attribute can be `baseline`, `above`, `below`, `top`,
+    /// `bottom` or `middle`. Values that contain spaces or `>` must be quoted with `"`.
+    /// `&lt;`, `&gt;`, `&quot;` and `&amp;` can be used to escape characters.
+    ///
+    /// Styles are applied on top of the builder's current style. Nothing is added to the builder if
+    /// the markup can not be parsed.
+    pub fn add_markup(&mut self, markup: &str) -> Result<Markup, MarkupError> {
+        let items = parse(markup)?;
+
+        let mut result = Markup::default();
+        let text_before = self.text();
+        let (offset, utf16_offset) = (text_before.len(), text_before.encode_utf16().count());
+        let mut utf16_len = 0;
+        let mut styles = vec![self.peek_style()];
+        let mut links: Vec<(String, usize, usize)> = Vec::new();
+
+        for item in items {
+            match item {
+                Item::Text(text) => {
+                    self.add_text(&text);
+                    result.text.push_str(&text);
+                    utf16_len += text.encode_utf16().count();
+                }
+                Item::Push(change) => {
+                    let mut style = styles.last().unwrap().clone();
+                    change.apply(&mut style);
+                    self.push_style(&style);
+                    styles.push(style);
+                }
+                Item::Pop => {
+                    styles.pop();
+                    self.pop();
+                }
+                Item::LinkStart(target) => links.push((target, result.text.len(), utf16_len)),
+                Item::LinkEnd => {
+                    let (target, start, utf16_start) = links.pop().unwrap();
+                    result.links.push(MarkupLink {
+                        target,
+                        text_range: offset + start..offset + result.text.len(),
+                        utf16_range: utf16_offset + utf16_start..utf16_offset + utf16_len,
+                    });
+                }
+                Item::Image(image, alignment) => {
+                    let id = self.placeholder_count();
+                    self.add_placeholder_with_id(
+                        &PlaceholderStyle::new(
+                            image.width,
+                            image.height,
+                            alignment,
+                            TextBaseline::Alphabetic,
+                            0.0,
+                        ),
+                        id,
+                    );
+                    result.images.push(MarkupImage {
+                        id,
+                        text_offset: offset + result.text.len(),
+                        ..image
+                    });
+                    result.text.push(OBJECT_REPLACEMENT_CHARACTER);
+                    utf16_len += 1;
+                }
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+const OBJECT_REPLACEMENT_CHARACTER: char = '\u{fffc}';
+
+#[derive(Debug)]
+enum Item {
+    Text(String),
+    Push(StyleChange),
+    Pop,
+    LinkStart(String),
+    LinkEnd,
+    Image(MarkupImage, PlaceholderAlignment),
+}
+
+#[derive(Debug)]
+enum StyleChange {
+    Bold,
+    Italic,
+    Underline,
+    Color(Color),
+    Size(scalar),
+    Font(String),
+}
+
+impl StyleChange {
+    fn apply(&self, style: &mut TextStyle) {
+        match self {
+            StyleChange::Bold => {
+                let fs = style.font_style();
+                style.set_font_style(FontStyle::new(Weight::BOLD, fs.width(), fs.slant()));
+            }
+            StyleChange::Italic => {
+                let fs = style.font_style();
+                style.set_font_style(FontStyle::new(fs.weight(), fs.width(), Slant::Italic));
+            }
+            StyleChange::Underline => {
+                style.decoration_mut().ty |= TextDecoration::UNDERLINE;
+            }
+            StyleChange::Color(color) => {
+                style.set_color(*color);
+            }
+            StyleChange::Size(size) => {
+                style.set_font_size(*size);
+            }
+            StyleChange::Font(family) => {
+                style.set_font_families(&[family]);
+            }
+        }
+    }
+}
+
+/// A parsed tag, `<name=value key=value ...>` or `</name>`.
+struct Tag<'a> {
+    offset: usize,
+    closing: bool,
+    name: &'a str,
+    value: Option<String>,
+    attributes: Vec<(&'a str, String)>,
+}
+
+impl Tag<'_> {
+    fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn invalid_value(&self) -> MarkupError {
+        MarkupError::InvalidValue {
+            offset: self.offset,
+            tag: self.name.into(),
+        }
+    }
+
+    fn parse_value<T: std::str::FromStr>(&self, value: Option<&str>) -> Result<T, MarkupError> {
+        value
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| self.invalid_value())
+    }
+}
+
+fn parse(markup: &str) -> Result<Vec<Item>, MarkupError> {
+    let mut items = Vec::new();
+    let mut open_tags: Vec<(&str, usize)> = Vec::new();
+    let mut text = String::new();
+    let mut rest = markup;
+
+    while !rest.is_empty() {
+        let offset = markup.len() - rest.len();
+        if rest.starts_with('<') {
+            let (tag, len) =
+                parse_tag(rest, offset).ok_or(MarkupError::UnterminatedTag { offset })?;
+            rest = &rest[len..];
+            if !text.is_empty() {
+                items.push(Item::Text(std::mem::take(&mut text)));
+            }
+
+            if tag.closing {
+                if tag.value.is_some() || !tag.attributes.is_empty() {
+                    return Err(tag.invalid_value());
+                }
+                match open_tags.pop() {
+                    Some((name, _)) if name == tag.name => {}
+                    _ => {
+                        return Err(MarkupError::UnexpectedClosingTag {
+                            offset,
+                            tag: tag.name.into(),
+                        })
+                    }
+                }
+                items.push(if tag.name == "link" {
+                    Item::LinkEnd
+                } else {
+                    Item::Pop
+                });
+                continue;
+            }
+
+            let value = tag.value.as_deref();
+            let item = match tag.name {
+                "b" => Item::Push(StyleChange::Bold),
+                "i" => Item::Push(StyleChange::Italic),
+                "u" => Item::Push(StyleChange::Underline),
+                "color" => Item::Push(StyleChange::Color(
+                    value
+                        .and_then(parse_color)
+                        .ok_or_else(|| tag.invalid_value())?,
+                )),
+                "size" => Item::Push(StyleChange::Size(tag.parse_value(value)?)),
+                "font" => Item::Push(StyleChange::Font(
+                    tag.value.clone().ok_or_else(|| tag.invalid_value())?,
+                )),
+                "link" => Item::LinkStart(tag.value.clone().ok_or_else(|| tag.invalid_value())?),
+                "image" => {
+                    let alignment = match tag.attribute("align") {
+                        None | Some("baseline") => PlaceholderAlignment::Baseline,
+                        Some("above") => PlaceholderAlignment::AboveBaseline,
+                        Some("below") => PlaceholderAlignment::BelowBaseline,
+                        Some("top") => PlaceholderAlignment::Top,
+                        Some("bottom") => PlaceholderAlignment::Bottom,
+                        Some("middle") => PlaceholderAlignment::Middle,
+                        Some(_) => return Err(tag.invalid_value()),
+                    };
+                    let image = MarkupImage {
+                        name: tag.value.clone().ok_or_else(|| tag.invalid_value())?,
+                        id: 0,
+                        text_offset: 0,
+                        width: tag.parse_value(tag.attribute("width"))?,
+                        height: tag.parse_value(tag.attribute("height"))?,
+                    };
+                    items.push(Item::Image(image, alignment));
+                    continue;
+                }
+                _ => {
+                    return Err(MarkupError::UnknownTag {
+                        offset,
+                        tag: tag.name.into(),
+                    })
+                }
+            };
+            items.push(item);
+            open_tags.push((tag.name, offset));
+        } else if rest.starts_with('&') {
+            let (c, len) = parse_entity(rest).unwrap_or(('&', 1));
+            text.push(c);
+            rest = &rest[len..];
+        } else {
+            let len = rest
+                .find(|c| c == '<' || c == '&')
+                .unwrap_or_else(|| rest.len());
+            text.push_str(&rest[..len]);
+            rest = &rest[len..];
+        }
+    }
+
+    if let Some((name, offset)) = open_tags.pop() {
+        return Err(MarkupError::UnclosedTag {
+            offset,
+            tag: name.into(),
+        });
+    }
+    if !text.is_empty() {
+        items.push(Item::Text(text));
+    }
+    Ok(items)
+}
+
+/// Parses the tag at the start of `s` and returns it with its length.
+fn parse_tag(s: &str, offset: usize) -> Option<(Tag, usize)> {
+    let mut rest = &s[1..];
+    let closing = rest.starts_with('/');
+    if closing {
+        rest = &rest[1..];
+    }
+
+    let name_len = rest.find(|c: char| !c.is_ascii_alphanumeric())?;
+    let name = &rest[..name_len];
+    rest = &rest[name_len..];
+
+    let mut value = None;
+    if let Some(r) = rest.strip_prefix('=') {
+        let (v, r) = parse_value(r)?;
+        value = Some(v);
+        rest = r;
+    }
+
+    let mut attributes = Vec::new();
+    loop {
+        rest = rest.trim_start();
+        if let Some(r) = rest.strip_prefix('>') {
+            let tag = Tag {
+                offset,
+                closing,
+                name,
+                value,
+                attributes,
+            };
+            return Some((tag, s.len() - r.len()));
+        }
+        let key_len = rest.find(|c: char| !c.is_ascii_alphanumeric())?;
+        if key_len == 0 {
+            return None;
+        }
+        let key = &rest[..key_len];
+        let (v, r) = parse_value(rest[key_len..].strip_prefix('=')?)?;
+        attributes.push((key, v));
+        rest = r;
+    }
+}
+
+/// Parses a quoted or unquoted value and returns it with the remaining string.
+fn parse_value(s: &str) -> Option<(String, &str)> {
+    if let Some(quoted) = s.strip_prefix('"') {
+        let end = quoted.find('"')?;
+        Some((unescape(&quoted[..end]), &quoted[end + 1..]))
+    } else {
+        let end = s.find(|c: char| c.is_whitespace() || c == '>')?;
+        Some((unescape(&s[..end]), &s[end..]))
+    }
+}
+
+fn parse_entity(s: &str) -> Option<(char, usize)> {
+    [
+        ("&lt;", '<'),
+        ("&gt;", '>'),
+        ("&quot;", '"'),
+        ("&amp;", '&'),
+    ]
+    .iter()
+    .find(|(entity, _)| s.starts_with(entity))
+    .map(|(entity, c)| (*c, entity.len()))
+}
+
+fn unescape(s: &str) -> String {
+    let mut r = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(i) = rest.find('&') {
+        r.push_str(&rest[..i]);
+        let (c, len) = parse_entity(&rest[i..]).unwrap_or(('&', 1));
+        r.push(c);
+        rest = &rest[i + len..];
+    }
+    r.push_str(rest);
+    r
+}
+
+fn parse_color(s: &str) -> Option<Color> {
+    let hex = s.strip_prefix('#')?;
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let v = u32::from_str_radix(hex, 16).ok()?;
+    match hex.len() {
+        6 => Some(Color::new(0xff00_0000 | v)),
+        8 => Some(Color::new(v)),
+        _ => None,
+    }
+}
+
+#[test]
+fn parse_markup() {
+    let items = parse("a<b>b<color=#ff0000>c</color></b>&lt;<link=\"x y\">d</link>").unwrap();
+    assert!(matches!(&items[0], Item::Text(t) if t == "a"));
+    assert!(matches!(&items[1], Item::Push(StyleChange::Bold)));
+    assert!(matches!(&items[3], Item::Push(StyleChange::Color(c)) if *c == Color::RED));
+    assert!(matches!(&items[7], Item::Text(t) if t == "<"));
+    assert!(matches!(&items[8], Item::LinkStart(t) if t == "x y"));
+    assert!(matches!(&items[10], Item::LinkEnd));
+
+    let items = parse("<image=icon width=10 height=12.5 align=middle>").unwrap();
+    assert!(matches!(
+        &items[0],
+        Item::Image(image, PlaceholderAlignment::Middle) if image.height == 12.5
+    ));
+}
+
+#[test]
+fn parse_markup_errors() {
+    assert_eq!(
+        parse("<b>a</i>").unwrap_err(),
+        MarkupError::UnexpectedClosingTag {
+            offset: 4,
+            tag: "i".into()
+        }
+    );
+    assert_eq!(
+        parse("a<i>b").unwrap_err(),
+        MarkupError::UnclosedTag {
+            offset: 1,
+            tag: "i".into()
+        }
+    );
+    assert_eq!(
+        parse("<size=big>").unwrap_err(),
+        MarkupError::InvalidValue {
+            offset: 0,
+            tag: "size".into()
+        }
+    );
+    assert_eq!(
+        parse("<blink>").unwrap_err(),
+        MarkupError::UnknownTag {
+            offset: 0,
+            tag: "blink".into()
+        }
+    );
+    assert_eq!(
+        parse("a<b").unwrap_err(),
+        MarkupError::UnterminatedTag { offset: 1 }
+    );
+    assert_eq!(
+        parse("<color=#+fffff>a</color>").unwrap_err(),
+        MarkupError::InvalidValue {
+            offset: 0,
+            tag: "color".into()
+        }
+    );
+    assert_eq!(
+        parse("<b>a</b=x>").unwrap_err(),
+        MarkupError::InvalidValue {
+            offset: 4,
+            tag: "b".into()
+        }
+    );
+}
+
+#[test]
+#[serial_test::serial]
+fn add_markup_reports_links_and_images() {
+    use super::{RectHeightStyle, RectWidthStyle};
+
+    let mut builder = super::tests::builder();
+    builder.push_style(&TextStyle::new());
+    builder.add_text("ä ");
+    let markup = builder
+        .add_markup("<b>Hello</b> <image=smiley width=16 height=16> <link=home>Wörld</link>")
+        .unwrap();
+    assert_eq!(markup.text, "Hello \u{fffc} Wörld");
+    assert_eq!(markup.images[0].text_offset, 9);
+    assert_eq!(markup.images[0].id, 0);
+    assert_eq!(markup.links[0].target, "home");
+    assert_eq!(markup.links[0].text_range, 13..19);
+    assert_eq!(markup.links[0].utf16_range, 10..15);
+
+    let mut paragraph = builder.build();
+    paragraph.layout(256.0);
+    assert_eq!(
+        &paragraph.text()[markup.links[0].text_range.clone()],
+        "Wörld"
+    );
+    let boxes = paragraph.get_placeholder_boxes();
+    assert_eq!(boxes.len(), 1);
+    assert_eq!(boxes[0].id, markup.images[0].id);
+    let link_boxes = paragraph.get_rects_for_range(
+        markup.links[0].utf16_range.clone(),
+        RectHeightStyle::Tight,
+        RectWidthStyle::Tight,
+    );
+    assert!(link_boxes.as_slice()[0].rect.left > boxes[0].text_box.rect.right);
+}
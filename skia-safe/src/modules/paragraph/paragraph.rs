@@ -1,16 +1,25 @@
-use super::{PositionWithAffinity, RectHeightStyle, RectWidthStyle, TextBox};
-use crate::{prelude::*, scalar, textlayout::LineMetrics, Canvas, Point};
+use super::{
+    Block, Placeholder, PositionWithAffinity, RectHeightStyle, RectWidthStyle, TextAlign, TextBox,
+    TextStyle,
+};
+use crate::{
+    prelude::*, scalar, textlayout::LineMetrics, Canvas, Font, GlyphId, Paint, Path, Point, Rect,
+    Unichar,
+};
 use skia_bindings as sb;
 use std::{
+    cmp::Ordering::{Greater, Less},
+    ffi::c_void,
     fmt,
-    ops::{Index, Range},
+    ops::{Deref, Index, Range},
+    os::raw,
 };
 
-pub type Paragraph = RefHandle<sb::skia_textlayout_Paragraph>;
+pub type Paragraph = RefHandle<sb::RustParagraph>;
 unsafe impl Send for Paragraph {}
 unsafe impl Sync for Paragraph {}
 
-impl NativeDrop for sb::skia_textlayout_Paragraph {
+impl NativeDrop for sb::RustParagraph {
     fn drop(&mut self) {
         unsafe { sb::C_Paragraph_delete(self) }
     }
@@ -33,45 +42,65 @@ impl fmt::Debug for Paragraph {
 }
 
 impl Paragraph {
+    fn native_paragraph(&self) -> &sb::skia_textlayout_Paragraph {
+        unsafe { &*self.native().paragraph }
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    fn native_paragraph_mut_force(&self) -> &mut sb::skia_textlayout_Paragraph {
+        unsafe { &mut *self.native().paragraph }
+    }
+
     pub fn max_width(&self) -> scalar {
-        self.native().fWidth
+        self.native_paragraph().fWidth
     }
 
     pub fn height(&self) -> scalar {
-        self.native().fHeight
+        self.native_paragraph().fHeight
     }
 
     pub fn min_intrinsic_width(&self) -> scalar {
-        self.native().fMinIntrinsicWidth
+        self.native_paragraph().fMinIntrinsicWidth
     }
 
     pub fn max_intrinsic_width(&self) -> scalar {
-        self.native().fMaxIntrinsicWidth
+        self.native_paragraph().fMaxIntrinsicWidth
     }
 
     pub fn alphabetic_baseline(&self) -> scalar {
-        self.native().fAlphabeticBaseline
+        self.native_paragraph().fAlphabeticBaseline
     }
 
     pub fn ideographic_baseline(&self) -> scalar {
-        self.native().fIdeographicBaseline
+        self.native_paragraph().fIdeographicBaseline
     }
 
     pub fn longest_line(&self) -> scalar {
-        self.native().fLongestLine
+        self.native_paragraph().fLongestLine
     }
 
     pub fn did_exceed_max_lines(&self) -> bool {
-        self.native().fExceededMaxLines
+        self.native_paragraph().fExceededMaxLines
     }
 
     pub fn layout(&mut self, width: scalar) {
-        unsafe { sb::C_Paragraph_layout(self.native_mut(), width) }
+        unsafe { sb::C_Paragraph_layout(self.native_paragraph_mut_force(), width) }
+        self.update_soft_hyphens();
     }
 
+    /// Paints the paragraph at `p`, including the hyphens at the soft hyphens lines were broken
+    /// at, see [`Self::soft_hyphens()`].
     pub fn paint(&self, canvas: &mut Canvas, p: impl Into<Point>) {
         let p = p.into();
-        unsafe { sb::C_Paragraph_paint(self.native_mut_force(), canvas.native_mut(), p.x, p.y) }
+        unsafe {
+            sb::C_Paragraph_paint(
+                self.native_paragraph_mut_force(),
+                canvas.native_mut(),
+                p.x,
+                p.y,
+            )
+        }
+        self.paint_soft_hyphens(canvas, p);
     }
 
     pub fn get_rects_for_range(
@@ -94,7 +123,7 @@ impl Paragraph {
 
     pub fn get_rects_for_placeholders(&self) -> TextBoxes {
         TextBoxes::construct(|tb| unsafe {
-            sb::C_Paragraph_getRectsForPlaceholders(self.native_mut_force(), tb)
+            sb::C_Paragraph_getRectsForPlaceholders(self.native_paragraph_mut_force(), tb)
         })
     }
 
@@ -123,14 +152,472 @@ impl Paragraph {
     }
 
     pub fn line_number(&self) -> usize {
-        unsafe { sb::C_Paragraph_lineNumber(self.native_mut_force()) }
+        unsafe { sb::C_Paragraph_lineNumber(self.native_paragraph_mut_force()) }
     }
 
     pub fn mark_dirty(&mut self) {
-        unsafe { sb::C_Paragraph_markDirty(self.native_mut()) }
+        unsafe { sb::C_Paragraph_markDirty(self.native_paragraph_mut_force()) }
+    }
+
+    /// Changes the text alignment of the paragraph.
+    ///
+    /// The text is not reshaped, the next [`Self::layout()`] only repositions the lines.
+    pub fn update_text_align(&mut self, text_align: TextAlign) {
+        unsafe { sb::C_Paragraph_updateTextAlign(self.native_paragraph_mut_force(), text_align) }
+    }
+
+    /// Changes the font size of the text in `range`.
+    ///
+    /// Skia can only update the style of the whole text (last checked: m91). To update a part of
+    /// the text, the paragraph is built again from its styles, which reshapes all of the text.
+    /// Either way, call [`Self::layout()`] before painting again.
+    pub fn update_font_size(&mut self, range: Range<usize>, font_size: scalar) {
+        unsafe {
+            sb::C_Paragraph_updateFontSize(self.native_mut(), range.start, range.end, font_size)
+        }
+    }
+
+    /// Changes the foreground paint of the text in `range`.
+    ///
+    /// Paints don't affect the shaping, but like [`Self::update_font_size()`], updating a part of
+    /// the text builds the paragraph again.
+    pub fn update_foreground_paint(&mut self, range: Range<usize>, paint: &Paint) {
+        unsafe {
+            sb::C_Paragraph_updateForegroundPaint(
+                self.native_mut(),
+                range.start,
+                range.end,
+                paint.native(),
+            )
+        }
+    }
+
+    /// Changes the background paint of the text in `range`.
+    ///
+    /// Paints don't affect the shaping, but like [`Self::update_font_size()`], updating a part of
+    /// the text builds the paragraph again.
+    pub fn update_background_paint(&mut self, range: Range<usize>, paint: &Paint) {
+        unsafe {
+            sb::C_Paragraph_updateBackgroundPaint(
+                self.native_mut(),
+                range.start,
+                range.end,
+                paint.native(),
+            )
+        }
+    }
+
+    /// Calls `visitor` for every run of glyphs of the laid out lines, in order.
+    ///
+    /// The first argument is the line number. At the end of each line, `visitor` is called with
+    /// `None`.
+    pub fn visit<F>(&self, mut visitor: F)
+    where
+        F: FnMut(usize, Option<&VisitorInfo>),
+    {
+        unsafe {
+            sb::C_Paragraph_visit(
+                self.native_mut_force(),
+                &mut visitor as *mut F as *mut c_void,
+                Some(visitor_trampoline::<F>),
+            )
+        };
+
+        unsafe extern "C" fn visitor_trampoline<F: FnMut(usize, Option<&VisitorInfo>)>(
+            context: *mut c_void,
+            line_number: raw::c_int,
+            info: *const sb::skia_textlayout_Paragraph_VisitorInfo,
+        ) {
+            let visitor = &mut *(context as *mut F);
+            visitor(
+                line_number.try_into().unwrap(),
+                info.into_option()
+                    .map(|info| VisitorInfo::from_native_ref(&*info)),
+            )
+        }
+    }
+
+    /// Returns the glyph cluster that contains the UTF-8 code unit at `code_unit_index`.
+    ///
+    /// This collects the clusters of the whole paragraph, use [`Self::glyph_clusters()`] for
+    /// repeated lookups.
+    pub fn get_glyph_cluster_at(&self, code_unit_index: usize) -> Option<GlyphClusterInfo> {
+        self.glyph_clusters()
+            .get_cluster_at(code_unit_index)
+            .cloned()
+    }
+
+    /// Returns the glyph cluster closest to the coordinate `d`, see
+    /// [`GlyphClusters::get_closest_cluster_at()`].
+    ///
+    /// This collects the clusters of the whole paragraph, use [`Self::glyph_clusters()`] for
+    /// repeated lookups.
+    pub fn get_closest_glyph_cluster_at(&self, d: impl Into<Point>) -> Option<GlyphClusterInfo> {
+        self.glyph_clusters().get_closest_cluster_at(d).cloned()
+    }
+
+    /// Returns the outlines of the paragraph's glyphs, placed like [`Self::paint()`] would draw
+    /// them at `p`.
+    ///
+    /// The hyphens [`Self::paint()`] draws at soft hyphens are included, backgrounds, shadows,
+    /// decorations and placeholders are not.
+    pub fn to_path(&self, p: impl Into<Point>) -> Path {
+        let mut path = Path::default();
+        for line in self.to_line_paths(p) {
+            path.add_path(&line, (0, 0), None);
+        }
+        path
+    }
+
+    /// Returns the outlines of the glyphs of each line, see [`Self::to_path()`].
+    pub fn to_line_paths(&self, p: impl Into<Point>) -> Vec<Path> {
+        let p = p.into();
+        let mut paths = vec![Path::default(); self.line_number()];
+        self.visit(|line_number, info| {
+            let info = match info {
+                Some(info) => info,
+                None => return,
+            };
+            if line_number >= paths.len() {
+                paths.resize(line_number + 1, Path::default());
+            }
+            let path = &mut paths[line_number];
+            let origin = p + info.origin();
+            let glyph_paths = info.font().get_paths_vec(info.glyphs());
+            for (glyph_path, position) in glyph_paths.iter().zip(info.positions()) {
+                if let Some(glyph_path) = glyph_path {
+                    path.add_path(glyph_path, origin + *position, None);
+                }
+            }
+        });
+        for hyphen in self.soft_hyphens() {
+            let glyph = hyphen.font.unichar_to_glyph('-' as Unichar);
+            if let (Some(glyph_path), Some(path)) = (
+                hyphen.font.get_path(glyph),
+                paths.get_mut(hyphen.line_number),
+            ) {
+                path.add_path(&glyph_path, p + hyphen.position, None);
+            }
+        }
+        paths
+    }
+
+    /// The text the paragraph was built from, placeholders are represented by the object
+    /// replacement character `U+FFFC`. The breaks inserted by a [`super::LineBreaker`] are not
+    /// part of it.
+    pub fn text(&self) -> &str {
+        let bytes = unsafe {
+            let mut len = 0;
+            let ptr = sb::C_Paragraph_text(self.native(), &mut len);
+            safer::from_raw_parts(ptr as *const u8, len)
+        };
+        std::str::from_utf8(bytes).unwrap()
+    }
+
+    /// The styled ranges of the text, in text order.
+    pub fn styles(&self) -> &[Block] {
+        unsafe {
+            let mut count = 0;
+            let ptr = sb::C_Paragraph_styles(self.native(), &mut count);
+            safer::from_raw_parts(Block::from_native_ptr(ptr), count)
+        }
+    }
+
+    /// Returns the style of the text at the UTF-8 offset `offset`.
+    pub fn get_style_at(&self, offset: usize) -> Option<&TextStyle> {
+        self.styles()
+            .iter()
+            .find(|block| block.range.contains(&offset))
+            .map(|block| &block.style)
+    }
+
+    /// The placeholders in the order they were added to the [`super::ParagraphBuilder`].
+    pub fn placeholders(&self) -> &[Placeholder] {
+        unsafe {
+            let mut count = 0;
+            let ptr = sb::C_Paragraph_placeholders(self.native(), &mut count);
+            safer::from_raw_parts(Placeholder::from_native_ptr(ptr), count)
+        }
+    }
+
+    /// The ids of the [`Self::placeholders()`], see
+    /// [`super::ParagraphBuilder::add_placeholder_with_id()`].
+    pub fn placeholder_ids(&self) -> &[usize] {
+        unsafe {
+            let mut count = 0;
+            let ptr = sb::C_Paragraph_placeholderIds(self.native(), &mut count);
+            safer::from_raw_parts(ptr, count)
+        }
+    }
+
+    /// Returns the boxes of the placeholders that were laid out, together with the indices and ids
+    /// of the placeholders.
+    pub fn get_placeholder_boxes(&self) -> Vec<PlaceholderBox> {
+        let text = self.text();
+        self.placeholders()
+            .iter()
+            .zip(self.placeholder_ids())
+            .enumerate()
+            .filter_map(|(index, (placeholder, id))| {
+                let start = text.get(..placeholder.range.start)?.encode_utf16().count();
+                let end = start + text.get(placeholder.range.clone())?.encode_utf16().count();
+                let boxes = self.get_rects_for_range(
+                    start..end,
+                    RectHeightStyle::Tight,
+                    RectWidthStyle::Tight,
+                );
+                let text_box = *boxes.as_slice().first()?;
+                Some(PlaceholderBox {
+                    index,
+                    id: *id,
+                    text_box,
+                })
+            })
+            .collect()
+    }
+
+    /// Collects the glyph clusters of all lines.
+    ///
+    /// The clusters reflect the current layout, they need to be collected again after the
+    /// paragraph was laid out.
+    pub fn glyph_clusters(&self) -> GlyphClusters {
+        let line_metrics = self.get_line_metrics();
+        let line_metrics = line_metrics.as_slice();
+        let mut clusters: Vec<GlyphClusterInfo> = Vec::new();
+        let mut sorted_starts: Vec<u32> = Vec::new();
+        self.visit(|line_number, info| {
+            let (info, metrics) = match (info, line_metrics.get(line_number)) {
+                (Some(info), Some(metrics)) => (info, metrics),
+                _ => return,
+            };
+            let top = (metrics.baseline - metrics.ascent) as scalar;
+            let bottom = (metrics.baseline + metrics.descent) as scalar;
+            let origin = info.origin();
+            let positions = info.positions();
+            let utf8_starts = info.utf8_starts();
+            // Right-to-left runs list their clusters in descending order.
+            sorted_starts.clear();
+            sorted_starts.extend_from_slice(utf8_starts);
+            sorted_starts.sort_unstable();
+            sorted_starts.dedup();
+            for (i, position) in positions.iter().enumerate() {
+                let start = utf8_starts[i];
+                let next = match sorted_starts.binary_search(&start) {
+                    Ok(j) => j + 1,
+                    Err(j) => j,
+                };
+                let end = match sorted_starts.get(next) {
+                    Some(end) => *end as usize,
+                    None => continue,
+                };
+                let start = start as usize;
+                let left = origin.x + position.x;
+                let right = origin.x
+                    + positions
+                        .get(i + 1)
+                        .map(|p| p.x)
+                        .unwrap_or_else(|| info.advance_x());
+                match clusters.last_mut() {
+                    Some(last) if last.text_range == (start..end) => {
+                        last.bounds.left = last.bounds.left.min(left);
+                        last.bounds.right = last.bounds.right.max(right);
+                    }
+                    _ => clusters.push(GlyphClusterInfo {
+                        bounds: Rect::new(left, top, right, bottom),
+                        text_range: start..end,
+                        line_number,
+                    }),
+                }
+            }
+        });
+        GlyphClusters::new(clusters)
     }
 }
 
+/// A run of glyphs passed to the visitor of [`Paragraph::visit()`].
+#[repr(transparent)]
+pub struct VisitorInfo(sb::skia_textlayout_Paragraph_VisitorInfo);
+
+impl NativeTransmutable<sb::skia_textlayout_Paragraph_VisitorInfo> for VisitorInfo {}
+
+impl fmt::Debug for VisitorInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VisitorInfo")
+            .field("font", self.font())
+            .field("origin", &self.origin())
+            .field("advance_x", &self.advance_x())
+            .field("glyphs", &self.glyphs())
+            .field("positions", &self.positions())
+            .field("utf8_starts", &self.utf8_starts())
+            .finish()
+    }
+}
+
+impl VisitorInfo {
+    pub fn font(&self) -> &Font {
+        Font::from_native_ref(unsafe { &*self.native().font })
+    }
+
+    /// The origin of the run, relative to the paragraph.
+    pub fn origin(&self) -> Point {
+        Point::from_native_c(self.native().origin)
+    }
+
+    pub fn advance_x(&self) -> scalar {
+        self.native().advanceX
+    }
+
+    pub fn count(&self) -> usize {
+        self.native().count.try_into().unwrap()
+    }
+
+    pub fn glyphs(&self) -> &[GlyphId] {
+        unsafe { safer::from_raw_parts(self.native().glyphs, self.count()) }
+    }
+
+    /// The glyph positions, relative to [`Self::origin()`].
+    pub fn positions(&self) -> &[Point] {
+        unsafe {
+            safer::from_raw_parts(
+                Point::from_native_ptr(self.native().positions),
+                self.count(),
+            )
+        }
+    }
+
+    /// The UTF-8 offsets of the glyphs' text in the paragraph, `count() + 1` values.
+    pub fn utf8_starts(&self) -> &[u32] {
+        unsafe { safer::from_raw_parts(self.native().utf8Starts, self.count() + 1) }
+    }
+}
+
+/// The glyph clusters of a laid out paragraph, line by line in visual order.
+///
+/// Returned by [`Paragraph::glyph_clusters()`].
+#[derive(Clone, Default, Debug)]
+pub struct GlyphClusters {
+    clusters: Vec<GlyphClusterInfo>,
+    /// The bottom and the range of the clusters of each line that contains clusters.
+    lines: Vec<(scalar, Range<usize>)>,
+    /// The indices of the clusters, ordered by their text ranges.
+    text_order: Vec<usize>,
+}
+
+impl Deref for GlyphClusters {
+    type Target = [GlyphClusterInfo];
+
+    fn deref(&self) -> &Self::Target {
+        &self.clusters
+    }
+}
+
+impl GlyphClusters {
+    fn new(clusters: Vec<GlyphClusterInfo>) -> Self {
+        let mut lines: Vec<(scalar, Range<usize>)> = Vec::new();
+        for (i, cluster) in clusters.iter().enumerate() {
+            match lines.last_mut() {
+                Some((_, range)) if clusters[range.start].line_number == cluster.line_number => {
+                    range.end = i + 1
+                }
+                _ => lines.push((cluster.bounds.bottom, i..i + 1)),
+            }
+        }
+        let mut text_order: Vec<usize> = (0..clusters.len()).collect();
+        text_order.sort_by_key(|i| clusters[*i].text_range.start);
+        Self {
+            clusters,
+            lines,
+            text_order,
+        }
+    }
+
+    /// Returns the cluster that contains the UTF-8 code unit at `code_unit_index`.
+    pub fn get_cluster_at(&self, code_unit_index: usize) -> Option<&GlyphClusterInfo> {
+        let i = match self
+            .text_order
+            .binary_search_by_key(&code_unit_index, |i| self.clusters[*i].text_range.start)
+        {
+            Ok(i) => i,
+            Err(0) => return None,
+            Err(i) => i - 1,
+        };
+        let cluster = &self.clusters[self.text_order[i]];
+        if cluster.text_range.contains(&code_unit_index) {
+            Some(cluster)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the cluster closest to the coordinate `d`.
+    ///
+    /// Coordinates above or below the paragraph refer to the first or last line. Returns `None` if
+    /// there are no clusters or if a coordinate of `d` is NaN.
+    pub fn get_closest_cluster_at(&self, d: impl Into<Point>) -> Option<&GlyphClusterInfo> {
+        let d = d.into();
+        if d.x.is_nan() || d.y.is_nan() {
+            return None;
+        }
+
+        // The first line and the first cluster of that line that end after `d`.
+        let line = self
+            .lines
+            .binary_search_by(|(bottom, _)| if *bottom <= d.y { Less } else { Greater })
+            .unwrap_or_else(|i| i);
+        let (_, range) = self.lines.get(line).or_else(|| self.lines.last())?;
+        let clusters = &self.clusters[range.clone()];
+        let i = clusters
+            .binary_search_by(|cluster| {
+                if cluster.bounds.right <= d.x {
+                    Less
+                } else {
+                    Greater
+                }
+            })
+            .unwrap_or_else(|i| i);
+
+        let distance = |cluster: &GlyphClusterInfo| {
+            (cluster.bounds.left - d.x)
+                .max(d.x - cluster.bounds.right)
+                .max(0.0)
+        };
+        let mut closest: Option<&GlyphClusterInfo> = None;
+        for cluster in i
+            .checked_sub(1)
+            .into_iter()
+            .chain(Some(i))
+            .filter_map(|i| clusters.get(i))
+        {
+            if closest.map_or(true, |closest| distance(cluster) < distance(closest)) {
+                closest = Some(cluster);
+            }
+        }
+        closest
+    }
+}
+
+/// A glyph cluster returned by [`GlyphClusters`], [`Paragraph::get_glyph_cluster_at()`] and
+/// [`Paragraph::get_closest_glyph_cluster_at()`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct GlyphClusterInfo {
+    /// The bounds of the cluster, relative to the paragraph, spanning the height of the line.
+    pub bounds: Rect,
+    /// The UTF-8 range of the cluster's text.
+    pub text_range: Range<usize>,
+    pub line_number: usize,
+}
+
+/// A placeholder's box returned by [`Paragraph::get_placeholder_boxes()`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct PlaceholderBox {
+    /// The index of the placeholder in [`Paragraph::placeholders()`].
+    pub index: usize,
+    /// The id the placeholder was added with, see
+    /// [`super::ParagraphBuilder::add_placeholder_with_id()`].
+    pub id: usize,
+    pub text_box: TextBox,
+}
+
 pub type TextBoxes = Handle<sb::TextBoxes>;
 
 impl NativeDrop for sb::TextBoxes {
@@ -242,3 +729,198 @@ fn test_line_metrics() {
 
     static LOREM_IPSUM: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit. Curabitur at leo at nulla tincidunt placerat. Proin eget purus augue. Quisque et est ullamcorper, pellentesque felis nec, pulvinar massa. Aliquam imperdiet, nulla ut dictum euismod, purus dui pulvinar risus, eu suscipit elit neque ac est. Nullam eleifend justo quis placerat ultricies. Vestibulum ut elementum velit. Praesent et dolor sit amet purus bibendum mattis. Aliquam erat volutpat.";
 }
+
+#[test]
+#[serial_test::serial]
+fn test_visit_and_glyph_clusters() {
+    let paragraph = super::tests::layout("Hello World", 256.0);
+
+    let mut glyphs = 0;
+    let mut line_ends = 0;
+    paragraph.visit(|line_number, info| {
+        assert_eq!(line_number, 0);
+        match info {
+            Some(info) => {
+                assert_eq!(info.positions().len(), info.glyphs().len());
+                glyphs += info.count();
+            }
+            None => line_ends += 1,
+        }
+    });
+    assert!(glyphs > 0);
+    assert_eq!(line_ends, 1);
+
+    let cluster = paragraph.get_glyph_cluster_at(6).unwrap();
+    assert_eq!(cluster.text_range, 6..7);
+    assert_eq!(cluster.line_number, 0);
+    let closest = paragraph
+        .get_closest_glyph_cluster_at(cluster.bounds.center())
+        .unwrap();
+    assert_eq!(closest, cluster);
+    assert_eq!(
+        paragraph
+            .get_closest_glyph_cluster_at((-100, -100))
+            .unwrap()
+            .text_range,
+        0..1
+    );
+
+    let clusters = paragraph.glyph_clusters();
+    assert_eq!(clusters.len(), 11);
+    assert_eq!(clusters.get_cluster_at(6), Some(&cluster));
+    assert_eq!(clusters.get_cluster_at(11), None);
+    assert_eq!(
+        clusters
+            .get_closest_cluster_at((1000, 1000))
+            .unwrap()
+            .text_range,
+        10..11
+    );
+    assert_eq!(clusters.get_closest_cluster_at((scalar::NAN, 0.0)), None);
+}
+
+#[test]
+#[serial_test::serial]
+fn test_update_text_align() {
+    let mut paragraph = super::tests::layout("Hello", 256.0);
+    let left = paragraph.get_line_metrics()[0].left;
+
+    paragraph.update_text_align(TextAlign::Right);
+    paragraph.layout(256.0);
+    assert!(paragraph.get_line_metrics()[0].left > left);
+
+    let height = paragraph.height();
+    let font_size = TextStyle::new().font_size();
+    paragraph.update_font_size(0..5, font_size * 2.0);
+    paragraph.layout(256.0);
+    assert!(paragraph.height() > height);
+    assert_eq!(
+        paragraph.get_style_at(0).unwrap().font_size(),
+        font_size * 2.0
+    );
+}
+
+#[test]
+#[serial_test::serial]
+#[allow(clippy::float_cmp)]
+fn test_update_part_of_the_text() {
+    use crate::Color;
+
+    let mut paragraph = super::tests::layout("Hello World", 256.0);
+    let font_size = TextStyle::new().font_size();
+    let width = paragraph.max_intrinsic_width();
+    paragraph.update_font_size(6..11, font_size * 2.0);
+    paragraph.update_foreground_paint(0..2, Paint::default().set_color(Color::RED));
+    paragraph.layout(256.0);
+    assert!(paragraph.max_intrinsic_width() > width);
+    assert_eq!(paragraph.text(), "Hello World");
+
+    let style = |offset| paragraph.get_style_at(offset).unwrap();
+    assert_eq!(style(5).font_size(), font_size);
+    assert_eq!(style(6).font_size(), font_size * 2.0);
+    assert_eq!(style(0).foreground().unwrap().color(), Color::RED);
+    assert!(style(2).foreground().is_none());
+    assert_eq!(paragraph.styles().len(), 3);
+}
+
+#[test]
+#[serial_test::serial]
+fn test_to_path() {
+    let paragraph = super::tests::layout("Hello\nWorld", 256.0);
+
+    let lines = paragraph.to_line_paths((10, 20));
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].bounds().bottom < lines[1].bounds().top);
+    let path = paragraph.to_path((10, 20));
+    assert!(path.bounds().left >= 10.0);
+    assert!(path.bounds().top >= 20.0);
+    assert_eq!(
+        path.count_verbs(),
+        lines[0].count_verbs() + lines[1].count_verbs()
+    );
+}
+
+#[test]
+#[serial_test::serial]
+fn test_to_path_includes_soft_hyphens() {
+    use crate::{textlayout::Hyphenator, Color};
+
+    let mut builder = super::tests::builder();
+    let hyphenator = Hyphenator::new("hy3ph he2n hena4 hen5at 1na n2at 1tio 2io o2n").unwrap();
+    builder.set_line_breaker(Some(Box::new(hyphenator)));
+    let mut style = TextStyle::new();
+    style.set_color(Color::BLACK);
+    builder.push_style(&style);
+    builder.add_text("hyphenation ".repeat(4));
+    let mut paragraph = builder.build();
+    paragraph.layout(40.0);
+    let hyphen = paragraph.soft_hyphens()[0].clone();
+
+    // The painted pixels and the pixels of the filled outline behind the end of the line.
+    let (width, height) = (64, paragraph.height().ceil() as i32);
+    let pixels_behind_line_end = |draw: &dyn Fn(&mut Canvas)| {
+        let mut pixels = vec![0u32; (width * height) as usize];
+        let mut canvas =
+            Canvas::from_raster_direct_n32((width, height), &mut pixels, None).unwrap();
+        draw(&mut canvas);
+        drop(canvas);
+        let (x, y) = (hyphen.position.x as i32, hyphen.position.y as i32);
+        let size = hyphen.font.size() as i32;
+        (x..(x + size).min(width))
+            .flat_map(|x| (y - size..y).map(move |y| (x, y)))
+            .filter(|(x, y)| pixels[(y * width + x) as usize] != 0)
+            .count()
+    };
+    let painted = pixels_behind_line_end(&|canvas| paragraph.paint(canvas, (0, 0)));
+    let outlined = pixels_behind_line_end(&|canvas| {
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        canvas.draw_path(&paragraph.to_path((0, 0)), &paint);
+    });
+    assert!(painted > 0);
+    assert!(outlined > 0);
+    let line = &paragraph.to_line_paths((0, 0))[hyphen.line_number];
+    assert!(line.bounds().right > hyphen.position.x);
+}
+
+#[test]
+#[serial_test::serial]
+#[allow(clippy::float_cmp)]
+fn test_placeholders_and_styles() {
+    use crate::textlayout::{PlaceholderAlignment, PlaceholderStyle, TextBaseline, TextStyle};
+
+    let mut paragraph_builder = super::tests::builder();
+    let mut style = TextStyle::new();
+    paragraph_builder.push_style(&style);
+    paragraph_builder.add_text("ab");
+    let placeholder = |width| {
+        PlaceholderStyle::new(
+            width,
+            10.0,
+            PlaceholderAlignment::Baseline,
+            TextBaseline::Alphabetic,
+            0.0,
+        )
+    };
+    paragraph_builder.add_placeholder(&placeholder(10.0));
+    style.set_font_size(30.0);
+    paragraph_builder.push_style(&style);
+    paragraph_builder.add_text("cd");
+    paragraph_builder.add_placeholder_with_id(&placeholder(20.0), 42);
+    let mut paragraph = paragraph_builder.build();
+    paragraph.layout(256.0);
+
+    assert_eq!(paragraph.text(), "ab\u{fffc}cd\u{fffc}");
+    assert_eq!(paragraph.placeholders().len(), 2);
+    let boxes = paragraph.get_placeholder_boxes();
+    assert_eq!(boxes.len(), 2);
+    assert_eq!(boxes[0].id, 0);
+    assert_eq!(boxes[1].index, 1);
+    assert_eq!(boxes[1].id, 42);
+    assert!(boxes[0].text_box.rect.right <= boxes[1].text_box.rect.left);
+    assert!((boxes[1].text_box.rect.width() - 20.0).abs() < 0.5);
+
+    assert_eq!(paragraph.get_style_at(0).unwrap().font_size(), 14.0);
+    assert_eq!(paragraph.get_style_at(5).unwrap().font_size(), 30.0);
+    assert!(paragraph.get_style_at(100).is_none());
+}
@@ -3,11 +3,11 @@ use crate::prelude::*;
 use skia_bindings as sb;
 use std::{fmt, os::raw};
 
-pub type ParagraphBuilder = RefHandle<sb::skia_textlayout_ParagraphBuilder>;
+pub type ParagraphBuilder = RefHandle<sb::RustParagraphBuilder>;
 unsafe impl Send for ParagraphBuilder {}
 unsafe impl Sync for ParagraphBuilder {}
 
-impl NativeDrop for sb::skia_textlayout_ParagraphBuilder {
+impl NativeDrop for sb::RustParagraphBuilder {
     fn drop(&mut self) {
         unsafe { sb::C_ParagraphBuilder_delete(self) }
     }
@@ -36,8 +36,19 @@ impl ParagraphBuilder {
         ts
     }
 
+    /// Adds `str`, with the break opportunities of the line breaker if one was set with
+    /// [`Self::set_line_breaker()`].
     pub fn add_text(&mut self, str: impl AsRef<str>) -> &mut Self {
         let str = str.as_ref();
+        match self.line_breaker_ptr() {
+            // The line breaker is owned by the native builder and stays in place while text is
+            // added.
+            Some(line_breaker) => self.add_text_with_line_breaker(str, unsafe { &*line_breaker }),
+            None => self.add_text_unbroken(str),
+        }
+    }
+
+    pub(super) fn add_text_unbroken(&mut self, str: &str) -> &mut Self {
         unsafe {
             sb::C_ParagraphBuilder_addText(
                 self.native_mut(),
@@ -48,13 +59,40 @@ impl ParagraphBuilder {
         self
     }
 
+    /// Adds a placeholder, its id is the index of the placeholder in
+    /// [`Paragraph::placeholders()`].
     pub fn add_placeholder(&mut self, placeholder_style: &PlaceholderStyle) -> &mut Self {
+        let id = self.placeholder_count();
+        self.add_placeholder_with_id(placeholder_style, id)
+    }
+
+    /// Adds a placeholder that can be identified by `id` in [`Paragraph::placeholder_ids()`] and
+    /// [`Paragraph::get_placeholder_boxes()`].
+    pub fn add_placeholder_with_id(
+        &mut self,
+        placeholder_style: &PlaceholderStyle,
+        id: usize,
+    ) -> &mut Self {
         unsafe {
-            sb::C_ParagraphBuilder_addPlaceholder(self.native_mut(), placeholder_style.native())
+            sb::C_ParagraphBuilder_addPlaceholder(self.native_mut(), placeholder_style.native(), id)
         }
         self
     }
 
+    pub(super) fn placeholder_count(&self) -> usize {
+        unsafe { sb::C_ParagraphBuilder_placeholderCount(self.native()) }
+    }
+
+    /// The text added so far, see [`Paragraph::text()`].
+    pub(super) fn text(&self) -> &str {
+        let bytes = unsafe {
+            let mut len = 0;
+            let ptr = sb::C_ParagraphBuilder_text(self.native(), &mut len);
+            safer::from_raw_parts(ptr as *const u8, len)
+        };
+        std::str::from_utf8(bytes).unwrap()
+    }
+
     pub fn set_paragraph_style(&mut self, style: &ParagraphStyle) -> &mut Self {
         unsafe { sb::C_ParagraphBuilder_setParagraphStyle(self.native_mut(), style.native()) }
         self
@@ -0,0 +1,245 @@
+use super::Paragraph;
+use crate::{canvas::PointMode, prelude::*, scalar, Paint, Path, Point, Rect, TextBlob, Vector};
+use skia_bindings as sb;
+use std::ffi::c_void;
+
+/// Receives the drawing commands of [`Paragraph::paint_with_painter()`].
+///
+/// A paragraph is painted line by line: first the backgrounds, then the shadows, then the text and
+/// then the decorations of each line. Coordinates are relative to the current transformation,
+/// which is only changed by [`Self::translate()`] and restored by [`Self::restore()`].
+///
+/// All methods do nothing by default.
+#[allow(unused_variables)]
+pub trait ParagraphPainter {
+    /// Draws a run of text with the foreground paint of its style.
+    fn draw_text_blob(&mut self, blob: &TextBlob, origin: Point, paint: &Paint) {}
+
+    /// Draws a run of text with the paint of one of the shadows of its style.
+    ///
+    /// Shadows are told apart from text by comparing the paints to the shadows of the
+    /// paragraph's styles. A shadow that is drawn with the same paint as the text of a style, which
+    /// only happens for a shadow without blur that has the text's color, is drawn with
+    /// [`Self::draw_text_blob()`].
+    fn draw_text_shadow(&mut self, blob: &TextBlob, origin: Point, paint: &Paint) {}
+
+    /// Draws the background of a run.
+    fn draw_rect(&mut self, rect: &Rect, paint: &Paint) {}
+
+    /// Draws a solid, double, dotted or dashed decoration line.
+    fn draw_line(&mut self, p0: Point, p1: Point, paint: &Paint) {}
+
+    /// Draws points, lines or a polygon.
+    ///
+    /// Decoration lines are drawn as [`PointMode::Lines`]. By default, lines and the segments of
+    /// polygons are forwarded to [`Self::draw_line()`] and points are ignored.
+    fn draw_points(&mut self, mode: PointMode, points: &[Point], paint: &Paint) {
+        match mode {
+            PointMode::Points => {}
+            PointMode::Lines => {
+                for line in points.chunks_exact(2) {
+                    self.draw_line(line[0], line[1], paint)
+                }
+            }
+            PointMode::Polygon => {
+                for line in points.windows(2) {
+                    self.draw_line(line[0], line[1], paint)
+                }
+            }
+        }
+    }
+
+    /// Draws a wavy decoration line.
+    fn draw_path(&mut self, path: &Path, paint: &Paint) {}
+
+    fn clip_rect(&mut self, rect: &Rect) {}
+
+    fn translate(&mut self, d: Vector) {}
+
+    fn save(&mut self) {}
+
+    fn restore(&mut self) {}
+}
+
+impl Paragraph {
+    /// Paints the paragraph at `p` by sending its drawing commands to `painter`.
+    ///
+    /// The hyphens at soft hyphens are sent last, with [`ParagraphPainter::draw_text_blob()`].
+    pub fn paint_with_painter(&self, painter: &mut impl ParagraphPainter, p: impl Into<Point>) {
+        let p = p.into();
+        let param = param(painter);
+        unsafe { sb::C_Paragraph_paintWithPainter(self.native_mut_force(), &param, p.x, p.y) }
+        self.paint_soft_hyphens_with_painter(painter, p);
+    }
+}
+
+fn param<P: ParagraphPainter>(painter: &mut P) -> sb::RustParagraphPainterParam {
+    unsafe fn as_painter<'a, P>(data: *mut c_void) -> &'a mut P {
+        &mut *(data as *mut P)
+    }
+
+    /// `blob` must outlive the returned reference, which points to the pointer itself.
+    unsafe fn text_blob(blob: &*mut sb::SkTextBlob) -> &TextBlob {
+        TextBlob::from_unshared_ptr_ref(blob).as_ref().unwrap()
+    }
+
+    unsafe extern "C" fn draw_text_blob<P: ParagraphPainter>(
+        data: *mut c_void,
+        blob: *const sb::SkTextBlob,
+        x: scalar,
+        y: scalar,
+        paint: *const sb::SkPaint,
+    ) {
+        let blob = blob as *mut _;
+        as_painter::<P>(data).draw_text_blob(
+            text_blob(&blob),
+            Point::new(x, y),
+            Paint::from_native_ref(&*paint),
+        )
+    }
+
+    unsafe extern "C" fn draw_text_shadow<P: ParagraphPainter>(
+        data: *mut c_void,
+        blob: *const sb::SkTextBlob,
+        x: scalar,
+        y: scalar,
+        paint: *const sb::SkPaint,
+    ) {
+        let blob = blob as *mut _;
+        as_painter::<P>(data).draw_text_shadow(
+            text_blob(&blob),
+            Point::new(x, y),
+            Paint::from_native_ref(&*paint),
+        )
+    }
+
+    unsafe extern "C" fn draw_rect<P: ParagraphPainter>(
+        data: *mut c_void,
+        rect: *const sb::SkRect,
+        paint: *const sb::SkPaint,
+    ) {
+        as_painter::<P>(data).draw_rect(
+            Rect::from_native_ref(&*rect),
+            Paint::from_native_ref(&*paint),
+        )
+    }
+
+    unsafe extern "C" fn draw_points<P: ParagraphPainter>(
+        data: *mut c_void,
+        mode: PointMode,
+        count: usize,
+        points: *const sb::SkPoint,
+        paint: *const sb::SkPaint,
+    ) {
+        as_painter::<P>(data).draw_points(
+            mode,
+            safer::from_raw_parts(Point::from_native_ptr(points), count),
+            Paint::from_native_ref(&*paint),
+        )
+    }
+
+    unsafe extern "C" fn draw_path<P: ParagraphPainter>(
+        data: *mut c_void,
+        path: *const sb::SkPath,
+        paint: *const sb::SkPaint,
+    ) {
+        as_painter::<P>(data).draw_path(
+            Path::from_native_ref(&*path),
+            Paint::from_native_ref(&*paint),
+        )
+    }
+
+    unsafe extern "C" fn clip_rect<P: ParagraphPainter>(
+        data: *mut c_void,
+        rect: *const sb::SkRect,
+    ) {
+        as_painter::<P>(data).clip_rect(Rect::from_native_ref(&*rect))
+    }
+
+    unsafe extern "C" fn translate<P: ParagraphPainter>(data: *mut c_void, dx: scalar, dy: scalar) {
+        as_painter::<P>(data).translate(Vector::new(dx, dy))
+    }
+
+    unsafe extern "C" fn save<P: ParagraphPainter>(data: *mut c_void) {
+        as_painter::<P>(data).save()
+    }
+
+    unsafe extern "C" fn restore<P: ParagraphPainter>(data: *mut c_void) {
+        as_painter::<P>(data).restore()
+    }
+
+    sb::RustParagraphPainterParam {
+        data: painter as *mut P as *mut c_void,
+        drawTextBlob: Some(draw_text_blob::<P>),
+        drawTextShadow: Some(draw_text_shadow::<P>),
+        drawRect: Some(draw_rect::<P>),
+        drawPoints: Some(draw_points::<P>),
+        drawPath: Some(draw_path::<P>),
+        clipRect: Some(clip_rect::<P>),
+        translate: Some(translate::<P>),
+        save: Some(save::<P>),
+        restore: Some(restore::<P>),
+    }
+}
+
+#[test]
+#[serial_test::serial]
+fn paint_with_painter_reports_text_and_decorations() {
+    use crate::{
+        textlayout::{TextDecoration, TextShadow, TextStyle},
+        Color,
+    };
+
+    #[derive(Default)]
+    struct Recorder {
+        text_blobs: Vec<Color>,
+        shadows: Vec<Color>,
+        backgrounds: usize,
+        lines: usize,
+        saves: isize,
+    }
+
+    impl ParagraphPainter for Recorder {
+        fn draw_text_blob(&mut self, _blob: &TextBlob, _origin: Point, paint: &Paint) {
+            self.text_blobs.push(paint.color());
+        }
+        fn draw_text_shadow(&mut self, _blob: &TextBlob, _origin: Point, paint: &Paint) {
+            self.shadows.push(paint.color());
+        }
+        fn draw_rect(&mut self, _rect: &Rect, _paint: &Paint) {
+            self.backgrounds += 1;
+        }
+        fn draw_line(&mut self, _p0: Point, _p1: Point, _paint: &Paint) {
+            self.lines += 1;
+        }
+        fn save(&mut self) {
+            self.saves += 1;
+        }
+        fn restore(&mut self) {
+            self.saves -= 1;
+        }
+    }
+
+    let mut builder = super::tests::builder();
+    let mut style = TextStyle::new();
+    style.set_background_color(Paint::default().set_color(Color::YELLOW).clone());
+    style.decoration_mut().ty = TextDecoration::UNDERLINE;
+    style.set_color(Color::BLACK);
+    style.add_shadow(TextShadow::new(Color::RED, (2, 2), 0.0));
+    style.add_shadow(TextShadow::new(Color::BLUE, (0, 0), 2.0));
+    builder.push_style(&style);
+    builder.add_text("Hello World");
+    let mut paragraph = builder.build();
+    paragraph.layout(256.0);
+
+    let mut recorder = Recorder::default();
+    paragraph.paint_with_painter(&mut recorder, (0, 0));
+    assert!(!recorder.text_blobs.is_empty());
+    assert!(recorder.text_blobs.iter().all(|c| *c == Color::BLACK));
+    assert_eq!(recorder.shadows.len(), recorder.text_blobs.len() * 2);
+    assert!(recorder.shadows.contains(&Color::RED));
+    assert!(recorder.shadows.contains(&Color::BLUE));
+    assert!(recorder.backgrounds > 0);
+    assert!(recorder.lines > 0);
+    assert_eq!(recorder.saves, 0);
+}
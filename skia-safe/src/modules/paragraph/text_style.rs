@@ -66,6 +66,114 @@ fn decoration_layout() {
     Decoration::test_layout();
 }
 
+impl Decoration {
+    /// A solid decoration of type `ty` in the text's color that is drawn through descenders, see
+    /// [`Self::with_skip_ink()`].
+    pub fn new(ty: TextDecoration) -> Self {
+        Self {
+            ty,
+            mode: TextDecorationMode::Through,
+            color: Color::TRANSPARENT,
+            style: TextDecorationStyle::Solid,
+            thickness_multiplier: 1.0,
+        }
+    }
+
+    pub fn underline() -> Self {
+        Self::new(TextDecoration::UNDERLINE)
+    }
+
+    pub fn overline() -> Self {
+        Self::new(TextDecoration::OVERLINE)
+    }
+
+    pub fn line_through() -> Self {
+        Self::new(TextDecoration::LINE_THROUGH)
+    }
+
+    #[must_use]
+    pub fn with_style(self, style: TextDecorationStyle) -> Self {
+        Self { style, ..self }
+    }
+
+    #[must_use]
+    pub fn solid(self) -> Self {
+        self.with_style(TextDecorationStyle::Solid)
+    }
+
+    #[must_use]
+    pub fn double(self) -> Self {
+        self.with_style(TextDecorationStyle::Double)
+    }
+
+    #[must_use]
+    pub fn dotted(self) -> Self {
+        self.with_style(TextDecorationStyle::Dotted)
+    }
+
+    #[must_use]
+    pub fn dashed(self) -> Self {
+        self.with_style(TextDecorationStyle::Dashed)
+    }
+
+    #[must_use]
+    pub fn wavy(self) -> Self {
+        self.with_style(TextDecorationStyle::Wavy)
+    }
+
+    /// Sets the color of the decoration, a transparent color uses the text's color.
+    #[must_use]
+    pub fn with_color(self, color: impl Into<Color>) -> Self {
+        Self {
+            color: color.into(),
+            ..self
+        }
+    }
+
+    /// Scales the thickness the font defines for the decoration.
+    #[must_use]
+    pub fn with_thickness_multiplier(self, thickness_multiplier: scalar) -> Self {
+        Self {
+            thickness_multiplier,
+            ..self
+        }
+    }
+
+    /// Leaves gaps where the decoration would cross descenders, only supported by underlines.
+    #[must_use]
+    pub fn with_skip_ink(self, skip_ink: bool) -> Self {
+        let mode = if skip_ink {
+            TextDecorationMode::Gaps
+        } else {
+            TextDecorationMode::Through
+        };
+        Self { mode, ..self }
+    }
+
+    pub fn skip_ink(&self) -> bool {
+        self.mode == TextDecorationMode::Gaps
+    }
+}
+
+#[test]
+fn decoration_builders() {
+    let decoration = Decoration::underline()
+        .wavy()
+        .with_color(Color::RED)
+        .with_thickness_multiplier(2.0)
+        .with_skip_ink(true);
+    assert_eq!(decoration.ty, TextDecoration::UNDERLINE);
+    assert_eq!(decoration.style, TextDecorationStyle::Wavy);
+    assert_eq!(decoration.color, Color::RED);
+    assert!(decoration.skip_ink());
+    assert!(!Decoration::underline().skip_ink());
+
+    let mut style = TextStyle::new();
+    style.set_decoration(&Decoration::line_through().double());
+    assert_eq!(style.decoration().ty, TextDecoration::LINE_THROUGH);
+    assert_eq!(style.decoration().style, TextDecorationStyle::Double);
+}
+
 pub use sb::skia_textlayout_PlaceholderAlignment as PlaceholderAlignment;
 
 #[test]
@@ -275,6 +383,11 @@ impl Handle<sb::skia_textlayout_TextStyle> {
         Decoration::from_native_ref_mut(&mut self.native_mut().fDecoration)
     }
 
+    pub fn set_decoration(&mut self, decoration: &Decoration) -> &mut Self {
+        *self.decoration_mut() = *decoration;
+        self
+    }
+
     pub fn font_style(&self) -> FontStyle {
         FontStyle::from_native_c(self.native().fFontStyle)
     }
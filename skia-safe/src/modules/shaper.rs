@@ -4,10 +4,13 @@ use skia_bindings::{
     SkShaper_LanguageRunIterator, SkShaper_RunHandler, SkShaper_RunIterator,
     SkShaper_ScriptRunIterator, SkTextBlobBuilderRunHandler,
 };
-use std::{ffi::CStr, fmt, marker::PhantomData, os::raw};
+use std::{ffi::CStr, fmt, marker::PhantomData, ops::DerefMut, os::raw};
 
 pub use run_handler::RunHandler;
 
+mod shaped_text;
+pub use shaped_text::*;
+
 pub type Shaper = RefHandle<SkShaper>;
 unsafe impl Send for Shaper {}
 unsafe impl Sync for Shaper {}
@@ -96,6 +99,190 @@ where
     }
 }
 
+/// Traits for run iterators implemented in Rust.
+///
+/// Types that implement these traits can be passed to [`Shaper::shape_with_iterators()`] in place
+/// of Skia's iterators, for example to segment the text by custom font fallback rules.
+pub mod run_iterator {
+    use super::RunIterator;
+    use crate::{Font, FourByteTag};
+    use std::ffi::CStr;
+
+    pub trait FontRunIterator: RunIterator {
+        fn current_font(&self) -> &Font;
+    }
+
+    pub trait BiDiRunIterator: RunIterator {
+        fn current_level(&self) -> u8;
+    }
+
+    pub trait ScriptRunIterator: RunIterator {
+        fn current_script(&self) -> FourByteTag;
+    }
+
+    pub trait LanguageRunIterator: RunIterator {
+        fn current_language(&self) -> &CStr;
+    }
+}
+
+/// Converts run iterators into the native run iterators passed to the shaper.
+///
+/// Implemented for Skia's run iterators and for all types that implement one of the traits in
+/// [`run_iterator`].
+pub trait AsRunIterator<'a, I: 'a> {
+    type RunIterator: DerefMut<Target = I> + 'a;
+    fn as_run_iterator<'b>(&'b mut self) -> Self::RunIterator
+    where
+        'b: 'a;
+}
+
+macro_rules! impl_as_run_iterator {
+    ($iterator:ident, $new_rust_iterator:ident) => {
+        impl<'a> AsRunIterator<'a, $iterator> for $iterator {
+            type RunIterator = &'a mut $iterator;
+
+            fn as_run_iterator<'b>(&'b mut self) -> Self::RunIterator
+            where
+                'b: 'a,
+            {
+                self
+            }
+        }
+
+        impl<'a> AsRunIterator<'a, $iterator> for Borrows<'_, $iterator> {
+            type RunIterator = &'a mut $iterator;
+
+            fn as_run_iterator<'b>(&'b mut self) -> Self::RunIterator
+            where
+                'b: 'a,
+            {
+                self
+            }
+        }
+
+        impl<'a, T: run_iterator::$iterator> AsRunIterator<'a, $iterator> for T {
+            type RunIterator = Borrows<'a, $iterator>;
+
+            fn as_run_iterator<'b>(&'b mut self) -> Self::RunIterator
+            where
+                'b: 'a,
+            {
+                rust_run_iterator::$new_rust_iterator(self)
+            }
+        }
+    };
+}
+
+impl_as_run_iterator!(FontRunIterator, new_font_run_iterator);
+impl_as_run_iterator!(BiDiRunIterator, new_bidi_run_iterator);
+impl_as_run_iterator!(ScriptRunIterator, new_script_run_iterator);
+impl_as_run_iterator!(LanguageRunIterator, new_language_run_iterator);
+
+mod rust_run_iterator {
+    use super::{
+        run_iterator, BiDiRunIterator, FontRunIterator, LanguageRunIterator, RunIterator,
+        ScriptRunIterator,
+    };
+    use crate::prelude::*;
+    use skia_bindings::{self as sb, RustRunIteratorParam, SkFont, SkFourByteTag};
+    use std::{ffi::c_void, os::raw};
+
+    pub fn new_font_run_iterator<I: run_iterator::FontRunIterator>(
+        iterator: &mut I,
+    ) -> Borrows<FontRunIterator> {
+        let iterator: *mut I = iterator;
+        let param = new_param(iterator);
+        FontRunIterator::from_ptr(unsafe {
+            sb::C_RustFontRunIterator_new(&param, Some(current_font::<I>))
+        })
+        .unwrap()
+        .borrows(unsafe { &*iterator })
+    }
+
+    pub fn new_bidi_run_iterator<I: run_iterator::BiDiRunIterator>(
+        iterator: &mut I,
+    ) -> Borrows<BiDiRunIterator> {
+        let iterator: *mut I = iterator;
+        let param = new_param(iterator);
+        BiDiRunIterator::from_ptr(unsafe {
+            sb::C_RustBiDiRunIterator_new(&param, Some(current_level::<I>))
+        })
+        .unwrap()
+        .borrows(unsafe { &*iterator })
+    }
+
+    pub fn new_script_run_iterator<I: run_iterator::ScriptRunIterator>(
+        iterator: &mut I,
+    ) -> Borrows<ScriptRunIterator> {
+        let iterator: *mut I = iterator;
+        let param = new_param(iterator);
+        ScriptRunIterator::from_ptr(unsafe {
+            sb::C_RustScriptRunIterator_new(&param, Some(current_script::<I>))
+        })
+        .unwrap()
+        .borrows(unsafe { &*iterator })
+    }
+
+    pub fn new_language_run_iterator<I: run_iterator::LanguageRunIterator>(
+        iterator: &mut I,
+    ) -> Borrows<LanguageRunIterator> {
+        let iterator: *mut I = iterator;
+        let param = new_param(iterator);
+        LanguageRunIterator::from_ptr(unsafe {
+            sb::C_RustLanguageRunIterator_new(&param, Some(current_language::<I>))
+        })
+        .unwrap()
+        .borrows(unsafe { &*iterator })
+    }
+
+    /// `iterator` is the only pointer the native iterator and the returned [`Borrows`] are
+    /// derived from, so that they stay valid for the lifetime of the borrow.
+    fn new_param<I: RunIterator>(iterator: *mut I) -> RustRunIteratorParam {
+        RustRunIteratorParam {
+            data: iterator as *mut c_void,
+            consume: Some(consume::<I>),
+            endOfCurrentRun: Some(end_of_current_run::<I>),
+            atEnd: Some(at_end::<I>),
+        }
+    }
+
+    extern "C" fn consume<I: RunIterator>(data: *mut c_void) {
+        unsafe { &mut *(data as *mut I) }.consume()
+    }
+
+    extern "C" fn end_of_current_run<I: RunIterator>(data: *mut c_void) -> usize {
+        unsafe { &*(data as *const I) }.end_of_current_run()
+    }
+
+    extern "C" fn at_end<I: RunIterator>(data: *mut c_void) -> bool {
+        unsafe { &*(data as *const I) }.at_end()
+    }
+
+    extern "C" fn current_font<I: run_iterator::FontRunIterator>(
+        data: *mut c_void,
+    ) -> *const SkFont {
+        unsafe { &*(data as *const I) }.current_font().native()
+    }
+
+    extern "C" fn current_level<I: run_iterator::BiDiRunIterator>(data: *mut c_void) -> u8 {
+        unsafe { &*(data as *const I) }.current_level()
+    }
+
+    extern "C" fn current_script<I: run_iterator::ScriptRunIterator>(
+        data: *mut c_void,
+    ) -> SkFourByteTag {
+        unsafe { &*(data as *const I) }
+            .current_script()
+            .into_native()
+    }
+
+    extern "C" fn current_language<I: run_iterator::LanguageRunIterator>(
+        data: *mut c_void,
+    ) -> *const raw::c_char {
+        unsafe { &*(data as *const I) }.current_language().as_ptr()
+    }
+}
+
 pub type FontRunIterator = RefHandle<SkShaper_FontRunIterator>;
 
 impl NativeBase<SkShaper_RunIterator> for SkShaper_FontRunIterator {}
@@ -491,13 +678,17 @@ impl Shaper {
     pub fn shape_with_iterators<'a, 'b: 'a>(
         &self,
         utf8: &str,
-        font_run_iterator: &mut FontRunIterator,
-        bidi_run_iterator: &mut BiDiRunIterator,
-        script_run_iterator: &mut ScriptRunIterator,
-        language_run_iterator: &mut LanguageRunIterator,
+        font_run_iterator: &'b mut impl AsRunIterator<'a, FontRunIterator>,
+        bidi_run_iterator: &'b mut impl AsRunIterator<'a, BiDiRunIterator>,
+        script_run_iterator: &'b mut impl AsRunIterator<'a, ScriptRunIterator>,
+        language_run_iterator: &'b mut impl AsRunIterator<'a, LanguageRunIterator>,
         width: scalar,
         run_handler: &'b mut impl AsRunHandler<'a>,
     ) {
+        let mut font_run_iterator = font_run_iterator.as_run_iterator();
+        let mut bidi_run_iterator = bidi_run_iterator.as_run_iterator();
+        let mut script_run_iterator = script_run_iterator.as_run_iterator();
+        let mut language_run_iterator = language_run_iterator.as_run_iterator();
         let mut run_handler = run_handler.as_run_handler();
 
         let bytes = utf8.as_bytes();
@@ -520,14 +711,18 @@ impl Shaper {
     pub fn shape_with_iterators_and_features<'a, 'b: 'a>(
         &self,
         utf8: &str,
-        font_run_iterator: &mut FontRunIterator,
-        bidi_run_iterator: &mut BiDiRunIterator,
-        script_run_iterator: &mut ScriptRunIterator,
-        language_run_iterator: &mut LanguageRunIterator,
+        font_run_iterator: &'b mut impl AsRunIterator<'a, FontRunIterator>,
+        bidi_run_iterator: &'b mut impl AsRunIterator<'a, BiDiRunIterator>,
+        script_run_iterator: &'b mut impl AsRunIterator<'a, ScriptRunIterator>,
+        language_run_iterator: &'b mut impl AsRunIterator<'a, LanguageRunIterator>,
         features: &[Feature],
         width: scalar,
         run_handler: &'b mut impl AsRunHandler<'a>,
     ) {
+        let mut font_run_iterator = font_run_iterator.as_run_iterator();
+        let mut bidi_run_iterator = bidi_run_iterator.as_run_iterator();
+        let mut script_run_iterator = script_run_iterator.as_run_iterator();
+        let mut language_run_iterator = language_run_iterator.as_run_iterator();
         let mut run_handler = run_handler.as_run_handler();
 
         let bytes = utf8.as_bytes();
@@ -752,4 +947,136 @@ pub mod icu {
         let bounds = blob.bounds();
         assert!(bounds.width() > 0.0 && bounds.height() > 0.0);
     }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_shape_with_rust_font_run_iterator() {
+        use crate::shaper::{run_iterator, RunIterator};
+        use crate::{Font, Shaper};
+
+        struct SingleFontRunIterator {
+            font: Font,
+            end: usize,
+            consumed: bool,
+        }
+
+        impl RunIterator for SingleFontRunIterator {
+            fn consume(&mut self) {
+                self.consumed = true;
+            }
+
+            fn end_of_current_run(&self) -> usize {
+                if self.consumed {
+                    self.end
+                } else {
+                    0
+                }
+            }
+
+            fn at_end(&self) -> bool {
+                self.consumed
+            }
+        }
+
+        impl run_iterator::FontRunIterator for SingleFontRunIterator {
+            fn current_font(&self) -> &Font {
+                &self.font
+            }
+        }
+
+        skia_bindings::icu::init();
+        let text = "Hello";
+        let mut font_run_iterator = SingleFontRunIterator {
+            font: Font::default(),
+            end: text.len(),
+            consumed: false,
+        };
+        let mut handler =
+            crate::shaper::TextBlobBuilderRunHandler::new(text, crate::Point::default());
+
+        Shaper::new(None).shape_with_iterators(
+            text,
+            &mut font_run_iterator,
+            &mut Shaper::new_trivial_bidi_run_iterator(0, text.len()),
+            &mut Shaper::new_trivial_script_run_iterator(0, text.len()),
+            &mut Shaper::new_trivial_language_run_iterator("en"),
+            10000.0,
+            &mut handler,
+        );
+
+        assert!(font_run_iterator.consumed);
+        let bounds = handler.make_blob().unwrap().bounds();
+        assert!(bounds.width() > 0.0 && bounds.height() > 0.0);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_shape_with_rust_bidi_and_script_run_iterators() {
+        use crate::shaper::{run_iterator, RunIterator};
+        use crate::{Font, FourByteTag, Shaper};
+
+        struct SingleRunIterator {
+            end: usize,
+            consumed: usize,
+        }
+
+        impl RunIterator for SingleRunIterator {
+            fn consume(&mut self) {
+                self.consumed += 1;
+            }
+
+            fn end_of_current_run(&self) -> usize {
+                if self.consumed > 0 {
+                    self.end
+                } else {
+                    0
+                }
+            }
+
+            fn at_end(&self) -> bool {
+                self.consumed > 0
+            }
+        }
+
+        impl run_iterator::BiDiRunIterator for SingleRunIterator {
+            fn current_level(&self) -> u8 {
+                1
+            }
+        }
+
+        impl run_iterator::ScriptRunIterator for SingleRunIterator {
+            fn current_script(&self) -> FourByteTag {
+                FourByteTag::from_chars('A', 'r', 'a', 'b')
+            }
+        }
+
+        skia_bindings::icu::init();
+        let text = "العربية";
+        let mut bidi_run_iterator = SingleRunIterator {
+            end: text.len(),
+            consumed: 0,
+        };
+        let mut script_run_iterator = SingleRunIterator {
+            end: text.len(),
+            consumed: 0,
+        };
+        let mut handler =
+            crate::shaper::TextBlobBuilderRunHandler::new(text, crate::Point::default());
+
+        let font = Font::default();
+        Shaper::new(None).shape_with_iterators(
+            text,
+            &mut Shaper::new_trivial_font_run_iterator(&font, text.len()),
+            &mut bidi_run_iterator,
+            &mut script_run_iterator,
+            &mut Shaper::new_trivial_language_run_iterator("ar"),
+            10000.0,
+            &mut handler,
+        );
+
+        assert_eq!(bidi_run_iterator.consumed, 1);
+        assert_eq!(script_run_iterator.consumed, 1);
+        let bounds = handler.make_blob().unwrap().bounds();
+        assert!(bounds.width() > 0.0 && bounds.height() > 0.0);
+    }
 }
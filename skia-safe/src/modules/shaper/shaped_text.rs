@@ -0,0 +1,284 @@
+use super::run_handler::{Buffer, RunHandler, RunInfo};
+use crate::{scalar, Font, GlyphId, Point, Shaper, TextBlob, TextBlobBuilder, Vector};
+use std::ops::Range;
+
+/// The result of [`Shaper::shape_text()`], the shaped glyph runs of a text, grouped into lines.
+#[derive(Clone, Default, Debug)]
+pub struct ShapedText {
+    runs: Vec<ShapedRun>,
+    lines: Vec<ShapedLine>,
+}
+
+/// A run of glyphs that share the same font and bidi level.
+#[derive(Clone, Debug)]
+pub struct ShapedRun {
+    pub font: Font,
+    pub bidi_level: u8,
+    pub glyphs: Vec<GlyphId>,
+    /// The positions of the glyphs, including the offset passed to [`Shaper::shape_text()`].
+    pub positions: Vec<Point>,
+    /// For each glyph, the UTF-8 offset of the cluster in the shaped text it belongs to.
+    pub clusters: Vec<u32>,
+    /// The position of the run's origin on its line's baseline.
+    pub origin: Point,
+    pub advance: Vector,
+    pub utf8_range: Range<usize>,
+}
+
+/// A line of [`ShapedRun`]s.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ShapedLine {
+    /// The indices of the line's runs in [`ShapedText::runs()`], in visual order.
+    pub runs: Range<usize>,
+    pub top: scalar,
+    pub baseline: scalar,
+    pub bottom: scalar,
+}
+
+impl Shaper {
+    /// Shapes `utf8` and returns the glyph runs.
+    ///
+    /// The first line's top is placed at `offset`.
+    pub fn shape_text(
+        &self,
+        utf8: &str,
+        font: &Font,
+        left_to_right: bool,
+        width: scalar,
+        offset: impl Into<Point>,
+    ) -> ShapedText {
+        let mut handler = ShapedTextRunHandler::new(offset.into());
+        self.shape(utf8, font, left_to_right, width, &mut handler);
+        handler.text
+    }
+}
+
+impl ShapedText {
+    pub fn runs(&self) -> &[ShapedRun] {
+        &self.runs
+    }
+
+    pub fn lines(&self) -> &[ShapedLine] {
+        &self.lines
+    }
+
+    pub fn line_runs(&self, line: usize) -> &[ShapedRun] {
+        &self.runs[self.lines[line].runs.clone()]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    /// Creates a [`TextBlob`] that contains all runs. Returns `None` if there are no glyphs.
+    pub fn to_text_blob(&self) -> Option<TextBlob> {
+        let mut builder = TextBlobBuilder::new();
+        for run in &self.runs {
+            run.add_to(&mut builder);
+        }
+        builder.make()
+    }
+
+    /// Returns the UTF-8 offset of the cluster at `point`.
+    ///
+    /// Points above or below the text hit the first or last line, points to the left or right of a
+    /// line hit the line's leftmost or rightmost glyph. Returns `None` if there are no glyphs or
+    /// if a coordinate of `point` is NaN.
+    pub fn cluster_at(&self, point: impl Into<Point>) -> Option<usize> {
+        let point = point.into();
+        if point.x.is_nan() || point.y.is_nan() {
+            return None;
+        }
+        let line = self
+            .lines
+            .iter()
+            .find(|line| point.y < line.bottom)
+            .or_else(|| self.lines.last())?;
+
+        self.runs[line.runs.clone()]
+            .iter()
+            .flat_map(|run| run.glyph_extents().map(move |(i, x)| (run.clusters[i], x)))
+            // Distances between finite glyph extents and a non-NaN coordinate are never NaN.
+            .min_by(|(_, a), (_, b)| {
+                distance(a, point.x)
+                    .partial_cmp(&distance(b, point.x))
+                    .unwrap()
+            })
+            .map(|(cluster, _)| cluster as usize)
+    }
+
+    /// Returns the baseline position of the cluster that contains the UTF-8 offset `utf8_offset`,
+    /// or `None` if `utf8_offset` is not covered by any run.
+    ///
+    /// For right-to-left runs, this is the right edge of the cluster.
+    pub fn cluster_position(&self, utf8_offset: usize) -> Option<Point> {
+        let run = self
+            .runs
+            .iter()
+            .find(|run| run.utf8_range.contains(&utf8_offset))?;
+        let cluster = run
+            .clusters
+            .iter()
+            .copied()
+            .filter(|c| *c as usize <= utf8_offset)
+            .max()?;
+        let (_, x) = run
+            .glyph_extents()
+            .find(|(i, _)| run.clusters[*i] == cluster)?;
+        let x = if run.is_left_to_right() {
+            x.start
+        } else {
+            x.end
+        };
+        Some(Point::new(x, run.origin.y))
+    }
+}
+
+impl ShapedRun {
+    pub fn is_left_to_right(&self) -> bool {
+        self.bidi_level & 1 == 0
+    }
+
+    /// Creates a [`TextBlob`] that contains this run. Returns `None` if the run has no glyphs.
+    pub fn to_text_blob(&self) -> Option<TextBlob> {
+        let mut builder = TextBlobBuilder::new();
+        self.add_to(&mut builder);
+        builder.make()
+    }
+
+    fn add_to(&self, builder: &mut TextBlobBuilder) {
+        let (glyphs, positions) = builder.alloc_run_pos(&self.font, self.glyphs.len(), None);
+        glyphs.copy_from_slice(&self.glyphs);
+        positions.copy_from_slice(&self.positions);
+    }
+
+    /// The horizontal extents of the glyphs from left to right.
+    ///
+    /// A glyph extends to the position of the next glyph, the last glyph to the end of the run.
+    fn glyph_extents(&self) -> impl Iterator<Item = (usize, Range<scalar>)> + '_ {
+        let end = self.origin.x + self.advance.x;
+        self.positions.iter().enumerate().map(move |(i, p)| {
+            let right = self.positions.get(i + 1).map(|p| p.x).unwrap_or(end);
+            (i, p.x..right)
+        })
+    }
+}
+
+fn distance(range: &Range<scalar>, x: scalar) -> scalar {
+    if x < range.start {
+        range.start - x
+    } else if x >= range.end {
+        x - range.end
+    } else {
+        0.0
+    }
+}
+
+/// A [`RunHandler`] that places the runs and lines like [`super::TextBlobBuilderRunHandler`] does.
+struct ShapedTextRunHandler {
+    text: ShapedText,
+    offset: Point,
+    current_position: Point,
+    max_run_ascent: scalar,
+    max_run_descent: scalar,
+    max_run_leading: scalar,
+    line_begin: usize,
+}
+
+impl ShapedTextRunHandler {
+    fn new(offset: Point) -> Self {
+        ShapedTextRunHandler {
+            text: ShapedText::default(),
+            offset,
+            current_position: offset,
+            max_run_ascent: 0.0,
+            max_run_descent: 0.0,
+            max_run_leading: 0.0,
+            line_begin: 0,
+        }
+    }
+}
+
+impl RunHandler for ShapedTextRunHandler {
+    fn begin_line(&mut self) {
+        self.current_position = self.offset;
+        self.max_run_ascent = 0.0;
+        self.max_run_descent = 0.0;
+        self.max_run_leading = 0.0;
+        self.line_begin = self.text.runs.len();
+    }
+
+    fn run_info(&mut self, info: &RunInfo) {
+        let (_, metrics) = info.font.metrics();
+        self.max_run_ascent = self.max_run_ascent.min(metrics.ascent);
+        self.max_run_descent = self.max_run_descent.max(metrics.descent);
+        self.max_run_leading = self.max_run_leading.max(metrics.leading);
+    }
+
+    fn commit_run_info(&mut self) {
+        self.current_position.y -= self.max_run_ascent;
+    }
+
+    fn run_buffer(&mut self, info: &RunInfo) -> Buffer {
+        let glyph_count = info.glyph_count;
+        self.text.runs.push(ShapedRun {
+            font: info.font.clone(),
+            bidi_level: info.bidi_level,
+            glyphs: vec![0; glyph_count],
+            positions: vec![Point::default(); glyph_count],
+            clusters: vec![0; glyph_count],
+            origin: self.current_position,
+            advance: info.advance,
+            utf8_range: info.utf8_range.clone(),
+        });
+        let run = self.text.runs.last_mut().unwrap();
+        Buffer {
+            glyphs: &mut run.glyphs,
+            positions: &mut run.positions,
+            offsets: None,
+            clusters: Some(&mut run.clusters),
+            point: self.current_position,
+        }
+    }
+
+    fn commit_run_buffer(&mut self, info: &RunInfo) {
+        self.current_position += info.advance;
+    }
+
+    fn commit_line(&mut self) {
+        let baseline = self.offset.y - self.max_run_ascent;
+        self.text.lines.push(ShapedLine {
+            runs: self.line_begin..self.text.runs.len(),
+            top: self.offset.y,
+            baseline,
+            bottom: baseline + self.max_run_descent + self.max_run_leading,
+        });
+        self.offset.y = self.text.lines.last().unwrap().bottom;
+    }
+}
+
+#[test]
+#[serial_test::serial]
+#[allow(clippy::float_cmp)]
+fn shape_text_and_hit_test_clusters() {
+    skia_bindings::icu::init();
+    let text = "Hello World";
+    let shaped = Shaper::new(None).shape_text(text, &Font::default(), true, 10000.0, (10, 20));
+
+    assert_eq!(shaped.lines().len(), 1);
+    let run = &shaped.runs()[0];
+    assert_eq!(run.utf8_range, 0..text.len());
+    assert_eq!(run.clusters.len(), run.glyphs.len());
+    assert_eq!(run.positions[0].x, 10.0);
+    assert_eq!(shaped.lines()[0].top, 20.0);
+
+    assert_eq!(shaped.cluster_at((0, 0)), Some(0));
+    assert_eq!(shaped.cluster_at((10000, 1000)), Some(text.len() - 1));
+    let w = shaped.cluster_position(6).unwrap();
+    assert_eq!(shaped.cluster_at((w.x + 0.1, w.y)), Some(6));
+    assert_eq!(shaped.cluster_at((scalar::NAN, 0.0)), None);
+    assert_eq!(shaped.cluster_at((0.0, scalar::NAN)), None);
+
+    let blob = shaped.to_text_blob().unwrap();
+    assert!(blob.bounds().width() > 0.0);
+}
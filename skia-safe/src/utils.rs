@@ -7,9 +7,18 @@ pub use custom_typeface::*;
 pub mod interpolator;
 pub use interpolator::Interpolator;
 
+mod n_way_canvas;
+pub use n_way_canvas::*;
+
 mod null_canvas;
 pub use null_canvas::*;
 
+mod overdraw_canvas;
+pub use overdraw_canvas::*;
+
+mod paint_filter_canvas;
+pub use paint_filter_canvas::*;
+
 pub mod parse_path;
 pub mod shadow_utils;
 pub mod text_utils;
@@ -1,6 +1,10 @@
-use crate::{prelude::*, FontMetrics, FontStyle, GlyphId, Image, Paint, Path, Picture, Typeface};
-use skia_bindings::{self as sb, SkCustomTypefaceBuilder};
-use std::fmt;
+use crate::{
+    font_style::{Slant, Weight, Width},
+    prelude::*,
+    Data, Font, FontMetrics, FontStyle, GlyphId, Path, Typeface,
+};
+use skia_bindings::{self as sb, SkCustomTypefaceBuilder, SkFontMetrics};
+use std::{convert::TryInto, fmt};
 
 pub type CustomTypefaceBuilder = Handle<SkCustomTypefaceBuilder>;
 unsafe impl Send for CustomTypefaceBuilder {}
@@ -29,21 +33,10 @@ impl CustomTypefaceBuilder {
         advance: f32,
         typeface_glyph: impl Into<TypefaceGlyph<'a>>,
     ) -> &mut Self {
-        unsafe {
-            match typeface_glyph.into() {
-                TypefaceGlyph::Path(path) => {
-                    self.native_mut().setGlyph(glyph_id, advance, path.native())
-                }
-                TypefaceGlyph::PathAndPaint(_path, _paint) => {
-                    unimplemented!("TypefaceGlyph::PathAndPaint is not supported yet, Skia implementation is missing (last checked: m86)")
-                }
-                TypefaceGlyph::Image { .. } => {
-                    unimplemented!("TypefaceGlyph::PathAndPaint is not supported yet, Skia implementation is missing (last checked: m86)")
-                }
-                TypefaceGlyph::Picture(_picture) => {
-                    unimplemented!("TypefaceGlyph::Picture is not supported yet, Skia implementation is missing (last checked: m86)")
-                }
-            }
+        match typeface_glyph.into() {
+            TypefaceGlyph::Path(path) => unsafe {
+                self.native_mut().setGlyph(glyph_id, advance, path.native())
+            },
         }
         self
     }
@@ -68,14 +61,209 @@ impl CustomTypefaceBuilder {
     pub fn detach(&mut self) -> Option<Typeface> {
         Typeface::from_ptr(unsafe { sb::C_SkCustomTypefaceBuilder_detach(self.native_mut()) })
     }
+
+    /// Serializes a typeface that was built with a [`CustomTypefaceBuilder`], returns `None` for
+    /// all other typefaces.
+    ///
+    /// Skia can not serialize custom typefaces (last checked: m91), the default serialization only
+    /// writes a reference to them that can not be resolved when deserialized. To embed custom
+    /// typefaces in a serialized [`Picture`], pass this function to
+    /// [`crate::SerialProcs::typeface()`] and [`Self::deserialize_typeface()`] to
+    /// [`crate::DeserialProcs::typeface()`].
+    ///
+    /// Only the outlines of the glyphs are preserved, which covers all glyphs a custom typeface
+    /// can contain in m91.
+    pub fn serialize_typeface(typeface: &Typeface) -> Option<Data> {
+        // Custom typefaces do not provide any font tables.
+        if typeface.count_tables() != 0 || typeface.count_glyphs() == 0 {
+            return None;
+        }
+
+        // Custom typefaces define their glyphs in a coordinate system where 1.0 is the text size.
+        let font = Font::from_typeface(typeface.clone(), 1.0);
+        let glyph_count: GlyphId = typeface.count_glyphs().try_into().unwrap();
+        let glyphs: Vec<GlyphId> = (0..glyph_count).collect();
+        let mut advances = vec![0.0; glyphs.len()];
+        font.get_widths(&glyphs, &mut advances);
+
+        let mut writer = serialization::Writer::default();
+        writer.write_bytes(serialization::MAGIC);
+        writer.write_u32(serialization::VERSION);
+
+        let style = typeface.font_style();
+        writer.write_i32(*style.weight());
+        writer.write_i32(*style.width());
+        writer.write_i32(style.slant() as i32);
+
+        let (_, metrics) = font.metrics();
+        let m = metrics.native();
+        writer.write_u32(m.fFlags);
+        for v in &[
+            m.fTop,
+            m.fAscent,
+            m.fDescent,
+            m.fBottom,
+            m.fLeading,
+            m.fAvgCharWidth,
+            m.fMaxCharWidth,
+            m.fXMin,
+            m.fXMax,
+            m.fXHeight,
+            m.fCapHeight,
+            m.fUnderlineThickness,
+            m.fUnderlinePosition,
+            m.fStrikeoutThickness,
+            m.fStrikeoutPosition,
+        ] {
+            writer.write_f32(*v);
+        }
+
+        writer.write_u32(glyphs.len() as u32);
+        for (glyph, advance) in glyphs.iter().zip(advances) {
+            writer.write_f32(advance);
+            let path = font.get_path(*glyph).unwrap_or_default().serialize();
+            writer.write_u32(path.len() as u32);
+            writer.write_bytes(path.as_bytes());
+        }
+
+        Some(Data::new_copy(&writer.0))
+    }
+
+    /// Creates a typeface from data returned by [`Self::serialize_typeface()`].
+    ///
+    /// Returns `None` if the data is invalid, so that Skia falls back to its default
+    /// deserialization when this is used in [`crate::DeserialProcs::typeface()`].
+    pub fn deserialize_typeface(data: &[u8]) -> Option<Typeface> {
+        let mut reader = serialization::Reader(data);
+        if reader.read_bytes(serialization::MAGIC.len())? != serialization::MAGIC
+            || reader.read_u32()? != serialization::VERSION
+        {
+            return None;
+        }
+
+        let weight = Weight::from(reader.read_i32()?);
+        let width = Width::from(reader.read_i32()?);
+        let slant = match reader.read_i32()? {
+            0 => Slant::Upright,
+            1 => Slant::Italic,
+            2 => Slant::Oblique,
+            _ => return None,
+        };
+
+        let mut metrics = FontMetrics::default();
+        let m: &mut SkFontMetrics = metrics.native_mut();
+        m.fFlags = reader.read_u32()?;
+        for v in &mut [
+            &mut m.fTop,
+            &mut m.fAscent,
+            &mut m.fDescent,
+            &mut m.fBottom,
+            &mut m.fLeading,
+            &mut m.fAvgCharWidth,
+            &mut m.fMaxCharWidth,
+            &mut m.fXMin,
+            &mut m.fXMax,
+            &mut m.fXHeight,
+            &mut m.fCapHeight,
+            &mut m.fUnderlineThickness,
+            &mut m.fUnderlinePosition,
+            &mut m.fStrikeoutThickness,
+            &mut m.fStrikeoutPosition,
+        ] {
+            **v = reader.read_f32()?;
+        }
+
+        let mut builder = CustomTypefaceBuilder::new();
+        builder
+            .set_font_style(FontStyle::new(weight, width, slant))
+            .set_metrics(&metrics, None);
+
+        let glyph_count = reader.read_u32()?;
+        if glyph_count > GlyphId::MAX as u32 + 1 {
+            return None;
+        }
+        for glyph in 0..glyph_count {
+            let advance = reader.read_f32()?;
+            let path_len = reader.read_u32()? as usize;
+            let path = Path::deserialize(&Data::new_copy(reader.read_bytes(path_len)?))?;
+            builder.set_glyph(glyph as GlyphId, advance, &path);
+        }
+
+        if !reader.0.is_empty() {
+            return None;
+        }
+
+        builder.detach()
+    }
+}
+
+mod serialization {
+    use std::convert::TryInto;
+
+    pub const MAGIC: &[u8] = b"skCT";
+    pub const VERSION: u32 = 1;
+
+    /// Writes little endian values.
+    #[derive(Default)]
+    pub struct Writer(pub Vec<u8>);
+
+    impl Writer {
+        pub fn write_bytes(&mut self, bytes: &[u8]) {
+            self.0.extend_from_slice(bytes)
+        }
+
+        pub fn write_u32(&mut self, v: u32) {
+            self.write_bytes(&v.to_le_bytes())
+        }
+
+        pub fn write_i32(&mut self, v: i32) {
+            self.write_bytes(&v.to_le_bytes())
+        }
+
+        pub fn write_f32(&mut self, v: f32) {
+            self.write_bytes(&v.to_le_bytes())
+        }
+    }
+
+    /// Reads little endian values, returns `None` if there is not enough data left.
+    pub struct Reader<'a>(pub &'a [u8]);
+
+    impl<'a> Reader<'a> {
+        pub fn read_bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+            if len > self.0.len() {
+                return None;
+            }
+            let (bytes, rest) = self.0.split_at(len);
+            self.0 = rest;
+            Some(bytes)
+        }
+
+        fn read_4(&mut self) -> Option<[u8; 4]> {
+            self.read_bytes(4).map(|b| b.try_into().unwrap())
+        }
+
+        pub fn read_u32(&mut self) -> Option<u32> {
+            self.read_4().map(u32::from_le_bytes)
+        }
+
+        pub fn read_i32(&mut self) -> Option<i32> {
+            self.read_4().map(i32::from_le_bytes)
+        }
+
+        pub fn read_f32(&mut self) -> Option<f32> {
+            self.read_4().map(f32::from_le_bytes)
+        }
+    }
 }
 
+/// The glyph definitions accepted by [`CustomTypefaceBuilder::set_glyph()`].
+///
+/// Skia supports only path glyphs in custom typefaces (last checked: m91). Glyphs that are drawn
+/// with a paint, an image, a picture or a drawable, and glyph bounds that differ from the path's
+/// bounds are not available.
 #[derive(Debug)]
 pub enum TypefaceGlyph<'a> {
     Path(&'a Path),
-    PathAndPaint(&'a Path, &'a Paint),
-    Image { image: Image, scale: f32 },
-    Picture(Picture),
 }
 
 impl<'a> From<&'a Path> for TypefaceGlyph<'a> {
@@ -84,33 +272,86 @@ impl<'a> From<&'a Path> for TypefaceGlyph<'a> {
     }
 }
 
-impl<'a> From<(&'a Path, &'a Paint)> for TypefaceGlyph<'a> {
-    fn from((path, paint): (&'a Path, &'a Paint)) -> Self {
-        Self::PathAndPaint(path, paint)
-    }
+#[test]
+fn build_custom_typeface() {
+    let mut builder = CustomTypefaceBuilder::new();
+    let path = Path::new();
+    builder.set_glyph(10u16, 0.0, &path);
+    builder.set_glyph(11u16, 0.0, &path);
+    let typeface = builder.detach().unwrap();
+    assert_eq!(typeface.native().ref_counted_base()._ref_cnt(), 1);
 }
 
-impl From<(Image, f32)> for TypefaceGlyph<'_> {
-    fn from((image, scale): (Image, f32)) -> Self {
-        Self::Image { image, scale }
-    }
+#[test]
+#[allow(clippy::float_cmp)]
+fn serialize_and_deserialize_custom_typeface() {
+    use crate::Rect;
+
+    let mut builder = CustomTypefaceBuilder::new();
+    let path = Path::rect(Rect::new(0.0, -0.5, 0.5, 0.0), None);
+    builder.set_glyph(0u16, 0.5, &Path::new());
+    builder.set_glyph(1u16, 0.75, &path);
+    builder.set_font_style(FontStyle::bold());
+    let typeface = builder.detach().unwrap();
+
+    let data = CustomTypefaceBuilder::serialize_typeface(&typeface).unwrap();
+    let deserialized = CustomTypefaceBuilder::deserialize_typeface(data.as_bytes()).unwrap();
+    assert_eq!(deserialized.count_glyphs(), 2);
+    assert!(deserialized.font_style() == FontStyle::bold());
+
+    let font = Font::from_typeface(deserialized, 10.0);
+    let mut advances = [0.0; 2];
+    font.get_widths(&[0, 1], &mut advances);
+    assert_eq!(advances, [5.0, 7.5]);
+    assert_eq!(
+        font.get_path(1).unwrap().bounds(),
+        &Rect::new(0.0, -5.0, 5.0, 0.0)
+    );
+
+    assert!(CustomTypefaceBuilder::deserialize_typeface(&data.as_bytes()[..20]).is_none());
 }
 
-impl From<(&Image, f32)> for TypefaceGlyph<'_> {
-    fn from((image, scale): (&Image, f32)) -> Self {
-        Self::Image {
-            image: image.clone(),
-            scale,
-        }
-    }
+#[test]
+fn serialize_only_custom_typefaces() {
+    assert!(CustomTypefaceBuilder::serialize_typeface(&Typeface::default()).is_none());
 }
 
 #[test]
-fn build_custom_typeface() {
+fn custom_typeface_survives_picture_round_trip() {
+    use crate::{
+        Canvas, DeserialProcs, Paint, Picture, PictureRecorder, Rect, SerialProcs, TextBlob,
+        TextEncoding,
+    };
+
     let mut builder = CustomTypefaceBuilder::new();
-    let path = Path::new();
-    builder.set_glyph(10u16, 0.0, &path);
-    builder.set_glyph(11u16, 0.0, &path);
+    builder.set_glyph(0u16, 0.5, &Path::new());
+    builder.set_glyph(1u16, 0.5, &Path::rect(Rect::new(0.0, -0.5, 0.5, 0.0), None));
     let typeface = builder.detach().unwrap();
-    assert_eq!(typeface.native().ref_counted_base()._ref_cnt(), 1);
+
+    let font = Font::from_typeface(typeface, 8.0);
+    let blob = TextBlob::from_text(&1u16.to_ne_bytes(), TextEncoding::GlyphId, &font).unwrap();
+    let mut recorder = PictureRecorder::new();
+    let canvas = recorder.begin_recording(Rect::from_wh(8.0, 8.0), None);
+    canvas.draw_text_blob(&blob, (0, 8), &Paint::default());
+    let picture = recorder.finish_recording_as_picture(None).unwrap();
+
+    let data = picture.serialize_with_procs(
+        &mut SerialProcs::default().typeface(CustomTypefaceBuilder::serialize_typeface),
+    );
+    let deserialized = Picture::from_bytes_with_procs(
+        data.as_bytes(),
+        &mut DeserialProcs::default().typeface(CustomTypefaceBuilder::deserialize_typeface),
+    )
+    .unwrap();
+
+    let mut pixels: [u32; 64] = [0; 64];
+    {
+        let mut canvas = Canvas::from_raster_direct_n32((8, 8), &mut pixels, None).unwrap();
+        deserialized.playback(&mut canvas);
+    }
+    // The glyph covers the lower left 4x4 pixels.
+    assert_ne!(pixels[7 * 8], 0);
+    assert_ne!(pixels[4 * 8 + 3], 0);
+    assert_eq!(pixels[3 * 8], 0);
+    assert_eq!(pixels[7 * 8 + 4], 0);
 }
@@ -0,0 +1,108 @@
+use crate::{prelude::*, Canvas, ISize, OwnedCanvas};
+use skia_bindings as sb;
+use std::{
+    fmt,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+/// A [`Canvas`] that forwards its drawing commands to all the canvases added to it.
+///
+/// The size of the canvas limits its clip, the added canvases keep their own matrices and clips,
+/// to which the ones of this canvas are applied.
+pub struct NWayCanvas<'a> {
+    canvas: OwnedCanvas<'a>,
+    canvases: PhantomData<&'a mut Canvas>,
+}
+
+impl Deref for NWayCanvas<'_> {
+    type Target = Canvas;
+
+    fn deref(&self) -> &Self::Target {
+        &self.canvas
+    }
+}
+
+impl DerefMut for NWayCanvas<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.canvas
+    }
+}
+
+impl fmt::Debug for NWayCanvas<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("NWayCanvas").field(&self.canvas).finish()
+    }
+}
+
+/// A canvas added to a [`NWayCanvas`], returned by [`NWayCanvas::add_canvas()`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct AddedCanvas(*mut sb::SkCanvas);
+
+impl<'a> NWayCanvas<'a> {
+    pub fn new(size: impl Into<ISize>) -> Self {
+        let size = size.into();
+        let canvas =
+            Canvas::own_from_native_ptr(unsafe { sb::C_SkNWayCanvas_new(size.width, size.height) })
+                .unwrap();
+        Self {
+            canvas,
+            canvases: PhantomData,
+        }
+    }
+
+    /// Adds `canvas`, which stays borrowed until the `NWayCanvas` is dropped.
+    ///
+    /// Returns the handle [`Self::remove_canvas()`] takes.
+    pub fn add_canvas(&mut self, canvas: &'a mut Canvas) -> AddedCanvas {
+        unsafe { sb::C_SkNWayCanvas_addCanvas(self.canvas.native_mut(), canvas.native_mut()) }
+        AddedCanvas(canvas.native_mut())
+    }
+
+    /// Stops forwarding drawing commands to the canvas `added` was returned for.
+    pub fn remove_canvas(&mut self, added: AddedCanvas) -> &mut Self {
+        unsafe { sb::C_SkNWayCanvas_removeCanvas(self.canvas.native_mut(), added.0) }
+        self
+    }
+
+    pub fn remove_all(&mut self) -> &mut Self {
+        unsafe { sb::C_SkNWayCanvas_removeAll(self.canvas.native_mut()) }
+        self
+    }
+}
+
+#[test]
+fn draws_into_all_canvases() {
+    use crate::{core::RectCounter, Paint, Rect};
+
+    let mut first = Canvas::with_backend((100, 100), RectCounter::default());
+    let mut second = Canvas::with_backend((100, 100), RectCounter::default());
+    {
+        let mut canvas = NWayCanvas::new((100, 100));
+        canvas.add_canvas(&mut first);
+        canvas.add_canvas(&mut second);
+        canvas.draw_rect(Rect::from_wh(10.0, 10.0), &Paint::default());
+        canvas.remove_all();
+        canvas.draw_rect(Rect::from_wh(10.0, 10.0), &Paint::default());
+    }
+    assert_eq!(first.backend().0, 1);
+    assert_eq!(second.backend().0, 1);
+}
+
+#[test]
+fn removed_canvas_receives_no_commands() {
+    use crate::{core::RectCounter, Paint, Rect};
+
+    let mut first = Canvas::with_backend((100, 100), RectCounter::default());
+    let mut second = Canvas::with_backend((100, 100), RectCounter::default());
+    {
+        let mut canvas = NWayCanvas::new((100, 100));
+        let added = canvas.add_canvas(&mut first);
+        canvas.add_canvas(&mut second);
+        canvas.draw_rect(Rect::from_wh(10.0, 10.0), &Paint::default());
+        canvas.remove_canvas(added);
+        canvas.draw_rect(Rect::from_wh(10.0, 10.0), &Paint::default());
+    }
+    assert_eq!(first.backend().0, 1);
+    assert_eq!(second.backend().0, 2);
+}
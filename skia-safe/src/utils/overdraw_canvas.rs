@@ -0,0 +1,65 @@
+use crate::{prelude::*, Canvas, OwnedCanvas};
+use skia_bindings as sb;
+use std::{
+    fmt,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+/// A [`Canvas`] that counts how often each pixel is drawn to by incrementing the alpha channel of
+/// another canvas, preferably one backed by an [`crate::ColorType::Alpha8`] surface.
+///
+/// Paints, colors, and shaders are ignored. The resulting overdraw counts can be turned into a
+/// heat map by drawing the alpha surface with a [`crate::ColorFilter::overdraw()`] color filter.
+pub struct OverdrawCanvas<'a> {
+    canvas: OwnedCanvas<'a>,
+    target: PhantomData<&'a mut Canvas>,
+}
+
+impl Deref for OverdrawCanvas<'_> {
+    type Target = Canvas;
+
+    fn deref(&self) -> &Self::Target {
+        &self.canvas
+    }
+}
+
+impl DerefMut for OverdrawCanvas<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.canvas
+    }
+}
+
+impl fmt::Debug for OverdrawCanvas<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("OverdrawCanvas").field(&self.canvas).finish()
+    }
+}
+
+impl<'a> OverdrawCanvas<'a> {
+    pub fn new(canvas: &'a mut Canvas) -> Self {
+        let canvas =
+            Canvas::own_from_native_ptr(unsafe { sb::C_SkOverdrawCanvas_new(canvas.native_mut()) })
+                .unwrap();
+        Self {
+            canvas,
+            target: PhantomData,
+        }
+    }
+}
+
+#[test]
+fn counts_overdraw() {
+    use crate::{ImageInfo, Paint, Rect, Surface};
+
+    let mut surface = Surface::new_raster(&ImageInfo::new_a8((20, 20)), None, None).unwrap();
+    {
+        let mut canvas = OverdrawCanvas::new(surface.canvas());
+        canvas.draw_rect(Rect::from_wh(10.0, 10.0), &Paint::default());
+        canvas.draw_rect(Rect::from_xywh(5.0, 5.0, 10.0, 10.0), &Paint::default());
+    }
+    let pixmap = surface.peek_pixels().unwrap();
+    assert_eq!(pixmap.get_color((2, 2)).a(), 1);
+    assert_eq!(pixmap.get_color((7, 7)).a(), 2);
+    assert_eq!(pixmap.get_color((17, 17)).a(), 0);
+}
@@ -0,0 +1,108 @@
+use crate::{prelude::*, Canvas, OwnedCanvas, Paint};
+use skia_bindings as sb;
+use std::{
+    ffi::c_void,
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+/// The kind of draw call a [`PaintFilterCanvas`] filters the paint of.
+pub use sb::PaintFilterDrawType;
+
+/// A [`Canvas`] that passes the paint of each drawing command through a filter before it forwards
+/// the command to another canvas.
+///
+/// The filter may change the paint, and skips the command by returning `false`. Commands that
+/// draw images without a paint are filtered with a default paint.
+pub struct PaintFilterCanvas<'a, F: FnMut(&mut Paint, PaintFilterDrawType) -> bool> {
+    // Dropped first, because the filter is referenced by the canvas.
+    canvas: OwnedCanvas<'a>,
+    filter: Box<F>,
+}
+
+impl<F: FnMut(&mut Paint, PaintFilterDrawType) -> bool> Deref for PaintFilterCanvas<'_, F> {
+    type Target = Canvas;
+
+    fn deref(&self) -> &Self::Target {
+        &self.canvas
+    }
+}
+
+impl<F: FnMut(&mut Paint, PaintFilterDrawType) -> bool> DerefMut for PaintFilterCanvas<'_, F> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.canvas
+    }
+}
+
+impl<F: FnMut(&mut Paint, PaintFilterDrawType) -> bool> fmt::Debug for PaintFilterCanvas<'_, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PaintFilterCanvas")
+            .field(&self.canvas)
+            .finish()
+    }
+}
+
+impl<'a, F: FnMut(&mut Paint, PaintFilterDrawType) -> bool> PaintFilterCanvas<'a, F> {
+    /// Creates a canvas that draws into `canvas` with the paints `filter` returns `true` for.
+    pub fn new(canvas: &'a mut Canvas, filter: F) -> Self {
+        unsafe extern "C" fn filter_paint<F: FnMut(&mut Paint, PaintFilterDrawType) -> bool>(
+            data: *mut c_void,
+            paint: *mut sb::SkPaint,
+            draw_type: PaintFilterDrawType,
+        ) -> bool {
+            (*(data as *mut F))(Paint::from_native_ref_mut(&mut *paint), draw_type)
+        }
+
+        let mut filter = Box::new(filter);
+        let param = sb::RustPaintFilterParam {
+            data: filter.as_mut() as *mut F as *mut c_void,
+            filter: Some(filter_paint::<F>),
+        };
+        let canvas = Canvas::own_from_native_ptr(unsafe {
+            sb::C_RustPaintFilterCanvas_new(canvas.native_mut(), &param)
+        })
+        .unwrap();
+        Self { canvas, filter }
+    }
+
+    pub fn filter(&self) -> &F {
+        &self.filter
+    }
+}
+
+#[test]
+fn filters_and_skips_paints() {
+    use crate::{CanvasBackend, Color, Path, Rect};
+
+    #[derive(Default)]
+    struct Recorder {
+        colors: Vec<Color>,
+        paths: usize,
+    }
+
+    impl CanvasBackend for Recorder {
+        fn on_draw_rect(&mut self, _rect: &Rect, paint: &Paint) {
+            self.colors.push(paint.color());
+        }
+
+        fn on_draw_path(&mut self, _path: &Path, _paint: &Paint) {
+            self.paths += 1;
+        }
+    }
+
+    let mut paint = Paint::default();
+    paint.set_color(Color::RED);
+
+    let mut target = Canvas::with_backend((100, 100), Recorder::default());
+    {
+        let mut canvas = PaintFilterCanvas::new(&mut target, |paint, draw_type| {
+            paint.set_color(Color::BLUE);
+            draw_type != PaintFilterDrawType::Path
+        });
+        canvas.draw_rect(Rect::from_wh(10.0, 10.0), &paint);
+        canvas.draw_path(&Path::circle((10, 10), 5.0, None), &paint);
+    }
+    assert_eq!(target.backend().colors, [Color::BLUE]);
+    assert_eq!(target.backend().paths, 0);
+    assert_eq!(paint.color(), Color::RED);
+}